@@ -81,6 +81,12 @@ fn main() -> Result<(), Box<dyn Error>> {
         Event::UserEvent(event) => {
             println!("User event: {event:?}");
         }
+        Event::FdReady { token, readiness } => {
+            println!("File descriptor {token:?} is ready: {readiness:?}");
+        }
+        Event::ConnectionLost => {
+            println!("Lost the connection to the display server");
+        }
         Event::Suspended | Event::LoopExiting | Event::MemoryWarning => (),
     })?;
 
@@ -264,6 +270,15 @@ impl Application {
             WindowEvent::Occluded(occluded) => {
                 window.set_occluded(occluded);
             }
+            WindowEvent::StateChanged(state) => {
+                println!("Window={window_id:?} changed state to {state:?}");
+            }
+            WindowEvent::DesktopChanged => {
+                println!("Window={window_id:?} moved to another desktop");
+            }
+            WindowEvent::HandleExported { handle, .. } => {
+                println!("Window={window_id:?} exported as {:?}", handle.as_raw());
+            }
             WindowEvent::CloseRequested => {
                 println!("Closing Window={window_id:?}");
                 self.windows.remove(&window_id);
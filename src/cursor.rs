@@ -1,6 +1,7 @@
 use core::fmt;
 use std::hash::Hasher;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{error::Error, hash::Hash};
 
 use cursor_icon::CursorIcon;
@@ -98,6 +99,49 @@ impl CustomCursor {
             )?,
         })
     }
+
+    /// Creates a new animated cursor from its frames, shown in order and looping.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Windows / macOS / Web:** Only the first frame is shown. On Web, see
+    ///   `CustomCursorExtWebSys::from_animation` instead.
+    /// - **iOS / Android / Orbital:** Unsupported.
+    pub fn from_frames(frames: Vec<CustomCursorFrame>) -> Result<CustomCursorSource, BadImage> {
+        if frames.is_empty() {
+            return Err(BadImage::NoFrames);
+        }
+
+        Ok(CustomCursorSource {
+            inner: PlatformCustomCursorSource::from_frames(frames),
+        })
+    }
+}
+
+/// A frame of an animated cursor, see [`CustomCursor::from_frames`].
+#[derive(Debug)]
+pub struct CustomCursorFrame {
+    pub(crate) image: CursorImage,
+    pub(crate) duration: Duration,
+}
+
+impl CustomCursorFrame {
+    /// Creates a new frame from an rgba buffer, shown for the given duration.
+    ///
+    /// See [`CustomCursor::from_rgba`] for the image.
+    pub fn from_rgba(
+        rgba: impl Into<Vec<u8>>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+        duration: Duration,
+    ) -> Result<Self, BadImage> {
+        Ok(Self {
+            image: CursorImage::from_rgba(rgba.into(), width, height, hotspot_x, hotspot_y)?,
+            duration,
+        })
+    }
 }
 
 /// Source for [`CustomCursor`].
@@ -108,7 +152,8 @@ pub struct CustomCursorSource {
     pub(crate) inner: PlatformCustomCursorSource,
 }
 
-/// An error produced when using [`CustomCursor::from_rgba`] with invalid arguments.
+/// An error produced when using [`CustomCursor::from_rgba`] or [`CustomCursor::from_frames`]
+/// with invalid arguments.
 #[derive(Debug, Clone)]
 pub enum BadImage {
     /// Produced when the image dimensions are larger than [`MAX_CURSOR_SIZE`]. This doesn't
@@ -133,6 +178,8 @@ pub enum BadImage {
         hotspot_x: u16,
         hotspot_y: u16,
     },
+    /// Produced when an animation has no frames.
+    NoFrames,
 }
 
 impl fmt::Display for BadImage {
@@ -160,6 +207,7 @@ impl fmt::Display for BadImage {
             } => write!(f,
                 "The specified hotspot ({hotspot_x:?}, {hotspot_y:?}) is outside the image bounds ({width:?}x{height:?}).",
             ),
+            BadImage::NoFrames => write!(f, "The animation has no frames."),
         }
     }
 }
@@ -183,25 +231,103 @@ impl OnlyCursorImageSource {
     ) -> Result<Self, BadImage> {
         CursorImage::from_rgba(rgba, width, height, hotspot_x, hotspot_y).map(Self)
     }
+
+    /// Only the first frame is kept.
+    pub(crate) fn from_frames(frames: Vec<CustomCursorFrame>) -> Self {
+        Self(frames.into_iter().next().unwrap().image)
+    }
+}
+
+/// Platforms export this directly as `PlatformCustomCursorSource` if they animate the cursors.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub(crate) struct AnimatedCursorImageSource {
+    /// The size the cursor was requested at, before scaling.
+    pub(crate) size: u32,
+    /// The images of the cursor at each of its nominal sizes, sorted by size.
+    pub(crate) variants: Vec<CursorVariant>,
+}
+
+#[allow(dead_code)]
+impl AnimatedCursorImageSource {
+    pub(crate) fn from_rgba(
+        rgba: Vec<u8>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+    ) -> Result<Self, BadImage> {
+        let image = CursorImage::from_rgba(rgba, width, height, hotspot_x, hotspot_y)?;
+        Ok(Self::from_frames(vec![CustomCursorFrame {
+            image,
+            duration: Duration::ZERO,
+        }]))
+    }
+
+    pub(crate) fn from_frames(frames: Vec<CustomCursorFrame>) -> Self {
+        let size = frames
+            .iter()
+            .map(|frame| frame.image.width.max(frame.image.height) as u32)
+            .max()
+            .unwrap_or_default();
+        Self {
+            size,
+            variants: vec![CursorVariant {
+                nominal_size: size,
+                frames,
+            }],
+        }
+    }
+
+    /// The index of the variant to show at the given scale factor.
+    pub(crate) fn variant_index(&self, scale_factor: f64) -> usize {
+        best_variant(
+            self.size,
+            self.variants.iter().map(|variant| variant.nominal_size),
+            scale_factor,
+        )
+    }
+}
+
+/// The images of a cursor at one nominal size.
+#[derive(Debug)]
+pub(crate) struct CursorVariant {
+    pub(crate) nominal_size: u32,
+    pub(crate) frames: Vec<CustomCursorFrame>,
 }
 
-/// Platforms export this directly as `PlatformCustomCursor` if they don't implement caching.
+/// Pick the smallest of the sorted nominal sizes fitting the requested size once scaled, or the
+/// largest one when none does.
+pub(crate) fn best_variant(
+    size: u32,
+    mut nominal_sizes: impl ExactSizeIterator<Item = u32>,
+    scale_factor: f64,
+) -> usize {
+    let target = (size as f64 * scale_factor).round() as u32;
+    let count = nominal_sizes.len();
+    nominal_sizes
+        .position(|nominal_size| nominal_size >= target)
+        .unwrap_or(count.saturating_sub(1))
+}
+
+/// Platforms export this directly as `PlatformCustomCursor` if they animate the cursors but don't
+/// implement caching.
 #[derive(Debug, Clone)]
-pub(crate) struct OnlyCursorImage(pub(crate) Arc<CursorImage>);
+pub(crate) struct AnimatedCursorImage(pub(crate) Arc<AnimatedCursorImageSource>);
 
-impl Hash for OnlyCursorImage {
+impl Hash for AnimatedCursorImage {
     fn hash<H: Hasher>(&self, state: &mut H) {
         Arc::as_ptr(&self.0).hash(state);
     }
 }
 
-impl PartialEq for OnlyCursorImage {
+impl PartialEq for AnimatedCursorImage {
     fn eq(&self, other: &Self) -> bool {
         Arc::ptr_eq(&self.0, &other.0)
     }
 }
 
-impl Eq for OnlyCursorImage {}
+impl Eq for AnimatedCursorImage {}
 
 #[derive(Debug)]
 #[allow(dead_code)]
@@ -277,4 +403,30 @@ impl NoCustomCursor {
         CursorImage::from_rgba(rgba, width, height, hotspot_x, hotspot_y)?;
         Ok(Self)
     }
+
+    pub(crate) fn from_frames(_frames: Vec<CustomCursorFrame>) -> Self {
+        Self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_variant_fits_scaled_size() {
+        let sizes = [16, 24, 32, 48];
+        assert_eq!(best_variant(24, sizes.into_iter(), 1.0), 1);
+        assert_eq!(best_variant(20, sizes.into_iter(), 1.0), 1);
+        assert_eq!(best_variant(24, sizes.into_iter(), 2.0), 3);
+        assert_eq!(best_variant(24, sizes.into_iter(), 1.25), 2);
+        assert_eq!(best_variant(8, sizes.into_iter(), 1.0), 0);
+    }
+
+    #[test]
+    fn best_variant_falls_back_to_largest() {
+        assert_eq!(best_variant(64, [16, 24, 32].into_iter(), 1.0), 2);
+        assert_eq!(best_variant(24, [32].into_iter(), 2.0), 0);
+        assert_eq!(best_variant(24, std::iter::empty::<u32>(), 1.0), 0);
+    }
 }
@@ -39,6 +39,8 @@ pub enum EventLoopError {
     RecreationAttempt,
     /// Application has exit with an error status.
     ExitFailure(i32),
+    /// The connection to the display server was lost.
+    ConnectionLost,
 }
 
 impl From<OsError> for EventLoopError {
@@ -107,6 +109,7 @@ impl fmt::Display for EventLoopError {
             EventLoopError::NotSupported(e) => e.fmt(f),
             EventLoopError::Os(e) => e.fmt(f),
             EventLoopError::ExitFailure(status) => write!(f, "Exit Failure: {status}"),
+            EventLoopError::ConnectionLost => write!(f, "Connection to the display server lost"),
         }
     }
 }
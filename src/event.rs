@@ -48,10 +48,10 @@ use crate::error::ExternalError;
 use crate::window::Window;
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize},
-    event_loop::AsyncRequestSerial,
+    event_loop::{AsyncRequestSerial, FdReadiness, FdToken},
     keyboard::{self, ModifiersKeyState, ModifiersKeys, ModifiersState},
     platform_impl,
-    window::{ActivationToken, Theme, WindowId},
+    window::{ActivationToken, ForeignWindowHandle, Theme, WindowId, WindowState},
 };
 
 /// Describes a generic event.
@@ -82,6 +82,19 @@ pub enum Event<T: 'static> {
     /// Emitted when an event is sent from [`EventLoopProxy::send_event`](crate::event_loop::EventLoopProxy::send_event)
     UserEvent(T),
 
+    /// Emitted when a file descriptor registered with the event loop became ready.
+    ///
+    /// The file descriptors are polled level-triggered, so this event will be emitted on every
+    /// iteration of the event loop until the file descriptor is drained or unregistered.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - Only available on **X11** and **Wayland**, see the `platform::fd_source` module.
+    FdReady {
+        token: FdToken,
+        readiness: FdReadiness,
+    },
+
     /// Emitted when the application has been suspended.
     ///
     /// # Portability
@@ -222,6 +235,29 @@ pub enum Event<T: 'static> {
     /// should render in response to [`WindowEvent::RedrawRequested`] events.
     AboutToWait,
 
+    /// Emitted when the connection to the display server was lost, like when the compositor
+    /// crashed or the remote session got disconnected.
+    ///
+    /// The event loop exits right after, emitting [`Event::LoopExiting`], and
+    /// [`EventLoop::run`] returns [`EventLoopError::ConnectionLost`]. The windows can't be used
+    /// anymore, but the application could still save its data, and reconnect by creating a new
+    /// [`EventLoop`] once the current one is dropped.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The connection loss can only be recovered from with libX11 1.7 or newer,
+    ///   otherwise Xlib exits the process. The window setters ignore the requests afterwards, but
+    ///   the getters querying the X server, like [`Window::inner_size`] or
+    ///   [`Window::outer_position`], may panic, so the windows should be dropped instead.
+    /// - **iOS / Android / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`EventLoop`]: crate::event_loop::EventLoop
+    /// [`EventLoop::run`]: crate::event_loop::EventLoop::run
+    /// [`EventLoopError::ConnectionLost`]: crate::error::EventLoopError::ConnectionLost
+    /// [`Window::inner_size`]: crate::window::Window::inner_size
+    /// [`Window::outer_position`]: crate::window::Window::outer_position
+    ConnectionLost,
+
     /// Emitted when the event loop is being shut down.
     ///
     /// This is irreversible - if this event is emitted, it is guaranteed to be the last event that
@@ -263,8 +299,10 @@ impl<T> Event<T> {
             UserEvent(_) => Err(self),
             WindowEvent { window_id, event } => Ok(WindowEvent { window_id, event }),
             DeviceEvent { device_id, event } => Ok(DeviceEvent { device_id, event }),
+            FdReady { token, readiness } => Ok(FdReady { token, readiness }),
             NewEvents(cause) => Ok(NewEvents(cause)),
             AboutToWait => Ok(AboutToWait),
+            ConnectionLost => Ok(ConnectionLost),
             LoopExiting => Ok(LoopExiting),
             Suspended => Ok(Suspended),
             Resumed => Ok(Resumed),
@@ -320,6 +358,20 @@ pub enum WindowEvent {
         token: ActivationToken,
     },
 
+    /// The window was exported and the handle could now be passed to another process.
+    ///
+    #[cfg_attr(
+        not(any(x11_platform, wayland_platform)),
+        allow(rustdoc::broken_intra_doc_links)
+    )]
+    /// Delivered in response to [`export_handle`].
+    ///
+    /// [`export_handle`]: crate::platform::foreign_window::WindowExtForeignWindow::export_handle
+    HandleExported {
+        serial: AsyncRequestSerial,
+        handle: ForeignWindowHandle,
+    },
+
     /// The size of the window has changed. Contains the client area's new dimensions.
     Resized(PhysicalSize<u32>),
 
@@ -409,6 +461,14 @@ pub enum WindowEvent {
         /// limited by the display area and it may have been transformed by the OS to implement effects such as cursor
         /// acceleration, it should not be used to implement non-cursor-like interactions such as 3D camera control.
         position: PhysicalPosition<f64>,
+
+        /// The time the event was generated at, converted from the clock of the display server.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Wayland:** `None` when the cursor enters the window.
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always `None`.
+        timestamp: Option<Instant>,
     },
 
     /// The cursor has entered the window.
@@ -434,10 +494,32 @@ pub enum WindowEvent {
     CursorLeft { device_id: DeviceId },
 
     /// A mouse wheel movement or touchpad scroll occurred.
+    ///
+    /// With a [`MouseScrollSource::Finger`] source, the [`TouchPhase::Ended`] phase is sent once
+    /// the fingers are lifted, to start kinetic scrolling.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The [`TouchPhase::Ended`] phase is never sent, XInput2 doesn't report the
+    ///   fingers being lifted. The scroll emulated from the legacy scroll buttons is a
+    ///   [`MouseScrollSource::Wheel`] or [`MouseScrollSource::WheelTilt`], while the smooth scroll
+    ///   is a [`MouseScrollSource::Finger`] on touchpads and touchscreens and a
+    ///   [`MouseScrollSource::Wheel`] otherwise.
+    /// - **Wayland:** The high-resolution wheel values are only reported by the version 8 of the
+    ///   seat, older seats report multiples of 120.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** The source is always
+    ///   [`MouseScrollSource::Unknown`] and the high-resolution wheel values aren't reported.
     MouseWheel {
         device_id: DeviceId,
         delta: MouseScrollDelta,
         phase: TouchPhase,
+        /// The kind of device the scroll originates from.
+        source: MouseScrollSource,
+        /// The horizontal and vertical movement of the wheel, where `120` is one detent.
+        ///
+        /// The sign matches [`MouseScrollDelta::LineDelta`]. Only reported for the wheel sources,
+        /// high-resolution wheels could report a fraction of a detent.
+        value120: Option<(i32, i32)>,
     },
 
     /// An mouse button press has been received.
@@ -445,6 +527,13 @@ pub enum WindowEvent {
         device_id: DeviceId,
         state: ElementState,
         button: MouseButton,
+
+        /// The time the event was generated at, converted from the clock of the display server.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always `None`.
+        timestamp: Option<Instant>,
     },
 
     /// Two-finger pinch gesture, often used for magnification.
@@ -586,6 +675,29 @@ pub enum WindowEvent {
     /// [`transform`]: https://developer.mozilla.org/en-US/docs/Web/CSS/transform
     Occluded(bool),
 
+    /// The state of the window changed, like it was maximized or tiled by the user or the window
+    /// manager.
+    ///
+    /// Carries the whole new [`WindowState`], thus the application doesn't need to poll
+    /// [`Window::is_maximized`] and friends.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    ///
+    /// [`Window::is_maximized`]: crate::window::Window::is_maximized
+    StateChanged(WindowState),
+
+    /// The virtual desktop the window is on changed, or the desktop shown by the window manager
+    /// changed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The desktops are given by `WindowExtX11::desktop` and
+    ///   `ActiveEventLoopExtX11::current_desktop`.
+    /// - **Android / iOS / macOS / Orbital / Wayland / Web / Windows:** Unsupported.
+    DesktopChanged,
+
     /// Emitted when a window should be redrawn.
     ///
     /// This gets triggered in two scenarios:
@@ -643,6 +755,13 @@ pub enum DeviceEvent {
         ///
         /// Different devices may use different units.
         delta: (f64, f64),
+
+        /// The time the event was generated at, converted from the clock of the display server.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always `None`.
+        timestamp: Option<Instant>,
     },
 
     /// Physical scroll event
@@ -808,6 +927,16 @@ pub struct KeyEvent {
     /// ```
     pub repeat: bool,
 
+    /// The time the key was pressed or released at, converted from the clock of the display
+    /// server.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11 / Wayland:** `None` for the synthetic key events.
+    /// - **Wayland:** `None` for the key repeats, which are generated by winit.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always `None`.
+    pub timestamp: Option<Instant>,
+
     /// Platform-specific key event information.
     ///
     /// On Windows, Linux and macOS, this type contains the key without modifiers and the text with all
@@ -1013,6 +1142,12 @@ pub struct Touch {
     pub force: Option<Force>,
     /// Unique identifier of a finger.
     pub id: u64,
+    /// The time the event was generated at, converted from the clock of the display server.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always `None`.
+    pub timestamp: Option<Instant>,
 }
 
 /// Describes the force of a touch event
@@ -1137,6 +1272,24 @@ pub enum MouseScrollDelta {
     PixelDelta(PhysicalPosition<f64>),
 }
 
+/// The kind of device a scroll originates from.
+#[non_exhaustive]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MouseScrollSource {
+    /// The source isn't known.
+    #[default]
+    Unknown,
+    /// A mouse wheel, scrolling in discrete steps.
+    Wheel,
+    /// Fingers on a touchpad or touchscreen.
+    Finger,
+    /// A continuous source without a terminating event, like a trackpoint or button scrolling.
+    Continuous,
+    /// A mouse wheel tilted sideways.
+    WheelTilt,
+}
+
 /// Handle to synchronously change the size of the window from the
 /// [`WindowEvent`].
 #[derive(Debug, Clone)]
@@ -1191,9 +1344,14 @@ mod tests {
                 x(UserEvent(()));
                 x(NewEvents(event::StartCause::Init));
                 x(AboutToWait);
+                x(ConnectionLost);
                 x(LoopExiting);
                 x(Suspended);
                 x(Resumed);
+                x(FdReady {
+                    token: crate::event_loop::FdToken::get(),
+                    readiness: Default::default(),
+                });
 
                 // Window events.
                 let with_window_event = |wev| {
@@ -1215,6 +1373,7 @@ mod tests {
                 with_window_event(CursorMoved {
                     device_id: did,
                     position: (0, 0).into(),
+                    timestamp: None,
                 });
                 with_window_event(ModifiersChanged(event::Modifiers::default()));
                 with_window_event(CursorEntered { device_id: did });
@@ -1223,11 +1382,14 @@ mod tests {
                     device_id: did,
                     delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
                     phase: event::TouchPhase::Started,
+                    source: event::MouseScrollSource::Wheel,
+                    value120: Some((0, 0)),
                 });
                 with_window_event(MouseInput {
                     device_id: did,
                     state: event::ElementState::Pressed,
                     button: event::MouseButton::Other(0),
+                    timestamp: None,
                 });
                 with_window_event(PinchGesture {
                     device_id: did,
@@ -1256,9 +1418,16 @@ mod tests {
                     location: (0.0, 0.0).into(),
                     id: 0,
                     force: Some(event::Force::Normalized(0.0)),
+                    timestamp: None,
                 }));
                 with_window_event(ThemeChanged(crate::window::Theme::Light));
                 with_window_event(Occluded(true));
+                with_window_event(StateChanged(crate::window::WindowState::MAXIMIZED));
+                with_window_event(DesktopChanged);
+                with_window_event(HandleExported {
+                    serial: crate::event_loop::AsyncRequestSerial::get(),
+                    handle: crate::window::ForeignWindowHandle::from_raw(String::new()),
+                });
             }
 
             #[allow(deprecated)]
@@ -1276,6 +1445,7 @@ mod tests {
                 with_device_event(Removed);
                 with_device_event(MouseMotion {
                     delta: (0.0, 0.0).into(),
+                    timestamp: None,
                 });
                 with_device_event(MouseWheel {
                     delta: event::MouseScrollDelta::LineDelta(0.0, 0.0),
@@ -1360,6 +1530,7 @@ mod tests {
             location: (0.0, 0.0).into(),
             id: 0,
             force: Some(event::Force::Normalized(0.0)),
+            timestamp: None,
         }
         .clone();
         let _ = event::Force::Calibrated {
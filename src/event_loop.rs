@@ -94,7 +94,8 @@ impl<T> EventLoopBuilder<T> {
     /// ## Platform-specific
     ///
     /// - **Wayland/X11:** to prevent running under `Wayland` or `X11` unset `WAYLAND_DISPLAY`
-    ///                    or `DISPLAY` respectively when building the event loop.
+    ///                    or `DISPLAY` respectively when building the event loop, or select the
+    ///                    backends with `EventLoopBuilderExtBackend::with_backends`.
     /// - **Android:** must be configured with an `AndroidApp` from `android_main()` by calling
     ///     [`.with_android_app(app)`] before calling `.build()`, otherwise it'll panic.
     ///
@@ -121,7 +122,7 @@ impl<T> EventLoopBuilder<T> {
         })
     }
 
-    #[cfg(web_platform)]
+    #[cfg(any(web_platform, x11_platform, wayland_platform))]
     pub(crate) fn allow_event_loop_recreation() {
         EVENT_LOOP_CREATED.store(false, Ordering::Relaxed);
     }
@@ -404,7 +405,10 @@ impl ActiveEventLoop {
     ///
     /// ## Platform-specific
     ///
-    /// - **Wayland / macOS / iOS / Android / Orbital:** Unsupported.
+    /// - **Wayland:** The compositor only delivers the input while a window has the pointer or
+    ///   keyboard focus, thus [`DeviceEvents::Always`] behaves like [`DeviceEvents::WhenFocused`]
+    ///   for input outside of the windows.
+    /// - **macOS / iOS / Android / Orbital:** Unsupported.
     ///
     /// [`DeviceEvent`]: crate::event::DeviceEvent
     pub fn listen_device_events(&self, allowed: DeviceEvents) {
@@ -592,3 +596,39 @@ impl AsyncRequestSerial {
         Self { serial }
     }
 }
+
+/// A unique identifier of a file descriptor registered with the event loop.
+///
+/// The token is returned when registering the file descriptor and is carried by every
+/// [`Event::FdReady`] emitted for it, so it could be used to tell the registered file
+/// descriptors apart.
+///
+/// [`Event::FdReady`]: crate::event::Event::FdReady
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdToken {
+    token: usize,
+}
+
+impl FdToken {
+    #[cfg(any(x11_platform, wayland_platform, test))]
+    pub(crate) fn get() -> Self {
+        static CURRENT_TOKEN: AtomicUsize = AtomicUsize::new(0);
+        let token = CURRENT_TOKEN.fetch_add(1, Ordering::Relaxed);
+        Self { token }
+    }
+}
+
+/// The readiness of a registered file descriptor.
+///
+/// Delivered inside [`Event::FdReady`].
+///
+/// [`Event::FdReady`]: crate::event::Event::FdReady
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdReadiness {
+    /// The file descriptor could be read from without blocking.
+    pub readable: bool,
+    /// The file descriptor could be written to without blocking.
+    pub writable: bool,
+    /// An error condition was signalled on the file descriptor.
+    pub error: bool,
+}
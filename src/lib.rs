@@ -158,6 +158,8 @@
 //! * `serde`: Enables serialization/deserialization of certain types with
 //!   [Serde](https://crates.io/crates/serde).
 //! * `mint`: Enables mint (math interoperability standard types) conversions.
+//! * `futures-core`: On X11 and Wayland, implements `futures_core::Stream` for
+//!   `platform::event_stream::EventStream`.
 //!
 //! See the [`platform`] module for documentation on platform-specific cargo
 //! features.
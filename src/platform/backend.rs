@@ -0,0 +1,236 @@
+//! Select the display backend at runtime.
+//!
+//! By default the backend is picked from the environment: Wayland when `WAYLAND_DISPLAY` or
+//! `WAYLAND_SOCKET` is set, X11 when `DISPLAY` is set. With
+//! [`EventLoopBuilderExtBackend::with_backends`] the application could instead give an ordered
+//! list of backends to try, alongside the [`BackendCapabilities`] it can't work without. Each
+//! backend is tried in turn, and the first one which connects and provides all the required
+//! capabilities is used.
+//!
+//! The backends which were rejected on the way are logged with the reason, and are listed in the
+//! error returned from [`EventLoopBuilder::build`] when none of them is suitable.
+//!
+//! Once running, the capabilities of the backend in use could be queried with
+//! [`ActiveEventLoopExtBackend::capabilities`] and [`WindowExtBackend::capabilities`], to not
+//! offer features which would be silently ignored by the display server.
+//!
+//! ```no_run
+//! use winit::event_loop::EventLoop;
+//! use winit::platform::backend::{Backend, BackendCapabilities, EventLoopBuilderExtBackend};
+//!
+//! // Prefer Wayland as long as the cursor could be locked, fall back to X11 otherwise.
+//! let event_loop = EventLoop::builder()
+//!     .with_backends(
+//!         &[Backend::Wayland, Backend::X11],
+//!         BackendCapabilities::CURSOR_LOCK,
+//!     )
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use std::fmt;
+
+use crate::event_loop::{ActiveEventLoop, EventLoopBuilder};
+use crate::window::Window;
+
+/// The display backend.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// The X Window System.
+    X11,
+    /// The Wayland protocol.
+    Wayland,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::X11 => f.write_str("X11"),
+            Self::Wayland => f.write_str("Wayland"),
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// The optional features a backend could provide depending on the display server.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BackendCapabilities: u32 {
+        /// The windows could be decorated by the display server.
+        const SERVER_SIDE_DECORATIONS = 1 << 0;
+        /// The position of the windows could be queried and changed.
+        const WINDOW_POSITION = 1 << 1;
+        /// The windows could request to be activated, see [`Window::focus_window`].
+        ///
+        /// [`Window::focus_window`]: crate::window::Window::focus_window
+        const ACTIVATION = 1 << 2;
+        /// The scale factor could be fractional instead of being rounded up.
+        const FRACTIONAL_SCALE = 1 << 3;
+        /// The cursor could be locked with [`CursorGrabMode::Locked`].
+        ///
+        /// [`CursorGrabMode::Locked`]: crate::window::CursorGrabMode::Locked
+        const CURSOR_LOCK = 1 << 4;
+        /// The cursor could be confined with [`CursorGrabMode::Confined`].
+        ///
+        /// [`CursorGrabMode::Confined`]: crate::window::CursorGrabMode::Confined
+        const CURSOR_CONFINE = 1 << 5;
+        /// Unaccelerated pointer motion is delivered with [`DeviceEvent::MouseMotion`].
+        ///
+        /// [`DeviceEvent::MouseMotion`]: crate::event::DeviceEvent::MouseMotion
+        const RAW_POINTER_MOTION = 1 << 6;
+        /// Text could be composed with an input method, see [`Window::set_ime_allowed`].
+        ///
+        /// [`Window::set_ime_allowed`]: crate::window::Window::set_ime_allowed
+        const IME = 1 << 7;
+        /// The window icon could be set with [`Window::set_window_icon`].
+        ///
+        /// [`Window::set_window_icon`]: crate::window::Window::set_window_icon
+        const WINDOW_ICON = 1 << 8;
+        /// The window level could be set with [`Window::set_window_level`].
+        ///
+        /// [`Window::set_window_level`]: crate::window::Window::set_window_level
+        const WINDOW_LEVEL = 1 << 9;
+        /// The resize increments could be set with [`Window::set_resize_increments`].
+        ///
+        /// [`Window::set_resize_increments`]: crate::window::Window::set_resize_increments
+        const RESIZE_INCREMENTS = 1 << 10;
+        /// The background of the window could be blurred with [`Window::set_blur`].
+        ///
+        /// [`Window::set_blur`]: crate::window::Window::set_blur
+        const BLUR = 1 << 11;
+        /// The window buttons could be set with [`Window::set_enabled_buttons`].
+        ///
+        /// [`Window::set_enabled_buttons`]: crate::window::Window::set_enabled_buttons
+        const ENABLED_BUTTONS = 1 << 12;
+        /// The window menu could be shown with [`Window::show_window_menu`].
+        ///
+        /// [`Window::show_window_menu`]: crate::window::Window::show_window_menu
+        const WINDOW_MENU = 1 << 13;
+        /// The window could be moved and resized interactively with [`Window::drag_window`] and
+        /// [`Window::drag_resize_window`].
+        ///
+        /// [`Window::drag_window`]: crate::window::Window::drag_window
+        /// [`Window::drag_resize_window`]: crate::window::Window::drag_resize_window
+        const DRAG_RESIZE = 1 << 14;
+        /// The opacity of the window could be set with [`Window::set_opacity`].
+        ///
+        /// [`Window::set_opacity`]: crate::window::Window::set_opacity
+        const OPACITY = 1 << 15;
+        /// The windows could be parented to the windows of another process, see
+        /// [`foreign_window`].
+        ///
+        /// [`foreign_window`]: crate::platform::foreign_window
+        const FOREIGN_PARENT = 1 << 16;
+    }
+}
+
+/// The reason the backend was not used.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendRejectionReason {
+    /// Support for the backend wasn't compiled in, see the `x11` and `wayland` cargo features.
+    NotCompiled,
+    /// Connecting to the display server failed.
+    Unavailable(String),
+    /// The display server lacks the given required capabilities.
+    MissingCapabilities(BackendCapabilities),
+}
+
+/// The backend rejected while building the event loop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackendRejection {
+    /// The rejected backend.
+    pub backend: Backend,
+    /// Why the backend was rejected.
+    pub reason: BackendRejectionReason,
+}
+
+impl fmt::Display for BackendRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            BackendRejectionReason::NotCompiled => write!(
+                f,
+                "{}: not compiled in; note: enable the `winit/{}` feature",
+                self.backend,
+                match self.backend {
+                    Backend::X11 => "x11",
+                    Backend::Wayland => "wayland",
+                }
+            ),
+            BackendRejectionReason::Unavailable(error) => write!(f, "{}: {error}", self.backend),
+            BackendRejectionReason::MissingCapabilities(missing) => {
+                write!(f, "{}: missing capabilities ", self.backend)?;
+                bitflags::parser::to_writer(missing, f)
+            }
+        }
+    }
+}
+
+/// Additional methods on [`EventLoopBuilder`] to select the display backend.
+pub trait EventLoopBuilderExtBackend {
+    /// Try the given `backends` in order, using the first one which provides all the `required`
+    /// capabilities.
+    ///
+    /// The environment variables aren't considered to pick the backend when the list is given.
+    /// Passing a single backend with no required capabilities forces that backend, like
+    /// `with_x11` and `with_wayland` in the respective platform modules do.
+    fn with_backends(&mut self, backends: &[Backend], required: BackendCapabilities) -> &mut Self;
+}
+
+impl<T> EventLoopBuilderExtBackend for EventLoopBuilder<T> {
+    #[inline]
+    fn with_backends(&mut self, backends: &[Backend], required: BackendCapabilities) -> &mut Self {
+        self.platform_specific.backend_preference = Some(crate::platform_impl::BackendPreference {
+            backends: backends.to_vec(),
+            required,
+        });
+        self
+    }
+}
+
+/// Additional methods on [`ActiveEventLoop`] to query the display backend.
+pub trait ActiveEventLoopExtBackend {
+    /// The backend in use.
+    fn backend(&self) -> Backend;
+
+    /// The capabilities of the backend in use.
+    ///
+    /// The capabilities depend on the display server and are queried each time, thus follow the
+    /// Wayland globals being removed or the X11 window manager being replaced.
+    fn capabilities(&self) -> BackendCapabilities;
+}
+
+impl ActiveEventLoopExtBackend for ActiveEventLoop {
+    #[inline]
+    fn backend(&self) -> Backend {
+        self.p.backend()
+    }
+
+    #[inline]
+    fn capabilities(&self) -> BackendCapabilities {
+        self.p.capabilities()
+    }
+}
+
+/// Additional methods on [`Window`] to query the display backend.
+pub trait WindowExtBackend {
+    /// The backend the window was created with.
+    fn backend(&self) -> Backend;
+
+    /// The capabilities of the backend the window was created with.
+    ///
+    /// See [`ActiveEventLoopExtBackend::capabilities`].
+    fn capabilities(&self) -> BackendCapabilities;
+}
+
+impl WindowExtBackend for Window {
+    #[inline]
+    fn backend(&self) -> Backend {
+        self.window.backend()
+    }
+
+    #[inline]
+    fn capabilities(&self) -> BackendCapabilities {
+        self.window.capabilities()
+    }
+}
@@ -0,0 +1,173 @@
+//! Drive the event loop from an async executor.
+//!
+//! The [`EventStream`] wraps an [`EventLoop`] and exposes it as a stream of events which could
+//! be awaited with [`EventStream::next_event`], next to other futures in the same task, without
+//! blocking the executor thread. With the `futures-core` cargo feature enabled it also implements
+//! `futures_core::Stream`.
+//!
+//! Internally the stream is built on top of [`EventLoopExtPumpEvents::pump_events`]. When no
+//! events are pending, the task awaiting the stream is woken up by [`EventLoopProxy::send_event`],
+//! thus user events are delivered without further setup.
+//!
+//! The stream doesn't wait for the display server by itself, since that would require a thread
+//! or a reactor of its own. Instead, it implements [`AsFd`] with the file descriptor of the event
+//! loop, which the reactor of the executor should wait on to become readable before polling the
+//! stream again, like with `tokio::io::unix::AsyncFd` or `async_io::Async`. Likewise, the
+//! deadline of [`ControlFlow::WaitUntil`] isn't tracked by the stream, use the timers of the
+//! executor to wake up the task instead.
+//!
+//! ## Main thread constraints
+//!
+//! The [`EventLoop`] is neither [`Send`] nor [`Sync`], and neither is the [`EventStream`]. The
+//! stream must be polled from the thread the event loop was created on, which is the main thread unless
+//! the event loop was built with `with_any_thread(true)` from the `x11` or `wayland` platform
+//! modules. This means that the stream can't be spawned onto a work-stealing executor; run it
+//! with a single-threaded executor on the thread owning the event loop instead, such as a local
+//! task set.
+//!
+//! ## Buffered events
+//!
+//! The events are buffered before being yielded, thus the caveats described in
+//! [`EventLoopExtPumpEvents::pump_events`] about synchronous events apply. Notably the
+//! [`InnerSizeWriter`] of [`WindowEvent::ScaleFactorChanged`] is expired by the time the event is
+//! yielded.
+//!
+//! [`ControlFlow::WaitUntil`]: crate::event_loop::ControlFlow::WaitUntil
+//! [`EventLoopProxy::send_event`]: crate::event_loop::EventLoopProxy::send_event
+//! [`InnerSizeWriter`]: crate::event::InnerSizeWriter
+//! [`WindowEvent::ScaleFactorChanged`]: crate::event::WindowEvent::ScaleFactorChanged
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::os::unix::io::{AsFd, BorrowedFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use crate::event::Event;
+use crate::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use crate::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+
+/// An [`EventLoop`] exposed as an asynchronous stream of events.
+///
+/// See the [module-level docs](self) for more information.
+pub struct EventStream<T: 'static> {
+    event_loop: EventLoop<T>,
+    events: VecDeque<Event<T>>,
+    exit_code: Option<i32>,
+}
+
+impl<T: 'static> EventStream<T> {
+    /// Create the stream from the given event loop.
+    ///
+    /// The event loop should not be run before, since the stream will deliver the initial
+    /// [`StartCause::Init`] and [`Event::Resumed`] events.
+    ///
+    /// [`StartCause::Init`]: crate::event::StartCause::Init
+    pub fn new(event_loop: EventLoop<T>) -> Self {
+        Self {
+            event_loop,
+            events: VecDeque::new(),
+            exit_code: None,
+        }
+    }
+
+    /// Get the underlying event loop.
+    pub fn event_loop(&self) -> &EventLoop<T> {
+        &self.event_loop
+    }
+
+    /// Get the [`ActiveEventLoop`] of the running event loop.
+    ///
+    /// Windows should be created with it in response to the [`Event::Resumed`] event.
+    pub fn active_event_loop(&self) -> &ActiveEventLoop {
+        self.event_loop.event_loop.window_target()
+    }
+
+    /// The exit code of the event loop once the stream has finished.
+    ///
+    /// The stream finishes after yielding [`Event::LoopExiting`] in response to
+    /// [`ActiveEventLoop::exit`].
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Wait for the next event.
+    ///
+    /// Resolves to `None` once the event loop has exited.
+    pub fn next_event(&mut self) -> NextEvent<'_, T> {
+        NextEvent { stream: self }
+    }
+
+    /// Poll for the next event, registering the waker of the current task when none is
+    /// available.
+    ///
+    /// Returns `Poll::Ready(None)` once the event loop has exited.
+    pub fn poll_next_event(&mut self, cx: &mut Context<'_>) -> Poll<Option<Event<T>>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                self.event_loop.event_loop.register_stream_waker(None);
+                return Poll::Ready(Some(event));
+            }
+
+            if self.exit_code.is_some() {
+                self.event_loop.event_loop.register_stream_waker(None);
+                return Poll::Ready(None);
+            }
+
+            // Registered before pumping, so the user events sent right after are not missed.
+            self.event_loop
+                .event_loop
+                .register_stream_waker(Some(cx.waker().clone()));
+
+            let events = &mut self.events;
+            let status = self
+                .event_loop
+                .pump_events(Some(Duration::ZERO), |event, _| events.push_back(event));
+            if let PumpStatus::Exit(code) = status {
+                self.exit_code = Some(code);
+            }
+
+            if !self.events.is_empty() || self.exit_code.is_some() {
+                continue;
+            }
+
+            if self.active_event_loop().control_flow() == ControlFlow::Poll {
+                cx.waker().wake_by_ref();
+            }
+
+            return Poll::Pending;
+        }
+    }
+}
+
+impl<T: 'static> AsFd for EventStream<T> {
+    /// Get the file descriptor of the event loop, which becomes readable when the stream should
+    /// be polled again.
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.event_loop.as_fd()
+    }
+}
+
+#[cfg(feature = "futures-core")]
+impl<T: Unpin + 'static> futures_core::Stream for EventStream<T> {
+    type Item = Event<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_next_event(cx)
+    }
+}
+
+/// Future returned by [`EventStream::next_event`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct NextEvent<'a, T: 'static> {
+    stream: &'a mut EventStream<T>,
+}
+
+impl<'a, T: 'static> Future for NextEvent<'a, T> {
+    type Output = Option<Event<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().stream.poll_next_event(cx)
+    }
+}
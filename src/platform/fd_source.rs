@@ -0,0 +1,107 @@
+//! Register external file descriptors with the event loop.
+//!
+//! Applications often have to wait on other file descriptors next to the display connection,
+//! like a D-Bus connection, a pipe from a worker process or an inotify instance. Instead of
+//! running a second poll loop or busy-pumping the event loop, such file descriptors could be
+//! registered with the loop winit is already polling with [`EventLoopExtFdSource::register_fd`].
+//!
+//! Once the file descriptor becomes ready, an [`Event::FdReady`] carrying the [`FdToken`]
+//! returned during registration is delivered to the event handler, no extra threads involved.
+//!
+//! The file descriptors are polled level-triggered, thus the event will be delivered on every
+//! iteration of the event loop until the file descriptor is drained or unregistered with
+//! [`EventLoopExtFdSource::unregister_fd`].
+//!
+//! [`Event::FdReady`]: crate::event::Event::FdReady
+
+use std::os::unix::io::BorrowedFd;
+
+use crate::error::ExternalError;
+use crate::event_loop::{ActiveEventLoop, EventLoop, FdToken};
+
+/// The readiness the registered file descriptor is polled for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FdInterest {
+    /// Wait for the file descriptor to become readable.
+    pub readable: bool,
+    /// Wait for the file descriptor to become writable.
+    pub writable: bool,
+}
+
+impl FdInterest {
+    /// Wait for the file descriptor to become readable.
+    pub const READ: Self = Self {
+        readable: true,
+        writable: false,
+    };
+
+    /// Wait for the file descriptor to become writable.
+    pub const WRITE: Self = Self {
+        readable: false,
+        writable: true,
+    };
+
+    /// Wait for the file descriptor to become either readable or writable.
+    pub const BOTH: Self = Self {
+        readable: true,
+        writable: true,
+    };
+}
+
+/// Additional methods on [`EventLoop`] and [`ActiveEventLoop`] to register external
+/// file descriptors.
+pub trait EventLoopExtFdSource {
+    /// Register the file descriptor to be polled by the event loop.
+    ///
+    /// An [`Event::FdReady`] with the returned [`FdToken`] will be delivered once the file
+    /// descriptor is ready according to the given `interest`.
+    ///
+    /// # Safety
+    ///
+    /// The file descriptor must stay open until it's unregistered with
+    /// [`unregister_fd`](Self::unregister_fd) or the event loop is dropped.
+    ///
+    /// [`Event::FdReady`]: crate::event::Event::FdReady
+    unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError>;
+
+    /// Stop polling the file descriptor registered with the given `token`.
+    ///
+    /// Unknown tokens are ignored.
+    fn unregister_fd(&self, token: FdToken);
+}
+
+impl<T> EventLoopExtFdSource for EventLoop<T> {
+    #[inline]
+    unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError> {
+        unsafe { self.event_loop.window_target().register_fd(fd, interest) }
+    }
+
+    #[inline]
+    fn unregister_fd(&self, token: FdToken) {
+        self.event_loop.window_target().unregister_fd(token)
+    }
+}
+
+impl EventLoopExtFdSource for ActiveEventLoop {
+    #[inline]
+    unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError> {
+        unsafe { self.p.register_fd(fd, interest) }
+    }
+
+    #[inline]
+    fn unregister_fd(&self, token: FdToken) {
+        self.p.unregister_fd(token)
+    }
+}
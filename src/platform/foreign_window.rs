@@ -0,0 +1,70 @@
+//! Parent windows to the windows of another process.
+//!
+//! A window is exported with [`WindowExtForeignWindow::export_handle`], which delivers a
+//! [`ForeignWindowHandle`] in [`WindowEvent::HandleExported`]. Its string representation,
+//! obtained with [`ForeignWindowHandle::as_raw`], could be sent to another process, which
+//! restores it with [`ForeignWindowHandle::from_raw`] and creates its windows on top of the
+//! exported one with [`WindowAttributesExtForeignWindow::with_foreign_parent`]. This is how the
+//! dialogs of a plugin running in its own process stay above the window of the host.
+//!
+//! ```no_run
+//! use winit::platform::foreign_window::WindowAttributesExtForeignWindow;
+//! use winit::window::{ForeignWindowHandle, Window};
+//!
+//! // The raw handle was received from the host process, e.g. on the command line.
+//! fn plugin_dialog(raw_handle: String) -> winit::window::WindowAttributes {
+//!     Window::default_attributes()
+//!         .with_title("Plugin settings")
+//!         .with_foreign_parent(ForeignWindowHandle::from_raw(raw_handle))
+//! }
+//! ```
+//!
+//! Whether the handles are supported is reported by [`BackendCapabilities::FOREIGN_PARENT`].
+//!
+//! ## Platform-specific
+//!
+//! - **X11:** The handle is the XID of the window and is valid as long as the window exists. The
+//!   parent is set with `WM_TRANSIENT_FOR`.
+//! - **Wayland:** The handle is obtained with `zxdg_exporter_v2` and the parent is set with
+//!   `zxdg_importer_v2`. Exporting the window again revokes the handle exported previously.
+//!
+//! [`WindowEvent::HandleExported`]: crate::event::WindowEvent::HandleExported
+//! [`BackendCapabilities::FOREIGN_PARENT`]: crate::platform::backend::BackendCapabilities::FOREIGN_PARENT
+
+use crate::error::NotSupportedError;
+use crate::event_loop::AsyncRequestSerial;
+use crate::window::{ForeignWindowHandle, Window, WindowAttributes};
+
+/// Additional methods on [`Window`] to export it to other processes.
+pub trait WindowExtForeignWindow {
+    /// Request a handle to the window which could be passed to another process.
+    ///
+    /// The handle will be delivered inside [`WindowEvent::HandleExported`].
+    ///
+    /// [`WindowEvent::HandleExported`]: crate::event::WindowEvent::HandleExported
+    fn export_handle(&self) -> Result<AsyncRequestSerial, NotSupportedError>;
+}
+
+/// Additional methods on [`WindowAttributes`] to parent the window to the window of another
+/// process.
+pub trait WindowAttributesExtForeignWindow {
+    /// Build the window transient for the window of another process, which exported the given
+    /// handle.
+    ///
+    /// The window is kept above its parent and usually doesn't get its own taskbar entry. An
+    /// invalid handle is ignored.
+    fn with_foreign_parent(self, handle: ForeignWindowHandle) -> Self;
+}
+
+impl WindowExtForeignWindow for Window {
+    fn export_handle(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
+        self.window.export_handle()
+    }
+}
+
+impl WindowAttributesExtForeignWindow for WindowAttributes {
+    fn with_foreign_parent(mut self, handle: ForeignWindowHandle) -> Self {
+        self.platform_specific.foreign_parent = Some(handle);
+        self
+    }
+}
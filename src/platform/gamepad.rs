@@ -0,0 +1,258 @@
+//! Gamepads and joysticks.
+//!
+//! The gamepads aren't handled by the display server, thus are read by winit from the evdev
+//! device nodes in `/dev/input`, which requires the user to have access to them. This is usually
+//! granted to the user of the active session by `udev`.
+//!
+//! Reading the gamepads must be enabled with [`EventLoopBuilderExtGamepad::with_gamepads`]. The
+//! gamepads connected at the time are announced with [`DeviceEvent::Added`] on the first
+//! iteration of the event loop, the ones plugged in or out later with [`DeviceEvent::Added`] and
+//! [`DeviceEvent::Removed`]. They are listed by [`ActiveEventLoopExtInputDevices::input_devices`]
+//! with the [`InputDeviceKind::Gamepad`] or [`InputDeviceKind::Joystick`] kind.
+//!
+//! The input is delivered with [`DeviceEvent::Button`] and [`DeviceEvent::Motion`], the ids of
+//! which are translated with [`GamepadButton::from_id`] and [`GamepadAxis::from_id`]. Like the
+//! other device events, they are subject to [`ActiveEventLoop::listen_device_events`].
+//!
+//! ```no_run
+//! use winit::event::{DeviceEvent, Event};
+//! use winit::event_loop::EventLoop;
+//! use winit::platform::gamepad::{EventLoopBuilderExtGamepad, GamepadButton};
+//!
+//! let event_loop = EventLoop::builder().with_gamepads(true).build().unwrap();
+//! event_loop
+//!     .run(|event, _| {
+//!         if let Event::DeviceEvent {
+//!             event: DeviceEvent::Button { button, state },
+//!             ..
+//!         } = event
+//!         {
+//!             if GamepadButton::from_id(button) == Some(GamepadButton::South) {
+//!                 println!("South button {state:?}");
+//!             }
+//!         }
+//!     })
+//!     .unwrap();
+//! ```
+//!
+//! ## Platform-specific
+//!
+//! - **FreeBSD / NetBSD / OpenBSD / DragonFly:** Unsupported, no gamepad is ever reported.
+//!
+//! [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+//! [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+//! [`DeviceEvent::Button`]: crate::event::DeviceEvent::Button
+//! [`DeviceEvent::Motion`]: crate::event::DeviceEvent::Motion
+//! [`ActiveEventLoop::listen_device_events`]: crate::event_loop::ActiveEventLoop::listen_device_events
+//! [`ActiveEventLoopExtInputDevices::input_devices`]: crate::platform::input_device::ActiveEventLoopExtInputDevices::input_devices
+//! [`InputDeviceKind::Gamepad`]: crate::platform::input_device::InputDeviceKind::Gamepad
+//! [`InputDeviceKind::Joystick`]: crate::platform::input_device::InputDeviceKind::Joystick
+
+use std::time::Duration;
+
+use crate::error::ExternalError;
+use crate::event::{AxisId, ButtonId, DeviceId};
+use crate::event_loop::{ActiveEventLoop, EventLoopBuilder};
+
+/// The offset of the ids of the buttons and axes outside the standard mapping.
+const OTHER_ID_OFFSET: u32 = 0x1_0000;
+
+/// A button of a gamepad.
+///
+/// The buttons are named after the layout of the standard gamepad, with four face buttons on the
+/// right, a directional pad on the left and two sticks in between.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// The bottom face button, `A` on Xbox controllers.
+    South,
+    /// The right face button, `B` on Xbox controllers.
+    East,
+    /// The top face button, `Y` on Xbox controllers.
+    North,
+    /// The left face button, `X` on Xbox controllers.
+    West,
+    /// The left shoulder button.
+    LeftShoulder,
+    /// The right shoulder button.
+    RightShoulder,
+    /// The left trigger, reported as pressed past the threshold of the device.
+    ///
+    /// The analog value is reported by [`GamepadAxis::LeftTrigger`] when available.
+    LeftTrigger,
+    /// The right trigger, reported as pressed past the threshold of the device.
+    ///
+    /// The analog value is reported by [`GamepadAxis::RightTrigger`] when available.
+    RightTrigger,
+    /// The left center button, `Back` or `View` on Xbox controllers.
+    Select,
+    /// The right center button, `Start` or `Menu` on Xbox controllers.
+    Start,
+    /// The vendor button in the middle, the guide button on Xbox controllers.
+    Mode,
+    /// The left stick being pushed in.
+    LeftStick,
+    /// The right stick being pushed in.
+    RightStick,
+    /// Up on the directional pad.
+    DPadUp,
+    /// Down on the directional pad.
+    DPadDown,
+    /// Left on the directional pad.
+    DPadLeft,
+    /// Right on the directional pad.
+    DPadRight,
+    /// A button outside the standard mapping, with the Linux input event code.
+    ///
+    /// The buttons of the joysticks are all reported this way.
+    Other(u16),
+}
+
+impl GamepadButton {
+    const STANDARD: [Self; 17] = [
+        Self::South,
+        Self::East,
+        Self::North,
+        Self::West,
+        Self::LeftShoulder,
+        Self::RightShoulder,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+        Self::Select,
+        Self::Start,
+        Self::Mode,
+        Self::LeftStick,
+        Self::RightStick,
+        Self::DPadUp,
+        Self::DPadDown,
+        Self::DPadLeft,
+        Self::DPadRight,
+    ];
+
+    /// The button with the given id, as reported by [`DeviceEvent::Button`] for gamepads.
+    ///
+    /// [`DeviceEvent::Button`]: crate::event::DeviceEvent::Button
+    pub fn from_id(id: ButtonId) -> Option<Self> {
+        match id.checked_sub(OTHER_ID_OFFSET) {
+            Some(code) => u16::try_from(code).ok().map(Self::Other),
+            None => Self::STANDARD.get(id as usize).copied(),
+        }
+    }
+
+    /// The id of the button, as reported by [`DeviceEvent::Button`] for gamepads.
+    ///
+    /// [`DeviceEvent::Button`]: crate::event::DeviceEvent::Button
+    pub fn id(self) -> ButtonId {
+        match self {
+            Self::Other(code) => OTHER_ID_OFFSET + code as u32,
+            button => Self::STANDARD.iter().position(|&b| b == button).unwrap() as u32,
+        }
+    }
+}
+
+/// An axis of a gamepad.
+///
+/// The sticks range from `-1.0` to `1.0`, with the positive values being right and down. The
+/// triggers range from `0.0` when released to `1.0` when fully pressed. No dead zone is applied.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    /// The horizontal position of the left stick.
+    LeftStickX,
+    /// The vertical position of the left stick.
+    LeftStickY,
+    /// The horizontal position of the right stick.
+    RightStickX,
+    /// The vertical position of the right stick.
+    RightStickY,
+    /// How far the left trigger is pressed.
+    LeftTrigger,
+    /// How far the right trigger is pressed.
+    RightTrigger,
+    /// An axis outside the standard mapping, with the Linux input event code.
+    ///
+    /// The value ranges from `-1.0` to `1.0`.
+    Other(u16),
+}
+
+impl GamepadAxis {
+    const STANDARD: [Self; 6] = [
+        Self::LeftStickX,
+        Self::LeftStickY,
+        Self::RightStickX,
+        Self::RightStickY,
+        Self::LeftTrigger,
+        Self::RightTrigger,
+    ];
+
+    /// The axis with the given id, as reported by [`DeviceEvent::Motion`] for gamepads.
+    ///
+    /// [`DeviceEvent::Motion`]: crate::event::DeviceEvent::Motion
+    pub fn from_id(id: AxisId) -> Option<Self> {
+        match id.checked_sub(OTHER_ID_OFFSET) {
+            Some(code) => u16::try_from(code).ok().map(Self::Other),
+            None => Self::STANDARD.get(id as usize).copied(),
+        }
+    }
+
+    /// The id of the axis, as reported by [`DeviceEvent::Motion`] for gamepads.
+    ///
+    /// [`DeviceEvent::Motion`]: crate::event::DeviceEvent::Motion
+    pub fn id(self) -> AxisId {
+        match self {
+            Self::Other(code) => OTHER_ID_OFFSET + code as u32,
+            axis => Self::STANDARD.iter().position(|&a| a == axis).unwrap() as u32,
+        }
+    }
+}
+
+/// Additional methods on [`EventLoopBuilder`] to read the gamepads.
+pub trait EventLoopBuilderExtGamepad {
+    /// Whether to read the gamepads and joysticks.
+    ///
+    /// This is disabled by default, since every evdev device node has to be opened to find them.
+    fn with_gamepads(&mut self, gamepads: bool) -> &mut Self;
+}
+
+impl<T> EventLoopBuilderExtGamepad for EventLoopBuilder<T> {
+    #[inline]
+    fn with_gamepads(&mut self, gamepads: bool) -> &mut Self {
+        self.platform_specific.gamepads = gamepads;
+        self
+    }
+}
+
+/// Additional methods on [`ActiveEventLoop`] to drive the gamepads.
+pub trait ActiveEventLoopExtGamepad {
+    /// Rumble the gamepad for the given duration.
+    ///
+    /// The `strong` and `weak` magnitudes range from `0.0` to `1.0`, and drive the low and high
+    /// frequency motors respectively. The previous rumble of the gamepad is replaced, thus it
+    /// could be stopped early by passing zero magnitudes. The duration is capped at about a
+    /// minute.
+    ///
+    /// An error is returned when the device isn't a gamepad with force feedback, see
+    /// [`InputDeviceCapabilities::FORCE_FEEDBACK`].
+    ///
+    /// [`InputDeviceCapabilities::FORCE_FEEDBACK`]: crate::platform::input_device::InputDeviceCapabilities::FORCE_FEEDBACK
+    fn set_rumble(
+        &self,
+        device_id: DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError>;
+}
+
+impl ActiveEventLoopExtGamepad for ActiveEventLoop {
+    #[inline]
+    fn set_rumble(
+        &self,
+        device_id: DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        self.p.set_rumble(device_id, strong, weak, duration)
+    }
+}
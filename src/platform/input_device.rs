@@ -0,0 +1,134 @@
+//! Enumerate the input devices.
+//!
+//! The [`DeviceId`] carried by the events is opaque, and [`DeviceEvent::Added`] and
+//! [`DeviceEvent::Removed`] only tell that the set of devices changed. With
+//! [`ActiveEventLoopExtInputDevices::input_devices`] the devices could be listed alongside their
+//! name, [`InputDeviceKind`], vendor and product ids and [`InputDeviceCapabilities`], to tell the
+//! users which device is which or to ignore the events of a misbehaving device.
+//!
+//! ## Platform-specific
+//!
+//! - **X11:** Every XInput device is listed, including the virtual core pointer and keyboard the
+//!   window events are attributed to, and the physical devices the device events are attributed
+//!   to. The vendor and product ids are read from the `Device Product ID` property, set by the
+//!   `libinput` and `evdev` drivers.
+//! - **Wayland:** The compositor doesn't expose the physical devices, each capability of each
+//!   seat is listed as a device named after the seat instead. The devices of a seat share the
+//!   same [`DeviceId`], and the vendor and product ids are never available.
+//! - **X11 / Wayland:** The gamepads read from the evdev device nodes are listed as well once
+//!   enabled, see the [`gamepad`](crate::platform::gamepad) module.
+//!
+//! [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+//! [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+
+use crate::event::DeviceId;
+use crate::event_loop::ActiveEventLoop;
+
+/// The kind of an input device.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDeviceKind {
+    /// A keyboard.
+    Keyboard,
+    /// A mouse, trackball or other device moving the pointer.
+    Pointer,
+    /// A touchpad.
+    Touchpad,
+    /// A touchscreen.
+    Touchscreen,
+    /// A graphics tablet or other device moving the pointer to absolute positions.
+    Tablet,
+    /// A gamepad, see the [`gamepad`](crate::platform::gamepad) module.
+    Gamepad,
+    /// A joystick, wheel or other game controller without the layout of a gamepad.
+    Joystick,
+}
+
+bitflags::bitflags! {
+    /// The input the device reports.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct InputDeviceCapabilities: u32 {
+        /// Key presses.
+        const KEYS = 1 << 0;
+        /// Button presses, like the ones of a mouse or a gamepad.
+        const BUTTONS = 1 << 1;
+        /// Relative motion, like a mouse moving.
+        const RELATIVE_MOTION = 1 << 2;
+        /// Absolute positions, like a pen on a graphics tablet or the sticks of a gamepad.
+        const ABSOLUTE_MOTION = 1 << 3;
+        /// Scrolling.
+        const SCROLL = 1 << 4;
+        /// Touch points.
+        const TOUCH = 1 << 5;
+        /// Force feedback, like the rumble of a gamepad.
+        const FORCE_FEEDBACK = 1 << 6;
+    }
+}
+
+/// Information about an input device.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InputDevice {
+    pub(crate) id: DeviceId,
+    pub(crate) name: String,
+    pub(crate) kind: InputDeviceKind,
+    pub(crate) vendor_id: Option<u16>,
+    pub(crate) product_id: Option<u16>,
+    pub(crate) capabilities: InputDeviceCapabilities,
+}
+
+impl InputDevice {
+    /// The id the events of the device are attributed to.
+    #[inline]
+    pub fn id(&self) -> DeviceId {
+        self.id
+    }
+
+    /// The human readable name of the device.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The kind of the device.
+    #[inline]
+    pub fn kind(&self) -> InputDeviceKind {
+        self.kind
+    }
+
+    /// The USB or Bluetooth vendor id of the device, when known.
+    #[inline]
+    pub fn vendor_id(&self) -> Option<u16> {
+        self.vendor_id
+    }
+
+    /// The USB or Bluetooth product id of the device, when known.
+    #[inline]
+    pub fn product_id(&self) -> Option<u16> {
+        self.product_id
+    }
+
+    /// The input reported by the device.
+    #[inline]
+    pub fn capabilities(&self) -> InputDeviceCapabilities {
+        self.capabilities
+    }
+}
+
+/// Additional methods on [`ActiveEventLoop`] to enumerate the input devices.
+pub trait ActiveEventLoopExtInputDevices {
+    /// The input devices currently available.
+    ///
+    /// The list is queried each time, thus should be refreshed after [`DeviceEvent::Added`] and
+    /// [`DeviceEvent::Removed`].
+    ///
+    /// [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+    /// [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+    fn input_devices(&self) -> Vec<InputDevice>;
+}
+
+impl ActiveEventLoopExtInputDevices for ActiveEventLoop {
+    #[inline]
+    fn input_devices(&self) -> Vec<InputDevice> {
+        self.p.input_devices()
+    }
+}
@@ -4,6 +4,18 @@
 
 #[cfg(any(android_platform, docsrs))]
 pub mod android;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod backend;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod event_stream;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod fd_source;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod foreign_window;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod gamepad;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod input_device;
 #[cfg(any(ios_platform, docsrs))]
 pub mod ios;
 #[cfg(any(macos_platform, docsrs))]
@@ -11,6 +23,8 @@ pub mod macos;
 #[cfg(any(orbital_platform, docsrs))]
 pub mod orbital;
 #[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod seat;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
 pub mod startup_notify;
 #[cfg(any(wayland_platform, docsrs))]
 pub mod wayland;
@@ -20,6 +34,8 @@ pub mod web;
 pub mod windows;
 #[cfg(any(x11_platform, docsrs))]
 pub mod x11;
+#[cfg(any(x11_platform, wayland_platform, docsrs))]
+pub mod xcursor;
 
 #[cfg(any(
     windows_platform,
@@ -0,0 +1,185 @@
+//! Tell apart the seats using the application at the same time.
+//!
+//! A seat is a group of input devices operated by one user, with its own pointer, keyboard focus
+//! and input method. Most systems only have a single seat, but a compositor could expose several
+//! of them, and X11 could have several master pointers with Multi-Pointer X. By default winit
+//! mixes all the seats together, which is fine for a single user but not for several users
+//! sharing the screen, like on a collaborative whiteboard.
+//!
+//! The seats are listed with [`ActiveEventLoopExtSeat::seats`], and the seat an input event comes
+//! from is found from the [`DeviceId`] of the event with [`ActiveEventLoopExtSeat::device_seat`].
+//! With [`WindowExtSeat`], the cursor icon, the cursor grab and the IME state of the window could
+//! then be set for a single seat, overriding the state set for the whole window.
+//!
+//! ```no_run
+//! use winit::event::DeviceId;
+//! use winit::event_loop::ActiveEventLoop;
+//! use winit::platform::seat::{ActiveEventLoopExtSeat, WindowExtSeat};
+//! use winit::window::{CursorIcon, Window};
+//!
+//! // Show the pen to the user who picked it, from the device of the click.
+//! fn pick_pen(elwt: &ActiveEventLoop, window: &Window, device_id: DeviceId) {
+//!     if let Some(seat) = elwt.device_seat(device_id) {
+//!         window.set_seat_cursor(seat, CursorIcon::Crosshair);
+//!     }
+//! }
+//! ```
+//!
+//! ## Platform-specific
+//!
+//! - **X11:** The seats are the master pointers, the master keyboards belonging to the seat of the
+//!   pointer they're paired with. The keyboard input is delivered through the core protocol, thus
+//!   attributed to the master keyboard of the latest raw key event while device events are
+//!   listened to, and to the master keyboard focusing the window otherwise. The IME isn't tied to
+//!   a seat, the IME methods of [`WindowExtSeat`] apply to all of them.
+//! - **Wayland:** The seats are the `wl_seat` globals, the events of each seat are attributed to a
+//!   distinct [`DeviceId`].
+//! - **X11 / Wayland:** The gamepads don't belong to any seat.
+
+use crate::dpi::{Position, Size};
+use crate::error::ExternalError;
+use crate::event::DeviceId;
+use crate::event_loop::ActiveEventLoop;
+use crate::window::{CursorGrabMode, CursorIcon, Window};
+
+/// The opaque identifier of a seat.
+///
+/// The identifier is unique among the seats present at the same time, but could be reused after
+/// the seat is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SeatId(pub(crate) u32);
+
+/// Information about a seat.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Seat {
+    pub(crate) id: SeatId,
+    pub(crate) name: String,
+}
+
+impl Seat {
+    /// The identifier of the seat.
+    #[inline]
+    pub fn id(&self) -> SeatId {
+        self.id
+    }
+
+    /// The human readable name of the seat.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Additional methods on [`ActiveEventLoop`] to enumerate the seats.
+pub trait ActiveEventLoopExtSeat {
+    /// The seats currently available.
+    ///
+    /// The list is queried each time, thus should be refreshed after [`DeviceEvent::Added`] and
+    /// [`DeviceEvent::Removed`].
+    ///
+    /// [`DeviceEvent::Added`]: crate::event::DeviceEvent::Added
+    /// [`DeviceEvent::Removed`]: crate::event::DeviceEvent::Removed
+    fn seats(&self) -> Vec<Seat>;
+
+    /// The seat the device belongs to.
+    ///
+    /// Returns `None` for devices which aren't part of a seat, like gamepads or floating X11
+    /// devices, and for devices which were removed.
+    fn device_seat(&self, device_id: DeviceId) -> Option<SeatId>;
+}
+
+impl ActiveEventLoopExtSeat for ActiveEventLoop {
+    #[inline]
+    fn seats(&self) -> Vec<Seat> {
+        self.p.seats()
+    }
+
+    #[inline]
+    fn device_seat(&self, device_id: DeviceId) -> Option<SeatId> {
+        self.p.device_seat(device_id)
+    }
+}
+
+/// Additional methods on [`Window`] to set the input state of a single seat.
+///
+/// Once set for a seat, the state is no longer affected by the methods of [`Window`] setting it
+/// for the whole window, until [`WindowExtSeat::reset_seat`] is called.
+pub trait WindowExtSeat {
+    /// Set the cursor icon shown while the pointer of the seat is over the window.
+    ///
+    /// Like [`Window::set_cursor`], the cursor is hidden by [`Window::set_cursor_visible`].
+    fn set_seat_cursor(&self, seat: SeatId, cursor: CursorIcon);
+
+    /// Set the cursor grab of the pointer of the seat.
+    ///
+    /// See [`Window::set_cursor_grab`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** [`CursorGrabMode::Locked`] is unsupported, and the seats can't be grabbed while
+    ///   the cursor of the window is locked.
+    fn set_seat_cursor_grab(&self, seat: SeatId, mode: CursorGrabMode)
+        -> Result<(), ExternalError>;
+
+    /// Set whether the IME of the seat is allowed on the window.
+    ///
+    /// See [`Window::set_ime_allowed`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The IME isn't tied to a seat, this is the same as [`Window::set_ime_allowed`].
+    fn set_seat_ime_allowed(&self, seat: SeatId, allowed: bool);
+
+    /// Set the area the IME candidate window of the seat should avoid.
+    ///
+    /// See [`Window::set_ime_cursor_area`].
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** The IME isn't tied to a seat, this is the same as
+    ///   [`Window::set_ime_cursor_area`].
+    fn set_seat_ime_cursor_area(&self, seat: SeatId, position: Position, size: Size);
+
+    /// Reset the state of the seat to the one set for the whole window.
+    ///
+    /// The cursor icon, the cursor grab and the IME state set with the other methods of this
+    /// trait follow the methods of [`Window`] again.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** The IME cursor area of the seat is only updated on the next call to
+    ///   [`Window::set_ime_cursor_area`].
+    /// - **X11:** The IME state isn't tied to a seat, thus is left as is.
+    fn reset_seat(&self, seat: SeatId);
+}
+
+impl WindowExtSeat for Window {
+    #[inline]
+    fn set_seat_cursor(&self, seat: SeatId, cursor: CursorIcon) {
+        self.window.set_seat_cursor(seat, cursor)
+    }
+
+    #[inline]
+    fn set_seat_cursor_grab(
+        &self,
+        seat: SeatId,
+        mode: CursorGrabMode,
+    ) -> Result<(), ExternalError> {
+        self.window.set_seat_cursor_grab(seat, mode)
+    }
+
+    #[inline]
+    fn set_seat_ime_allowed(&self, seat: SeatId, allowed: bool) {
+        self.window.set_seat_ime_allowed(seat, allowed)
+    }
+
+    #[inline]
+    fn set_seat_ime_cursor_area(&self, seat: SeatId, position: Position, size: Size) {
+        self.window.set_seat_ime_cursor_area(seat, position, size)
+    }
+
+    #[inline]
+    fn reset_seat(&self, seat: SeatId) {
+        self.window.reset_seat(seat)
+    }
+}
@@ -16,6 +16,7 @@
 use crate::{
     event_loop::{ActiveEventLoop, EventLoopBuilder},
     monitor::MonitorHandle,
+    platform::backend::Backend,
     window::{Window, WindowAttributes},
 };
 
@@ -49,7 +50,9 @@ pub trait EventLoopBuilderExtWayland {
 impl<T> EventLoopBuilderExtWayland for EventLoopBuilder<T> {
     #[inline]
     fn with_wayland(&mut self) -> &mut Self {
-        self.platform_specific.forced_backend = Some(crate::platform_impl::Backend::Wayland);
+        self.platform_specific.backend_preference = Some(
+            crate::platform_impl::BackendPreference::forced(Backend::Wayland),
+        );
         self
     }
 
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     event_loop::{ActiveEventLoop, EventLoopBuilder},
     monitor::MonitorHandle,
+    platform::backend::Backend,
     window::{Window, WindowAttributes},
 };
 
@@ -54,6 +55,42 @@ pub enum WindowType {
     Normal,
 }
 
+/// A virtual desktop, also called workspace, of the window manager.
+///
+/// See [`WindowExtX11::set_desktop`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Desktop {
+    /// The desktop with the given index, starting from `0`.
+    Index(u32),
+    /// All the desktops, the window being sticky.
+    All,
+}
+
+bitflags::bitflags! {
+    /// The `_NET_WM_STATE` hints of a window not covered by [`WindowState`].
+    ///
+    /// See [`WindowExtX11::set_net_wm_state`].
+    ///
+    /// [`WindowState`]: crate::window::WindowState
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct NetWmState: u32 {
+        /// The window isn't shown in the taskbar, `_NET_WM_STATE_SKIP_TASKBAR`.
+        const SKIP_TASKBAR = 1 << 0;
+        /// The window isn't shown in the pager nor when switching windows,
+        /// `_NET_WM_STATE_SKIP_PAGER`.
+        const SKIP_PAGER = 1 << 1;
+        /// The window is shown on all the desktops, `_NET_WM_STATE_STICKY`.
+        const STICKY = 1 << 2;
+        /// The window is rolled up to its title bar, `_NET_WM_STATE_SHADED`.
+        const SHADED = 1 << 3;
+        /// The window needs the attention of the user, `_NET_WM_STATE_DEMANDS_ATTENTION`.
+        const DEMANDS_ATTENTION = 1 << 4;
+        /// The window is a modal dialog of its transient parent, `_NET_WM_STATE_MODAL`.
+        const MODAL = 1 << 5;
+    }
+}
+
 /// The first argument in the provided hook will be the pointer to `XDisplay`
 /// and the second one the pointer to [`XErrorEvent`]. The returned `bool` is an
 /// indicator whether the error was handled by the callback.
@@ -94,6 +131,23 @@ pub fn register_xlib_error_hook(hook: XlibErrorHook) {
 pub trait ActiveEventLoopExtX11 {
     /// True if the [`ActiveEventLoop`] uses X11.
     fn is_x11(&self) -> bool;
+
+    /// The number of desktops of the window manager, from `_NET_NUMBER_OF_DESKTOPS`.
+    ///
+    /// Returns `None` when the window manager doesn't support desktops, or on Wayland.
+    fn desktop_count(&self) -> Option<u32>;
+
+    /// The names of the desktops, from `_NET_DESKTOP_NAMES`.
+    ///
+    /// There may be fewer names than desktops.
+    fn desktop_names(&self) -> Vec<String>;
+
+    /// The index of the desktop currently shown, from `_NET_CURRENT_DESKTOP`.
+    ///
+    /// [`WindowEvent::DesktopChanged`] is sent to every window when it changes.
+    ///
+    /// [`WindowEvent::DesktopChanged`]: crate::event::WindowEvent::DesktopChanged
+    fn current_desktop(&self) -> Option<u32>;
 }
 
 impl ActiveEventLoopExtX11 for ActiveEventLoop {
@@ -101,6 +155,21 @@ impl ActiveEventLoopExtX11 for ActiveEventLoop {
     fn is_x11(&self) -> bool {
         !self.p.is_wayland()
     }
+
+    #[inline]
+    fn desktop_count(&self) -> Option<u32> {
+        self.p.desktop_count()
+    }
+
+    #[inline]
+    fn desktop_names(&self) -> Vec<String> {
+        self.p.desktop_names()
+    }
+
+    #[inline]
+    fn current_desktop(&self) -> Option<u32> {
+        self.p.current_desktop()
+    }
 }
 
 /// Additional methods on [`EventLoopBuilder`] that are specific to X11.
@@ -118,7 +187,9 @@ pub trait EventLoopBuilderExtX11 {
 impl<T> EventLoopBuilderExtX11 for EventLoopBuilder<T> {
     #[inline]
     fn with_x11(&mut self) -> &mut Self {
-        self.platform_specific.forced_backend = Some(crate::platform_impl::Backend::X);
+        self.platform_specific.backend_preference = Some(
+            crate::platform_impl::BackendPreference::forced(Backend::X11),
+        );
         self
     }
 
@@ -130,9 +201,54 @@ impl<T> EventLoopBuilderExtX11 for EventLoopBuilder<T> {
 }
 
 /// Additional methods on [`Window`] that are specific to X11.
-pub trait WindowExtX11 {}
+pub trait WindowExtX11 {
+    /// The desktop the window is on, from `_NET_WM_DESKTOP`.
+    ///
+    /// Returns `None` when the window manager didn't place the window on a desktop yet, or on
+    /// Wayland.
+    fn desktop(&self) -> Option<Desktop>;
 
-impl WindowExtX11 for Window {}
+    /// Move the window to another desktop.
+    ///
+    /// [`WindowEvent::DesktopChanged`] is sent once the window manager moved it. This is a no-op
+    /// on Wayland.
+    ///
+    /// [`WindowEvent::DesktopChanged`]: crate::event::WindowEvent::DesktopChanged
+    fn set_desktop(&self, desktop: Desktop);
+
+    /// The `_NET_WM_STATE` hints of the window.
+    ///
+    /// Returns an empty set on Wayland.
+    fn net_wm_state(&self) -> NetWmState;
+
+    /// Add the given `_NET_WM_STATE` hints to the window, or remove them when `enabled` is
+    /// `false`, leaving the other hints alone.
+    ///
+    /// This is a no-op on Wayland.
+    fn set_net_wm_state(&self, state: NetWmState, enabled: bool);
+}
+
+impl WindowExtX11 for Window {
+    #[inline]
+    fn desktop(&self) -> Option<Desktop> {
+        self.window.desktop()
+    }
+
+    #[inline]
+    fn set_desktop(&self, desktop: Desktop) {
+        self.window.set_desktop(desktop)
+    }
+
+    #[inline]
+    fn net_wm_state(&self) -> NetWmState {
+        self.window.net_wm_state()
+    }
+
+    #[inline]
+    fn set_net_wm_state(&self, state: NetWmState, enabled: bool) {
+        self.window.set_net_wm_state(state, enabled)
+    }
+}
 
 /// Additional methods on [`WindowAttributes`] that are specific to X11.
 pub trait WindowAttributesExtX11 {
@@ -185,6 +301,22 @@ pub trait WindowAttributesExtX11 {
     /// # Ok(()) }
     /// ```
     fn with_embed_parent_window(self, parent_window_id: XWindow) -> Self;
+
+    /// Build window on the given desktop, instead of the current one.
+    ///
+    /// See [`WindowExtX11::set_desktop`] for details.
+    fn with_desktop(self, desktop: Desktop) -> Self;
+
+    /// Build window with the given `_NET_WM_STATE` hints; defaults to none.
+    ///
+    /// ```
+    /// # use winit::window::Window;
+    /// # use winit::platform::x11::{NetWmState, WindowAttributesExtX11};
+    /// // A tool palette not shown in the taskbar nor when switching windows.
+    /// Window::default_attributes()
+    ///     .with_net_wm_state(NetWmState::SKIP_TASKBAR | NetWmState::SKIP_PAGER);
+    /// ```
+    fn with_net_wm_state(self, state: NetWmState) -> Self;
 }
 
 impl WindowAttributesExtX11 for WindowAttributes {
@@ -232,6 +364,18 @@ impl WindowAttributesExtX11 for WindowAttributes {
         self.platform_specific.x11.embed_window = Some(parent_window_id);
         self
     }
+
+    #[inline]
+    fn with_desktop(mut self, desktop: Desktop) -> Self {
+        self.platform_specific.x11.desktop = Some(desktop);
+        self
+    }
+
+    #[inline]
+    fn with_net_wm_state(mut self, state: NetWmState) -> Self {
+        self.platform_specific.x11.net_wm_state = state;
+        self
+    }
 }
 
 /// Additional methods on `MonitorHandle` that are specific to X11.
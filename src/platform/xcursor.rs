@@ -0,0 +1,211 @@
+//! Load cursors from XCursor files and themes.
+//!
+//! XCursor is the cursor format used by X11 and most Wayland compositors. A cursor file holds the
+//! images of the cursor at several nominal sizes, each of them possibly animated. The cursors
+//! loaded here keep all of their sizes, the one fitting the scale factor of the window best being
+//! shown once set with [`Window::set_cursor`] or [`WindowAttributes::with_cursor`].
+//!
+//! ```no_run
+//! use winit::event_loop::ActiveEventLoop;
+//! use winit::platform::xcursor::CustomCursorExtXCursor;
+//! use winit::window::{CustomCursor, Window};
+//!
+//! fn use_pencil(event_loop: &ActiveEventLoop, window: &Window) {
+//!     let source = CustomCursor::from_xcursor_theme("MyTheme", "pencil", 24).unwrap();
+//!     window.set_cursor(event_loop.create_custom_cursor(source));
+//! }
+//! ```
+//!
+//! [`Window::set_cursor`]: crate::window::Window::set_cursor
+//! [`WindowAttributes::with_cursor`]: crate::window::WindowAttributes::with_cursor
+
+use std::error::Error;
+use std::path::Path;
+use std::time::Duration;
+use std::{fmt, fs, io};
+
+use crate::cursor::{CursorVariant, CustomCursorFrame};
+use crate::platform_impl::PlatformCustomCursorSource;
+use crate::window::{BadImage, CustomCursor, CustomCursorSource};
+
+/// Additional methods on [`CustomCursor`] to load XCursor files.
+pub trait CustomCursorExtXCursor {
+    /// Load a cursor from the content of an XCursor file.
+    ///
+    /// The `size` is the nominal size the cursor is shown at with a scale factor of `1.0`, `24`
+    /// being the most common one. When the file doesn't have the exact size, the closest larger
+    /// one is used, or the largest one.
+    fn from_xcursor(data: &[u8], size: u32) -> Result<CustomCursorSource, XCursorError>;
+
+    /// Load a cursor from an XCursor file.
+    ///
+    /// See [`CustomCursorExtXCursor::from_xcursor`] for the `size`.
+    fn from_xcursor_file(
+        path: impl AsRef<Path>,
+        size: u32,
+    ) -> Result<CustomCursorSource, XCursorError>;
+
+    /// Load the cursor named `name` from the cursor theme named `theme`.
+    ///
+    /// The theme is searched for in the directories of `XCURSOR_PATH`, or in the standard icon
+    /// directories when unset, falling back to the themes it inherits from.
+    ///
+    /// See [`CustomCursorExtXCursor::from_xcursor`] for the `size`.
+    fn from_xcursor_theme(
+        theme: &str,
+        name: &str,
+        size: u32,
+    ) -> Result<CustomCursorSource, XCursorError>;
+}
+
+impl CustomCursorExtXCursor for CustomCursor {
+    fn from_xcursor(data: &[u8], size: u32) -> Result<CustomCursorSource, XCursorError> {
+        let images = xcursor::parser::parse_xcursor(data).ok_or(XCursorError::InvalidFile)?;
+
+        // The frames of an animation follow each other in the file.
+        let mut variants: Vec<CursorVariant> = Vec::new();
+        for image in images {
+            let frame = CustomCursorFrame::from_rgba(
+                to_straight_rgba(&image.pixels_rgba),
+                image.width as u16,
+                image.height as u16,
+                image.xhot as u16,
+                image.yhot as u16,
+                Duration::from_millis(image.delay as u64),
+            )
+            .map_err(XCursorError::BadImage)?;
+
+            match variants
+                .iter_mut()
+                .find(|variant| variant.nominal_size == image.size)
+            {
+                Some(variant) => variant.frames.push(frame),
+                None => variants.push(CursorVariant {
+                    nominal_size: image.size,
+                    frames: vec![frame],
+                }),
+            }
+        }
+
+        if variants.is_empty() {
+            return Err(XCursorError::InvalidFile);
+        }
+
+        variants.sort_by_key(|variant| variant.nominal_size);
+
+        Ok(CustomCursorSource {
+            inner: PlatformCustomCursorSource { size, variants },
+        })
+    }
+
+    fn from_xcursor_file(
+        path: impl AsRef<Path>,
+        size: u32,
+    ) -> Result<CustomCursorSource, XCursorError> {
+        let data = fs::read(path).map_err(XCursorError::Io)?;
+        Self::from_xcursor(&data, size)
+    }
+
+    fn from_xcursor_theme(
+        theme: &str,
+        name: &str,
+        size: u32,
+    ) -> Result<CustomCursorSource, XCursorError> {
+        let path = xcursor::CursorTheme::load(theme)
+            .load_icon(name)
+            .ok_or(XCursorError::NotFound)?;
+        Self::from_xcursor_file(path, size)
+    }
+}
+
+/// Convert the premultiplied little-endian ARGB pixels of XCursor to straight RGBA.
+fn to_straight_rgba(pixels: &[u8]) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(pixels.len());
+    for pixel in pixels.chunks_exact(4) {
+        let alpha = pixel[3];
+        let unpremultiply = |color: u8| match alpha {
+            0 => 0,
+            255 => color,
+            _ => (color as u32 * 255 / alpha as u32).min(255) as u8,
+        };
+        rgba.extend_from_slice(&[
+            unpremultiply(pixel[2]),
+            unpremultiply(pixel[1]),
+            unpremultiply(pixel[0]),
+            alpha,
+        ]);
+    }
+    rgba
+}
+
+/// An error produced when loading an XCursor file.
+#[derive(Debug)]
+pub enum XCursorError {
+    /// The cursor wasn't found in the theme.
+    NotFound,
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// The file isn't a valid XCursor file, or doesn't have any image.
+    InvalidFile,
+    /// An image of the file is unsuitable for a cursor.
+    BadImage(BadImage),
+}
+
+impl fmt::Display for XCursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => write!(f, "The cursor wasn't found in the theme."),
+            Self::Io(err) => write!(f, "Failed to read the cursor file: {err}"),
+            Self::InvalidFile => write!(f, "The cursor file is invalid."),
+            Self::BadImage(err) => write!(f, "The cursor image is invalid: {err}"),
+        }
+    }
+}
+
+impl Error for XCursorError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::BadImage(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_rgba_from_argb() {
+        // Opaque pixels are swizzled from BGRA as is.
+        assert_eq!(
+            to_straight_rgba(&[0x10, 0x20, 0x30, 0xff]),
+            [0x30, 0x20, 0x10, 0xff]
+        );
+        // Transparent pixels lose their color.
+        assert_eq!(to_straight_rgba(&[0x10, 0x20, 0x30, 0x00]), [0, 0, 0, 0]);
+        // Translucent pixels are unpremultiplied.
+        assert_eq!(
+            to_straight_rgba(&[0x00, 0x40, 0x80, 0x80]),
+            [0xff, 0x7f, 0x00, 0x80]
+        );
+    }
+
+    #[test]
+    fn straight_rgba_clamps_invalid_premultiplication() {
+        assert_eq!(
+            to_straight_rgba(&[0x00, 0x00, 0xff, 0x80]),
+            [0xff, 0x00, 0x00, 0x80]
+        );
+    }
+
+    #[test]
+    fn straight_rgba_ignores_trailing_bytes() {
+        let pixels = [0x01, 0x02, 0x03, 0xff, 0x04, 0x05, 0x06, 0xff, 0x07];
+        assert_eq!(
+            to_straight_rgba(&pixels),
+            [0x03, 0x02, 0x01, 0xff, 0x06, 0x05, 0x04, 0xff]
+        );
+    }
+}
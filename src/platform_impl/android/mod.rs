@@ -427,6 +427,7 @@ impl<T: 'static> EventLoop<T> {
                                 location,
                                 id: pointer.pointer_id() as u64,
                                 force: Some(Force::Normalized(pointer.pressure() as f64)),
+                                timestamp: None,
                             }),
                         };
                         callback(event, self.window_target());
@@ -468,6 +469,7 @@ impl<T: 'static> EventLoop<T> {
                                     repeat: key.repeat_count() > 0,
                                     text: None,
                                     platform_specific: KeyEventExtra {},
+                                    timestamp: None,
                                 },
                                 is_synthetic: false,
                             },
@@ -880,6 +882,14 @@ impl Window {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_opacity(&self, _opacity: f32) {}
+
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+
+    pub fn set_opaque_region(&self, _region: Option<window::OpaqueRegion>) {}
+
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {}
+
     pub fn set_visible(&self, _visibility: bool) {}
 
     pub fn is_visible(&self) -> Option<bool> {
@@ -978,6 +988,24 @@ impl Window {
         ))
     }
 
+    pub fn set_input_region(
+        &self,
+        _region: Option<window::WindowRegion>,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn set_shape(
+        &self,
+        _shape: Option<window::WindowRegion>,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
     #[cfg(feature = "rwh_04")]
     pub fn raw_window_handle_rwh_04(&self) -> rwh_04::RawWindowHandle {
         use rwh_04::HasRawWindowHandle;
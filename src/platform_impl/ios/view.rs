@@ -382,6 +382,7 @@ impl WinitView {
                     location: physical_location,
                     force,
                     phase,
+                    timestamp: None,
                 }),
             }));
         }
@@ -23,8 +23,8 @@ use crate::{
     platform::ios::{ScreenEdge, StatusBarStyle, ValidOrientations},
     platform_impl::platform::{app_state, monitor, ActiveEventLoop, Fullscreen, MonitorHandle},
     window::{
-        CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-        WindowButtons, WindowId as RootWindowId, WindowLevel,
+        CursorGrabMode, ImePurpose, OpaqueRegion, ResizeDirection, Theme, UserAttentionType,
+        WindowAttributes, WindowButtons, WindowId as RootWindowId, WindowLevel, WindowRegion,
     },
 };
 
@@ -123,6 +123,22 @@ impl Inner {
         debug!("`Window::set_blur` is ignored on iOS")
     }
 
+    pub fn set_opacity(&self, _opacity: f32) {
+        debug!("`Window::set_opacity` is ignored on iOS")
+    }
+
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {
+        debug!("`Window::set_aspect_ratio` is ignored on iOS")
+    }
+
+    pub fn set_opaque_region(&self, _region: Option<OpaqueRegion>) {
+        debug!("`Window::set_opaque_region` is ignored on iOS")
+    }
+
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {
+        debug!("`Window::set_idle_inhibited` is ignored on iOS")
+    }
+
     pub fn set_visible(&self, visible: bool) {
         self.window.setHidden(!visible)
     }
@@ -282,6 +298,14 @@ impl Inner {
         Err(ExternalError::NotSupported(NotSupportedError::new()))
     }
 
+    pub fn set_input_region(&self, _region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     pub fn set_minimized(&self, _minimized: bool) {
         warn!("`Window::set_minimized` is ignored on iOS")
     }
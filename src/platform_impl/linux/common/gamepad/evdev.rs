@@ -0,0 +1,158 @@
+//! Bindings to the evdev interface of the Linux kernel.
+
+use std::ffi::CStr;
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::io::{AsRawFd, BorrowedFd};
+
+pub const EV_SYN: u16 = 0x00;
+pub const EV_KEY: u16 = 0x01;
+pub const EV_ABS: u16 = 0x03;
+pub const EV_FF: u16 = 0x15;
+
+pub const SYN_REPORT: u16 = 0;
+pub const SYN_DROPPED: u16 = 3;
+
+pub const KEY_CNT: usize = 0x300;
+pub const ABS_CNT: usize = 0x40;
+pub const FF_CNT: usize = 0x80;
+
+pub const BTN_JOYSTICK: u16 = 0x120;
+pub const BTN_SOUTH: u16 = 0x130;
+pub const BTN_EAST: u16 = 0x131;
+pub const BTN_NORTH: u16 = 0x133;
+pub const BTN_WEST: u16 = 0x134;
+pub const BTN_TL: u16 = 0x136;
+pub const BTN_TR: u16 = 0x137;
+pub const BTN_TL2: u16 = 0x138;
+pub const BTN_TR2: u16 = 0x139;
+pub const BTN_SELECT: u16 = 0x13a;
+pub const BTN_START: u16 = 0x13b;
+pub const BTN_MODE: u16 = 0x13c;
+pub const BTN_THUMBL: u16 = 0x13d;
+pub const BTN_THUMBR: u16 = 0x13e;
+pub const BTN_DPAD_UP: u16 = 0x220;
+pub const BTN_DPAD_DOWN: u16 = 0x221;
+pub const BTN_DPAD_LEFT: u16 = 0x222;
+pub const BTN_DPAD_RIGHT: u16 = 0x223;
+
+pub const ABS_X: u16 = 0x00;
+pub const ABS_Y: u16 = 0x01;
+pub const ABS_Z: u16 = 0x02;
+pub const ABS_RX: u16 = 0x03;
+pub const ABS_RY: u16 = 0x04;
+pub const ABS_RZ: u16 = 0x05;
+pub const ABS_HAT0X: u16 = 0x10;
+pub const ABS_HAT0Y: u16 = 0x11;
+pub const ABS_HAT2X: u16 = 0x14;
+pub const ABS_HAT2Y: u16 = 0x15;
+
+pub const FF_RUMBLE: u16 = 0x50;
+
+// The encoding of the ioctl requests differs between the architectures.
+#[cfg(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+))]
+mod ioc {
+    pub const WRITE: u32 = 4;
+    pub const READ: u32 = 2;
+    pub const DIR_SHIFT: u32 = 29;
+}
+#[cfg(not(any(
+    target_arch = "mips",
+    target_arch = "mips64",
+    target_arch = "powerpc",
+    target_arch = "powerpc64",
+    target_arch = "sparc",
+    target_arch = "sparc64"
+)))]
+mod ioc {
+    pub const WRITE: u32 = 1;
+    pub const READ: u32 = 2;
+    pub const DIR_SHIFT: u32 = 30;
+}
+
+/// The evdev ioctl request with the given direction, number and argument size.
+const fn ioc(dir: u32, nr: u32, size: usize) -> u32 {
+    (dir << ioc::DIR_SHIFT) | ((size as u32) << 16) | ((b'E' as u32) << 8) | nr
+}
+
+fn ioctl<T>(fd: BorrowedFd<'_>, request: u32, arg: *mut T) -> io::Result<()> {
+    // SAFETY: the requests are built with the size of the argument.
+    if unsafe { libc::ioctl(fd.as_raw_fd(), request as _, arg) } < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether the bit of the given code is set in the bitmask.
+pub fn has_bit(bits: &[u8], code: u16) -> bool {
+    bits.get(code as usize / 8)
+        .is_some_and(|byte| byte & (1 << (code % 8)) != 0)
+}
+
+/// The name of the device.
+pub fn name(fd: BorrowedFd<'_>) -> io::Result<String> {
+    let mut name = [0u8; 256];
+    // The last byte is kept for the nul terminator.
+    ioctl(fd, ioc(ioc::READ, 0x06, name.len() - 1), name.as_mut_ptr())?;
+    let name = CStr::from_bytes_until_nul(&name).unwrap_or_default();
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// The bus, vendor and product of the device.
+pub fn id(fd: BorrowedFd<'_>) -> io::Result<libc::input_id> {
+    let mut id = MaybeUninit::<libc::input_id>::uninit();
+    ioctl(
+        fd,
+        ioc(ioc::READ, 0x02, std::mem::size_of::<libc::input_id>()),
+        id.as_mut_ptr(),
+    )?;
+    // SAFETY: the kernel filled the struct.
+    Ok(unsafe { id.assume_init() })
+}
+
+/// The codes of the given event type reported by the device.
+pub fn bits(fd: BorrowedFd<'_>, ty: u16, bits: &mut [u8]) -> io::Result<()> {
+    ioctl(
+        fd,
+        ioc(ioc::READ, 0x20 + ty as u32, bits.len()),
+        bits.as_mut_ptr(),
+    )
+}
+
+/// The keys and buttons currently pressed.
+pub fn pressed_keys(fd: BorrowedFd<'_>, bits: &mut [u8]) -> io::Result<()> {
+    ioctl(fd, ioc(ioc::READ, 0x18, bits.len()), bits.as_mut_ptr())
+}
+
+/// The range and current value of the absolute axis.
+pub fn abs_info(fd: BorrowedFd<'_>, code: u16) -> io::Result<libc::input_absinfo> {
+    let mut info = MaybeUninit::<libc::input_absinfo>::uninit();
+    ioctl(
+        fd,
+        ioc(
+            ioc::READ,
+            0x40 + code as u32,
+            std::mem::size_of::<libc::input_absinfo>(),
+        ),
+        info.as_mut_ptr(),
+    )?;
+    // SAFETY: the kernel filled the struct.
+    Ok(unsafe { info.assume_init() })
+}
+
+/// Upload the force feedback effect, the kernel assigns its id when it's `-1`.
+pub fn upload_effect(fd: BorrowedFd<'_>, effect: &mut libc::ff_effect) -> io::Result<()> {
+    ioctl(
+        fd,
+        ioc(ioc::WRITE, 0x80, std::mem::size_of::<libc::ff_effect>()),
+        effect,
+    )
+}
@@ -0,0 +1,384 @@
+//! Translation of the evdev events to the standard gamepad mapping.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Read};
+use std::{mem, ptr};
+
+use crate::event::{DeviceEvent, ElementState};
+use crate::platform::gamepad::{GamepadAxis, GamepadButton};
+
+use super::evdev::*;
+
+/// The size of the events read from the device.
+const EVENT_SIZE: usize = mem::size_of::<libc::input_event>();
+
+/// The range of an absolute axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbsRange {
+    pub min: i32,
+    pub max: i32,
+}
+
+impl AbsRange {
+    /// Map the value to `-1.0..=1.0` when `centered`, to `0.0..=1.0` otherwise.
+    fn normalize(self, value: i32, centered: bool) -> f64 {
+        if self.max <= self.min {
+            return 0.0;
+        }
+
+        let value = value.clamp(self.min, self.max);
+        let unit = (value as f64 - self.min as f64) / (self.max as f64 - self.min as f64);
+        if centered {
+            unit * 2.0 - 1.0
+        } else {
+            unit
+        }
+    }
+}
+
+/// The state of the device, queried to recover once the kernel dropped events.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeviceState {
+    /// The keys and buttons pressed.
+    pub keys: Vec<u16>,
+    /// The values of the absolute axes.
+    pub axes: Vec<(u16, i32)>,
+}
+
+/// What an absolute axis is mapped to.
+enum AbsTarget {
+    Axis(GamepadAxis, bool),
+    Hat(GamepadButton, GamepadButton),
+}
+
+/// The translation of the events of one device, deduplicating the reported state.
+#[derive(Debug)]
+pub struct Mapping {
+    /// Whether the device has the layout of a gamepad, for the triggers to be mapped.
+    standard: bool,
+    ranges: HashMap<u16, AbsRange>,
+    pressed: HashSet<GamepadButton>,
+    values: HashMap<GamepadAxis, f64>,
+    /// The kernel dropped events, ignore them until the next report.
+    dropped: bool,
+}
+
+impl Mapping {
+    pub fn new(standard: bool, ranges: HashMap<u16, AbsRange>) -> Self {
+        Self {
+            standard,
+            ranges,
+            pressed: HashSet::new(),
+            values: HashMap::new(),
+            dropped: false,
+        }
+    }
+
+    /// The absolute axes of the device.
+    pub fn abs_codes(&self) -> impl Iterator<Item = u16> + '_ {
+        self.ranges.keys().copied()
+    }
+
+    /// Process the raw event, returns whether the state of the device must be passed to
+    /// [`Self::sync`] since events were dropped.
+    pub fn process(&mut self, event: &libc::input_event, out: &mut Vec<DeviceEvent>) -> bool {
+        match (event.type_, event.code) {
+            (EV_SYN, SYN_DROPPED) => {
+                self.dropped = true;
+                false
+            }
+            (EV_SYN, SYN_REPORT) => mem::take(&mut self.dropped),
+            _ if self.dropped => false,
+            // Auto-repeat is reported with the value 2.
+            (EV_KEY, code) => {
+                self.set_button(button(code), event.value != 0, out);
+                false
+            }
+            (EV_ABS, code) => {
+                self.set_abs(code, event.value, out);
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Report the changes to reach the given state of the device.
+    pub fn sync(&mut self, state: &DeviceState, out: &mut Vec<DeviceEvent>) {
+        let mut pressed: HashSet<GamepadButton> =
+            state.keys.iter().map(|&code| button(code)).collect();
+        for &(code, value) in &state.axes {
+            if let Some(AbsTarget::Hat(negative, positive)) = self.abs_target(code) {
+                if value < 0 {
+                    pressed.insert(negative);
+                } else if value > 0 {
+                    pressed.insert(positive);
+                }
+            }
+        }
+
+        let released: Vec<_> = self.pressed.difference(&pressed).copied().collect();
+        for button in released {
+            self.set_button(button, false, out);
+        }
+        for button in pressed {
+            self.set_button(button, true, out);
+        }
+
+        for &(code, value) in &state.axes {
+            if let Some(AbsTarget::Axis(..)) = self.abs_target(code) {
+                self.set_abs(code, value, out);
+            }
+        }
+    }
+
+    fn abs_target(&self, code: u16) -> Option<AbsTarget> {
+        let target = match code {
+            ABS_X => AbsTarget::Axis(GamepadAxis::LeftStickX, true),
+            ABS_Y => AbsTarget::Axis(GamepadAxis::LeftStickY, true),
+            ABS_RX => AbsTarget::Axis(GamepadAxis::RightStickX, true),
+            ABS_RY => AbsTarget::Axis(GamepadAxis::RightStickY, true),
+            ABS_Z | ABS_HAT2Y if self.standard => AbsTarget::Axis(GamepadAxis::LeftTrigger, false),
+            ABS_RZ | ABS_HAT2X if self.standard => {
+                AbsTarget::Axis(GamepadAxis::RightTrigger, false)
+            }
+            ABS_HAT0X => AbsTarget::Hat(GamepadButton::DPadLeft, GamepadButton::DPadRight),
+            ABS_HAT0Y => AbsTarget::Hat(GamepadButton::DPadUp, GamepadButton::DPadDown),
+            code if (code as usize) < ABS_CNT => AbsTarget::Axis(GamepadAxis::Other(code), true),
+            _ => return None,
+        };
+        Some(target)
+    }
+
+    fn set_abs(&mut self, code: u16, value: i32, out: &mut Vec<DeviceEvent>) {
+        match self.abs_target(code) {
+            Some(AbsTarget::Axis(axis, centered)) => {
+                let value = match self.ranges.get(&code) {
+                    Some(range) => range.normalize(value, centered),
+                    None => return,
+                };
+                if self.values.insert(axis, value) != Some(value) {
+                    out.push(DeviceEvent::Motion {
+                        axis: axis.id(),
+                        value,
+                    });
+                }
+            }
+            // The hats are digital on gamepads, the directional pad.
+            Some(AbsTarget::Hat(negative, positive)) => {
+                self.set_button(negative, value < 0, out);
+                self.set_button(positive, value > 0, out);
+            }
+            None => (),
+        }
+    }
+
+    fn set_button(&mut self, button: GamepadButton, pressed: bool, out: &mut Vec<DeviceEvent>) {
+        let changed = if pressed {
+            self.pressed.insert(button)
+        } else {
+            self.pressed.remove(&button)
+        };
+
+        if changed {
+            let state = if pressed {
+                ElementState::Pressed
+            } else {
+                ElementState::Released
+            };
+            out.push(DeviceEvent::Button {
+                button: button.id(),
+                state,
+            });
+        }
+    }
+}
+
+/// The button of the given key code.
+fn button(code: u16) -> GamepadButton {
+    match code {
+        BTN_SOUTH => GamepadButton::South,
+        BTN_EAST => GamepadButton::East,
+        BTN_NORTH => GamepadButton::North,
+        BTN_WEST => GamepadButton::West,
+        BTN_TL => GamepadButton::LeftShoulder,
+        BTN_TR => GamepadButton::RightShoulder,
+        BTN_TL2 => GamepadButton::LeftTrigger,
+        BTN_TR2 => GamepadButton::RightTrigger,
+        BTN_SELECT => GamepadButton::Select,
+        BTN_START => GamepadButton::Start,
+        BTN_MODE => GamepadButton::Mode,
+        BTN_THUMBL => GamepadButton::LeftStick,
+        BTN_THUMBR => GamepadButton::RightStick,
+        BTN_DPAD_UP => GamepadButton::DPadUp,
+        BTN_DPAD_DOWN => GamepadButton::DPadDown,
+        BTN_DPAD_LEFT => GamepadButton::DPadLeft,
+        BTN_DPAD_RIGHT => GamepadButton::DPadRight,
+        code => GamepadButton::Other(code),
+    }
+}
+
+/// Read and translate the events from the device until it would block or the stream ends.
+///
+/// Returns whether the state of the device must be passed to [`Mapping::sync`].
+pub fn read_events(
+    reader: &mut impl Read,
+    mapping: &mut Mapping,
+    out: &mut Vec<DeviceEvent>,
+) -> io::Result<bool> {
+    let mut buffer = [0u8; 64 * EVENT_SIZE];
+    let mut needs_sync = false;
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(needs_sync),
+            Ok(len) => len,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => return Ok(needs_sync),
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+
+        // The device only returns whole events.
+        for chunk in buffer[..len].chunks_exact(EVENT_SIZE) {
+            // SAFETY: the chunk has the size of the event, which is plain old data.
+            let event = unsafe { ptr::read_unaligned(chunk.as_ptr().cast::<libc::input_event>()) };
+            needs_sync |= mapping.process(&event, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests replaying recorded evdev event streams, in the format of `evemu-record`.
+
+    use super::*;
+
+    const XBOX_CONTROLLER: &str = include_str!("tests/xbox_controller.evemu");
+
+    /// The axis ranges and the raw event stream of the recording.
+    fn parse_recording(recording: &str) -> (HashMap<u16, AbsRange>, Vec<u8>) {
+        let mut ranges = HashMap::new();
+        let mut stream = Vec::new();
+        for line in recording.lines() {
+            let line = line.split('#').next().unwrap();
+            let fields: Vec<_> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["A:", code, min, max, ..] => {
+                    let range = AbsRange {
+                        min: min.parse().unwrap(),
+                        max: max.parse().unwrap(),
+                    };
+                    ranges.insert(u16::from_str_radix(code, 16).unwrap(), range);
+                }
+                // The timestamps aren't used by the mapping.
+                ["E:", _, ty, code, value] => {
+                    let mut event: libc::input_event = unsafe { mem::zeroed() };
+                    event.type_ = u16::from_str_radix(ty, 16).unwrap();
+                    event.code = u16::from_str_radix(code, 16).unwrap();
+                    event.value = value.parse().unwrap();
+                    let bytes = unsafe {
+                        std::slice::from_raw_parts(ptr::addr_of!(event).cast::<u8>(), EVENT_SIZE)
+                    };
+                    stream.extend_from_slice(bytes);
+                }
+                _ => (),
+            }
+        }
+
+        (ranges, stream)
+    }
+
+    fn button_event(button: GamepadButton, state: ElementState) -> DeviceEvent {
+        DeviceEvent::Button {
+            button: button.id(),
+            state,
+        }
+    }
+
+    fn motion_event(axis: GamepadAxis, value: f64) -> DeviceEvent {
+        DeviceEvent::Motion {
+            axis: axis.id(),
+            value,
+        }
+    }
+
+    #[test]
+    fn replay_xbox_controller() {
+        use ElementState::{Pressed, Released};
+
+        let (ranges, stream) = parse_recording(XBOX_CONTROLLER);
+        let mut mapping = Mapping::new(true, ranges);
+
+        // The controller at rest, as queried when opening the device.
+        let mut state = DeviceState {
+            keys: Vec::new(),
+            axes: mapping.abs_codes().map(|code| (code, 0)).collect(),
+        };
+        mapping.sync(&state, &mut Vec::new());
+
+        let mut events = Vec::new();
+        let needs_sync = read_events(&mut stream.as_slice(), &mut mapping, &mut events).unwrap();
+        assert_eq!(
+            events,
+            [
+                button_event(GamepadButton::South, Pressed),
+                button_event(GamepadButton::South, Released),
+                motion_event(GamepadAxis::LeftStickX, 1.0),
+                motion_event(GamepadAxis::LeftStickY, -1.0),
+                button_event(GamepadButton::DPadLeft, Pressed),
+                button_event(GamepadButton::DPadLeft, Released),
+                button_event(GamepadButton::DPadRight, Pressed),
+                button_event(GamepadButton::DPadRight, Released),
+                motion_event(GamepadAxis::RightTrigger, 1.0),
+                button_event(GamepadButton::LeftShoulder, Pressed),
+            ]
+        );
+
+        // The press of the east button was dropped, and is recovered from the device state.
+        assert!(needs_sync);
+        state.keys = vec![BTN_EAST, BTN_TL];
+        for (code, value) in &mut state.axes {
+            *value = match *code {
+                ABS_X | ABS_Y => [32767, -32768][*code as usize],
+                ABS_RZ => 1023,
+                _ => 0,
+            };
+        }
+        events.clear();
+        mapping.sync(&state, &mut events);
+        assert_eq!(events, [button_event(GamepadButton::East, Pressed)]);
+    }
+
+    #[test]
+    fn joystick_axes_are_not_triggers() {
+        let ranges = HashMap::from([(ABS_Z, AbsRange { min: 0, max: 255 })]);
+        let mut mapping = Mapping::new(false, ranges);
+
+        let mut event: libc::input_event = unsafe { mem::zeroed() };
+        event.type_ = EV_ABS;
+        event.code = ABS_Z;
+        event.value = 255;
+        let mut events = Vec::new();
+        mapping.process(&event, &mut events);
+        assert_eq!(events, [motion_event(GamepadAxis::Other(ABS_Z), 1.0)]);
+    }
+
+    #[test]
+    fn ids_round_trip() {
+        let buttons = [
+            GamepadButton::South,
+            GamepadButton::DPadRight,
+            GamepadButton::Other(BTN_JOYSTICK),
+        ];
+        for button in buttons {
+            assert_eq!(GamepadButton::from_id(button.id()), Some(button));
+        }
+
+        let axes = [
+            GamepadAxis::LeftStickX,
+            GamepadAxis::RightTrigger,
+            GamepadAxis::Other(0x28),
+        ];
+        for axis in axes {
+            assert_eq!(GamepadAxis::from_id(axis.id()), Some(axis));
+        }
+    }
+}
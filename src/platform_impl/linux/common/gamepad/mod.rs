@@ -0,0 +1,475 @@
+//! Gamepads and joysticks read from the evdev device nodes.
+//!
+//! The device nodes in `/dev/input` are watched with inotify, the ones reporting the buttons of a
+//! gamepad or a joystick are kept open. The inotify instance and the devices are polled with an
+//! epoll instance of their own, so the event loops only have to register a single file
+//! descriptor and call [`Gamepads::dispatch`] once it's readable.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{mem, ptr, slice};
+
+use crate::error::ExternalError;
+use crate::event::{DeviceEvent, DeviceId as RootDeviceId};
+use crate::platform::input_device::{InputDevice, InputDeviceCapabilities, InputDeviceKind};
+use crate::platform_impl::{DeviceId as PlatformDeviceId, OsError};
+
+use self::evdev::*;
+use self::mapping::{AbsRange, DeviceState, Mapping};
+
+mod evdev;
+mod mapping;
+
+/// The directory of the evdev device nodes.
+const DEV_INPUT: &str = "/dev/input";
+
+/// The epoll token of the inotify instance, the devices use their id.
+const INOTIFY_TOKEN: u64 = u64::MAX;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GamepadId(u32);
+
+fn mkdid(id: GamepadId) -> RootDeviceId {
+    RootDeviceId(PlatformDeviceId::Gamepad(id))
+}
+
+fn cvt(ret: libc::c_int) -> io::Result<libc::c_int> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret)
+    }
+}
+
+/// An opened gamepad or joystick.
+#[derive(Debug)]
+struct Gamepad {
+    file: File,
+    path: PathBuf,
+    name: String,
+    kind: InputDeviceKind,
+    input_id: Option<libc::input_id>,
+    capabilities: InputDeviceCapabilities,
+    mapping: Mapping,
+    /// The id of the rumble effect uploaded to the device.
+    effect: Cell<Option<i16>>,
+}
+
+impl Gamepad {
+    /// Open the device node, `None` when it's not a gamepad or a joystick.
+    fn open(path: &Path) -> Option<Self> {
+        // Writing is only needed for the force feedback.
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .or_else(|_| {
+                OpenOptions::new()
+                    .read(true)
+                    .custom_flags(libc::O_NONBLOCK)
+                    .open(path)
+            })
+            .ok()?;
+        let fd = file.as_fd();
+
+        let mut keys = [0u8; KEY_CNT / 8];
+        bits(fd, EV_KEY, &mut keys).ok()?;
+        let kind = if (BTN_SOUTH..=BTN_THUMBR).any(|code| has_bit(&keys, code)) {
+            InputDeviceKind::Gamepad
+        } else if (BTN_JOYSTICK..BTN_SOUTH).any(|code| has_bit(&keys, code)) {
+            InputDeviceKind::Joystick
+        } else {
+            return None;
+        };
+
+        let mut abs = [0u8; ABS_CNT / 8];
+        let ranges: HashMap<_, _> = match bits(fd, EV_ABS, &mut abs) {
+            Ok(()) => (0..ABS_CNT as u16)
+                .filter(|&code| has_bit(&abs, code))
+                .filter_map(|code| {
+                    let info = abs_info(fd, code).ok()?;
+                    let range = AbsRange {
+                        min: info.minimum,
+                        max: info.maximum,
+                    };
+                    Some((code, range))
+                })
+                .collect(),
+            Err(_) => HashMap::new(),
+        };
+
+        let mut ff = [0u8; FF_CNT / 8];
+        let rumble = bits(fd, EV_FF, &mut ff).is_ok() && has_bit(&ff, FF_RUMBLE);
+
+        let mut capabilities = InputDeviceCapabilities::BUTTONS;
+        capabilities.set(InputDeviceCapabilities::ABSOLUTE_MOTION, !ranges.is_empty());
+        capabilities.set(InputDeviceCapabilities::FORCE_FEEDBACK, rumble);
+
+        let mut gamepad = Gamepad {
+            name: name(fd).unwrap_or_default(),
+            input_id: id(fd).ok(),
+            file,
+            path: path.to_owned(),
+            kind,
+            capabilities,
+            mapping: Mapping::new(kind == InputDeviceKind::Gamepad, ranges),
+            effect: Cell::new(None),
+        };
+
+        // Start from the current state, without reporting it.
+        let state = gamepad.state().ok()?;
+        gamepad.mapping.sync(&state, &mut Vec::new());
+
+        Some(gamepad)
+    }
+
+    /// Query the state of the device.
+    fn state(&self) -> io::Result<DeviceState> {
+        let fd = self.file.as_fd();
+
+        let mut keys = [0u8; KEY_CNT / 8];
+        pressed_keys(fd, &mut keys)?;
+        let keys = (0..KEY_CNT as u16)
+            .filter(|&code| has_bit(&keys, code))
+            .collect();
+
+        let axes = self
+            .mapping
+            .abs_codes()
+            .map(|code| Ok((code, abs_info(fd, code)?.value)))
+            .collect::<io::Result<_>>()?;
+
+        Ok(DeviceState { keys, axes })
+    }
+
+    /// Read the pending events of the device.
+    fn read(&mut self, out: &mut Vec<DeviceEvent>) -> io::Result<()> {
+        if mapping::read_events(&mut self.file, &mut self.mapping, out)? {
+            let state = self.state()?;
+            self.mapping.sync(&state, out);
+        }
+
+        Ok(())
+    }
+
+    fn rumble(&self, strong: f32, weak: f32, duration: Duration) -> io::Result<()> {
+        let magnitude = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32) as u16;
+
+        // SAFETY: the effect is plain old data.
+        let mut effect: libc::ff_effect = unsafe { mem::zeroed() };
+        effect.type_ = FF_RUMBLE;
+        effect.id = self.effect.get().unwrap_or(-1);
+        effect.replay.length = duration.as_millis().min(u16::MAX as u128) as u16;
+        let rumble = libc::ff_rumble_effect {
+            strong_magnitude: magnitude(strong),
+            weak_magnitude: magnitude(weak),
+        };
+        // SAFETY: the rumble is the variant of the effect union for `FF_RUMBLE`.
+        unsafe { ptr::write_unaligned(ptr::addr_of_mut!(effect.u).cast(), rumble) };
+
+        upload_effect(self.file.as_fd(), &mut effect)?;
+        self.effect.set(Some(effect.id));
+
+        // SAFETY: the event is plain old data.
+        let mut play: libc::input_event = unsafe { mem::zeroed() };
+        play.type_ = EV_FF;
+        play.code = effect.id as u16;
+        play.value = 1;
+        // SAFETY: the bytes of the event are written as is.
+        let play = unsafe {
+            slice::from_raw_parts(
+                ptr::addr_of!(play).cast::<u8>(),
+                mem::size_of::<libc::input_event>(),
+            )
+        };
+        (&self.file).write_all(play)
+    }
+}
+
+/// The gamepads and joysticks, kept up to date with the device nodes.
+#[derive(Debug)]
+pub struct Gamepads {
+    epoll: OwnedFd,
+    /// The watch of the device nodes being added and removed, if available.
+    inotify: Option<File>,
+    devices: RefCell<HashMap<GamepadId, Gamepad>>,
+    next_id: Cell<u32>,
+}
+
+impl Gamepads {
+    /// Open the gamepads connected at the moment and start watching for new ones.
+    pub fn new() -> io::Result<Self> {
+        // SAFETY: the returned file descriptor is owned.
+        let epoll = unsafe { OwnedFd::from_raw_fd(cvt(libc::epoll_create1(libc::EPOLL_CLOEXEC))?) };
+
+        let inotify = match Self::watch_dev_input() {
+            Ok(inotify) => Some(inotify),
+            Err(error) => {
+                log::warn!("Failed to watch {DEV_INPUT}, gamepads won't be hot-plugged: {error}");
+                None
+            }
+        };
+
+        let gamepads = Self {
+            epoll,
+            inotify,
+            devices: Default::default(),
+            next_id: Cell::new(1),
+        };
+
+        if let Some(inotify) = &gamepads.inotify {
+            gamepads.epoll_add(inotify.as_raw_fd(), INOTIFY_TOKEN)?;
+        }
+
+        // Sorted to keep the order of the ids stable.
+        let mut paths: Vec<_> = fs::read_dir(DEV_INPUT)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| is_event_node(path.file_name()))
+            .collect();
+        paths.sort();
+        for path in paths {
+            gamepads.open(&path);
+        }
+
+        Ok(gamepads)
+    }
+
+    fn watch_dev_input() -> io::Result<File> {
+        // SAFETY: the returned file descriptor is owned.
+        let inotify = unsafe {
+            let fd = cvt(libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC))?;
+            File::from_raw_fd(fd)
+        };
+
+        // The permissions of the new nodes are usually set by udev after their creation.
+        let path = b"/dev/input\0";
+        let mask = libc::IN_CREATE | libc::IN_ATTRIB | libc::IN_DELETE;
+        cvt(unsafe { libc::inotify_add_watch(inotify.as_raw_fd(), path.as_ptr().cast(), mask) })?;
+
+        Ok(inotify)
+    }
+
+    fn epoll_add(&self, fd: RawFd, token: u64) -> io::Result<()> {
+        let mut event = libc::epoll_event {
+            events: libc::EPOLLIN as u32,
+            u64: token,
+        };
+        let op = libc::EPOLL_CTL_ADD;
+        cvt(unsafe { libc::epoll_ctl(self.epoll.as_raw_fd(), op, fd, &mut event) })?;
+        Ok(())
+    }
+
+    /// Open the device node, returning the id of the new gamepad.
+    fn open(&self, path: &Path) -> Option<GamepadId> {
+        if self
+            .devices
+            .borrow()
+            .values()
+            .any(|gamepad| gamepad.path == path)
+        {
+            return None;
+        }
+
+        let gamepad = Gamepad::open(path)?;
+        let id = GamepadId(self.next_id.get());
+        if let Err(error) = self.epoll_add(gamepad.file.as_raw_fd(), id.0 as u64) {
+            log::warn!("Failed to poll the gamepad {path:?}: {error}");
+            return None;
+        }
+
+        self.next_id.set(id.0 + 1);
+        self.devices.borrow_mut().insert(id, gamepad);
+        Some(id)
+    }
+
+    /// The ids of the gamepads, to announce the ones connected from the start.
+    pub fn device_ids(&self) -> Vec<RootDeviceId> {
+        let mut ids: Vec<_> = self.devices.borrow().keys().copied().collect();
+        ids.sort();
+        ids.into_iter().map(mkdid).collect()
+    }
+
+    /// The gamepads as input devices.
+    pub fn input_devices(&self) -> Vec<InputDevice> {
+        let devices = self.devices.borrow();
+        let mut ids: Vec<_> = devices.keys().copied().collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| {
+                let gamepad = &devices[&id];
+                InputDevice {
+                    id: mkdid(id),
+                    name: gamepad.name.clone(),
+                    kind: gamepad.kind,
+                    vendor_id: gamepad.input_id.map(|id| id.vendor),
+                    product_id: gamepad.input_id.map(|id| id.product),
+                    capabilities: gamepad.capabilities,
+                }
+            })
+            .collect()
+    }
+
+    /// Rumble the gamepad, see `ActiveEventLoopExtGamepad::set_rumble`.
+    pub fn set_rumble(
+        &self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        let devices = self.devices.borrow();
+        let gamepad = match devices.get(&id) {
+            Some(gamepad)
+                if gamepad
+                    .capabilities
+                    .contains(InputDeviceCapabilities::FORCE_FEEDBACK) =>
+            {
+                gamepad
+            }
+            _ => {
+                return Err(ExternalError::NotSupported(
+                    crate::error::NotSupportedError::new(),
+                ))
+            }
+        };
+
+        gamepad.rumble(strong, weak, duration).map_err(|error| {
+            log::warn!("Failed to rumble the gamepad {:?}: {error}", gamepad.path);
+            ExternalError::Os(os_error!(OsError::Misc("failed to rumble the gamepad")))
+        })
+    }
+
+    /// Read the pending events, passing them to the `callback` alongside the id of the gamepad.
+    pub fn dispatch<F>(&self, mut callback: F)
+    where
+        F: FnMut(RootDeviceId, DeviceEvent),
+    {
+        let mut events = [libc::epoll_event { events: 0, u64: 0 }; 16];
+        loop {
+            let count = unsafe {
+                libc::epoll_wait(
+                    self.epoll.as_raw_fd(),
+                    events.as_mut_ptr(),
+                    events.len() as libc::c_int,
+                    0,
+                )
+            };
+            let count = match cvt(count) {
+                Ok(count) => count as usize,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => {
+                    log::error!("Failed to poll the gamepads: {error}");
+                    return;
+                }
+            };
+
+            for event in &events[..count] {
+                match event.u64 {
+                    INOTIFY_TOKEN => self.read_inotify(&mut callback),
+                    token => self.read_gamepad(GamepadId(token as u32), &mut callback),
+                }
+            }
+
+            if count < events.len() {
+                return;
+            }
+        }
+    }
+
+    fn read_gamepad<F>(&self, id: GamepadId, callback: &mut F)
+    where
+        F: FnMut(RootDeviceId, DeviceEvent),
+    {
+        let mut events = Vec::new();
+        let result = match self.devices.borrow_mut().get_mut(&id) {
+            Some(gamepad) => gamepad.read(&mut events),
+            None => return,
+        };
+
+        for event in events {
+            callback(mkdid(id), event);
+        }
+
+        // The device is gone, usually `ENODEV` once unplugged.
+        if let Err(error) = result {
+            if let Some(gamepad) = self.devices.borrow_mut().remove(&id) {
+                log::debug!("Closing the gamepad {:?}: {error}", gamepad.path);
+                callback(mkdid(id), DeviceEvent::Removed);
+            }
+        }
+    }
+
+    fn read_inotify<F>(&self, callback: &mut F)
+    where
+        F: FnMut(RootDeviceId, DeviceEvent),
+    {
+        let mut inotify = match &self.inotify {
+            Some(inotify) => inotify,
+            None => return,
+        };
+
+        let mut buffer = [0u8; 4096];
+        loop {
+            let len = match inotify.read(&mut buffer) {
+                Ok(0) => return,
+                Ok(len) => len,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(_) => return,
+            };
+
+            let mut offset = 0;
+            while offset + mem::size_of::<libc::inotify_event>() <= len {
+                // SAFETY: the kernel only returns whole events.
+                let event = unsafe {
+                    ptr::read_unaligned(buffer[offset..].as_ptr().cast::<libc::inotify_event>())
+                };
+                let name_start = offset + mem::size_of::<libc::inotify_event>();
+                offset = name_start + event.len as usize;
+
+                // The name is padded with nul bytes.
+                let name = buffer[name_start..offset.min(len)]
+                    .split(|&b| b == 0)
+                    .next();
+                let name = OsStr::from_bytes(name.unwrap_or_default());
+                if !is_event_node(Some(name)) {
+                    continue;
+                }
+
+                let path = Path::new(DEV_INPUT).join(name);
+                if event.mask & libc::IN_DELETE != 0 {
+                    let mut devices = self.devices.borrow_mut();
+                    let id = devices
+                        .iter()
+                        .find_map(|(&id, gamepad)| (gamepad.path == path).then_some(id));
+                    if let Some(id) = id {
+                        devices.remove(&id);
+                        drop(devices);
+                        callback(mkdid(id), DeviceEvent::Removed);
+                    }
+                } else if let Some(id) = self.open(&path) {
+                    callback(mkdid(id), DeviceEvent::Added);
+                }
+            }
+        }
+    }
+}
+
+impl AsFd for Gamepads {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.epoll.as_fd()
+    }
+}
+
+/// Whether the file is an evdev device node, the legacy `js` and `mouse` nodes are ignored.
+fn is_event_node(name: Option<&OsStr>) -> bool {
+    name.is_some_and(|name| name.as_bytes().starts_with(b"event"))
+}
@@ -1 +1,5 @@
+#[cfg(target_os = "linux")]
+pub mod gamepad;
+pub mod stream_waker;
+pub mod timestamp;
 pub mod xkb;
@@ -0,0 +1,29 @@
+//! Wake up the task awaiting the event stream from the event loop proxies.
+
+use std::sync::{Arc, Mutex};
+use std::task::Waker;
+
+/// The waker of the task awaiting the `EventStream`, shared with the event loop proxies.
+#[derive(Debug, Default, Clone)]
+pub struct StreamWaker {
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl StreamWaker {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Register the waker to wake up on the next event sent through a proxy.
+    pub fn register(&self, waker: Option<Waker>) {
+        *self.waker.lock().unwrap() = waker;
+    }
+
+    /// Wake up the registered waker, if any.
+    pub fn wake(&self) {
+        let waker = self.waker.lock().unwrap().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Conversion of the display server timestamps to [`Instant`].
+
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+/// The millisecond clock of the display server.
+///
+/// Both X11 and Wayland timestamp the input events with a 32-bit millisecond counter of an
+/// unspecified base. The counter is mapped to [`Instant`] by anchoring it to the local clock,
+/// thus the time between the events is preserved as is, while the offset converges to the
+/// smallest observed delivery latency.
+///
+/// The anchor is moved back whenever an event would come from the future, and slowly forward by
+/// [`MAX_DRIFT`] otherwise, so a server clock running slower than the local one doesn't make the
+/// offset grow without bound.
+#[derive(Debug, Default)]
+pub struct ServerClock {
+    /// The last converted server time and its local time.
+    anchor: Cell<Option<(u32, Instant)>>,
+}
+
+impl ServerClock {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Convert the server time in milliseconds to the local time.
+    pub fn instant(&self, time: u32) -> Instant {
+        self.instant_at(time, Instant::now())
+    }
+
+    fn instant_at(&self, time: u32, now: Instant) -> Instant {
+        anchored_instant(&self.anchor, time, now, |anchor_time| {
+            // Signed to handle the events older than the anchor and the counter wrapping.
+            let delta = time.wrapping_sub(anchor_time) as i32;
+            (
+                delta >= 0,
+                Duration::from_millis(delta.unsigned_abs() as u64),
+            )
+        })
+    }
+}
+
+/// The microsecond clock of the display server.
+///
+/// Used by the Wayland relative pointer events, whose 64-bit counter has a base unrelated to the
+/// one of [`ServerClock`], thus it's anchored on its own.
+#[derive(Debug, Default)]
+pub struct MicrosecondServerClock {
+    /// The last converted server time and its local time.
+    anchor: Cell<Option<(u64, Instant)>>,
+}
+
+impl MicrosecondServerClock {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Convert the server time in microseconds to the local time.
+    pub fn instant(&self, time: u64) -> Instant {
+        self.instant_at(time, Instant::now())
+    }
+
+    fn instant_at(&self, time: u64, now: Instant) -> Instant {
+        anchored_instant(&self.anchor, time, now, |anchor_time| {
+            let delta = time.wrapping_sub(anchor_time) as i64;
+            (delta >= 0, Duration::from_micros(delta.unsigned_abs()))
+        })
+    }
+}
+
+/// The fraction of the server time the local time is allowed to catch up by, compensating the
+/// drift of a server clock running slower than the local one.
+const MAX_DRIFT: u32 = 1000;
+
+/// Map the server time to the local time, given the direction and the distance of the time to
+/// the anchor.
+fn anchored_instant<T: Copy>(
+    anchor: &Cell<Option<(T, Instant)>>,
+    time: T,
+    now: Instant,
+    delta: impl FnOnce(T) -> (bool, Duration),
+) -> Instant {
+    let instant = match anchor.get() {
+        Some((anchor_time, anchor_instant)) => match delta(anchor_time) {
+            (true, delta) => {
+                let instant = anchor_instant + delta;
+                // Catch up with the local clock, without distorting the time between the events.
+                let lag = now.saturating_duration_since(instant);
+                instant + lag.min(delta / MAX_DRIFT)
+            }
+            (false, delta) => anchor_instant.checked_sub(delta).unwrap_or(anchor_instant),
+        },
+        None => now,
+    };
+
+    // The event can't come from the future, the previous events were delivered late.
+    let instant = instant.min(now);
+
+    // Move the anchor along to keep the delta small.
+    anchor.set(Some((time, instant)));
+
+    instant
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn server_clock_wrap() {
+        let clock = ServerClock::new();
+        let start = Instant::now();
+
+        assert_eq!(clock.instant_at(u32::MAX - 5, start), start);
+        // The counter wrapped 10ms later, while delivered a second later.
+        assert_eq!(
+            clock.instant_at(4, start + ms(1000)),
+            start + ms(10) + ms(10) / MAX_DRIFT
+        );
+    }
+
+    #[test]
+    fn server_clock_older_than_anchor() {
+        let clock = ServerClock::new();
+        let start = Instant::now() + ms(1000);
+
+        assert_eq!(clock.instant_at(1000, start), start);
+        assert_eq!(clock.instant_at(990, start + ms(5)), start - ms(10));
+        // The anchor moved along with the older event.
+        assert_eq!(
+            clock.instant_at(995, start + ms(10)),
+            start - ms(5) + ms(5) / MAX_DRIFT
+        );
+    }
+
+    #[test]
+    fn server_clock_never_in_the_future() {
+        let clock = ServerClock::new();
+        let start = Instant::now();
+
+        assert_eq!(clock.instant_at(1000, start), start);
+        // The first event was delivered late, thus the event can't be 100ms after it yet.
+        assert_eq!(clock.instant_at(1100, start + ms(50)), start + ms(50));
+        // The anchor moved back to the smaller latency.
+        assert_eq!(
+            clock.instant_at(1200, start + ms(200)),
+            start + ms(150) + ms(100) / MAX_DRIFT
+        );
+    }
+
+    #[test]
+    fn server_clock_slower_than_local() {
+        let clock = ServerClock::new();
+        let start = Instant::now();
+
+        // The server clock runs 0.05% slower for an hour.
+        let mut now = start;
+        let mut instant = clock.instant_at(0, now);
+        for time in (100..3_600_000).step_by(100) {
+            now += Duration::from_micros(100_050);
+            instant = clock.instant_at(time, now);
+        }
+
+        assert!(instant <= now);
+        assert!(now - instant < ms(1));
+    }
+
+    #[test]
+    fn microsecond_server_clock() {
+        let clock = MicrosecondServerClock::new();
+        let start = Instant::now() + ms(1000);
+
+        assert_eq!(clock.instant_at(5_000, start), start);
+        assert_eq!(clock.instant_at(3_000, start + ms(1)), start - ms(2));
+        assert_eq!(clock.instant_at(10_000, start + ms(2)), start + ms(2));
+    }
+}
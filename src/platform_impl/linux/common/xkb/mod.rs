@@ -2,6 +2,7 @@ use std::ops::Deref;
 use std::os::raw::c_char;
 use std::ptr::{self, NonNull};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
 use crate::utils::Lazy;
 use log::warn;
@@ -27,7 +28,6 @@ mod state;
 use compose::{ComposeStatus, XkbComposeState, XkbComposeTable};
 use keymap::XkbKeymap;
 
-#[cfg(x11_platform)]
 pub use keymap::raw_keycode_to_physicalkey;
 pub use keymap::{physicalkey_to_scancode, scancode_to_physicalkey};
 pub use state::XkbState;
@@ -205,6 +205,7 @@ impl<'a> KeyContext<'a> {
         keycode: u32,
         state: ElementState,
         repeat: bool,
+        timestamp: Option<Instant>,
     ) -> KeyEvent {
         let mut event =
             KeyEventResults::new(self, keycode, !repeat && state == ElementState::Pressed);
@@ -226,6 +227,7 @@ impl<'a> KeyContext<'a> {
             location,
             state,
             repeat,
+            timestamp,
             platform_specific,
         }
     }
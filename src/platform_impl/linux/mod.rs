@@ -5,10 +5,11 @@ compile_error!("Please select a feature to build for unix: `x11`, `wayland`");
 
 use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
 use std::sync::Arc;
+use std::task::Waker;
 use std::time::Duration;
 use std::{collections::VecDeque, env, fmt};
 #[cfg(x11_platform)]
-use std::{ffi::CStr, mem::MaybeUninit, os::raw::*, sync::Mutex};
+use std::{ffi::CStr, mem, mem::MaybeUninit, os::raw::*, sync::Mutex};
 
 #[cfg(x11_platform)]
 use crate::utils::Lazy;
@@ -17,25 +18,37 @@ use smol_str::SmolStr;
 #[cfg(x11_platform)]
 use self::x11::{X11Error, XConnection, XError, XNotSupported};
 #[cfg(x11_platform)]
-use crate::platform::x11::{WindowType as XWindowType, XlibErrorHook};
+use crate::platform::x11::{
+    Desktop as XDesktop, NetWmState, WindowType as XWindowType, XlibErrorHook,
+};
 use crate::window::{CustomCursor, CustomCursorSource};
 use crate::{
     dpi::{PhysicalPosition, PhysicalSize, Position, Size},
     error::{EventLoopError, ExternalError, NotSupportedError, OsError as RootOsError},
     event_loop::{
         ActiveEventLoop as RootELW, AsyncRequestSerial, ControlFlow, DeviceEvents, EventLoopClosed,
+        FdReadiness, FdToken,
     },
     icon::Icon,
     keyboard::Key,
-    platform::pump_events::PumpStatus,
+    platform::{
+        backend::{
+            Backend as RootBackend, BackendCapabilities, BackendRejection, BackendRejectionReason,
+        },
+        fd_source::FdInterest,
+        input_device::InputDevice,
+        pump_events::PumpStatus,
+        seat::{Seat, SeatId},
+    },
     window::{
-        ActivationToken, Cursor, CursorGrabMode, ImePurpose, ResizeDirection, Theme,
-        UserAttentionType, WindowAttributes, WindowButtons, WindowLevel,
+        ActivationToken, Cursor, CursorGrabMode, CursorIcon, ForeignWindowHandle, ImePurpose,
+        OpaqueRegion, ResizeDirection, Theme, UserAttentionType, WindowAttributes, WindowButtons,
+        WindowLevel, WindowRegion,
     },
 };
 
 pub(crate) use self::common::xkb::{physicalkey_to_scancode, scancode_to_physicalkey};
-pub(crate) use crate::cursor::OnlyCursorImageSource as PlatformCustomCursorSource;
+pub(crate) use crate::cursor::AnimatedCursorImageSource as PlatformCustomCursorSource;
 pub(crate) use crate::icon::RgbaIcon as PlatformIcon;
 pub(crate) use crate::platform_impl::Fullscreen;
 
@@ -53,10 +66,26 @@ pub(crate) enum Backend {
     Wayland,
 }
 
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct BackendPreference {
+    pub(crate) backends: Vec<RootBackend>,
+    pub(crate) required: BackendCapabilities,
+}
+
+impl BackendPreference {
+    pub(crate) fn forced(backend: RootBackend) -> Self {
+        Self {
+            backends: vec![backend],
+            required: BackendCapabilities::empty(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct PlatformSpecificEventLoopAttributes {
-    pub(crate) forced_backend: Option<Backend>,
+    pub(crate) backend_preference: Option<BackendPreference>,
     pub(crate) any_thread: bool,
+    pub(crate) gamepads: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -75,6 +104,7 @@ impl ApplicationName {
 pub struct PlatformSpecificWindowAttributes {
     pub name: Option<ApplicationName>,
     pub activation_token: Option<ActivationToken>,
+    pub foreign_parent: Option<ForeignWindowHandle>,
     #[cfg(x11_platform)]
     pub x11: X11WindowAttributes,
 }
@@ -90,6 +120,12 @@ pub struct X11WindowAttributes {
 
     /// The parent window to embed this window into.
     pub embed_window: Option<x11rb::protocol::xproto::Window>,
+
+    /// The desktop to put the window on.
+    pub desktop: Option<XDesktop>,
+
+    /// The additional `_NET_WM_STATE` hints.
+    pub net_wm_state: NetWmState,
 }
 
 impl Default for PlatformSpecificWindowAttributes {
@@ -97,6 +133,7 @@ impl Default for PlatformSpecificWindowAttributes {
         Self {
             name: None,
             activation_token: None,
+            foreign_parent: None,
             #[cfg(x11_platform)]
             x11: X11WindowAttributes {
                 visual_id: None,
@@ -105,6 +142,8 @@ impl Default for PlatformSpecificWindowAttributes {
                 override_redirect: false,
                 x11_window_types: vec![XWindowType::Normal],
                 embed_window: None,
+                desktop: None,
+                net_wm_state: NetWmState::empty(),
             },
         }
     }
@@ -117,6 +156,7 @@ pub(crate) static X11_BACKEND: Lazy<Mutex<Result<Arc<XConnection>, XNotSupported
 #[derive(Debug, Clone)]
 pub enum OsError {
     Misc(&'static str),
+    NoSuitableBackend(Vec<BackendRejection>),
     #[cfg(x11_platform)]
     XError(Arc<X11Error>),
     #[cfg(wayland_platform)]
@@ -127,6 +167,14 @@ impl fmt::Display for OsError {
     fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         match *self {
             OsError::Misc(e) => _f.pad(e),
+            OsError::NoSuitableBackend(ref rejections) => {
+                _f.write_str("no suitable backend found")?;
+                for (i, rejection) in rejections.iter().enumerate() {
+                    _f.write_str(if i == 0 { ": " } else { "; " })?;
+                    fmt::Display::fmt(rejection, _f)?;
+                }
+                Ok(())
+            }
             #[cfg(x11_platform)]
             OsError::XError(ref e) => fmt::Display::fmt(e, _f),
             #[cfg(wayland_platform)]
@@ -169,6 +217,8 @@ pub enum DeviceId {
     X(x11::DeviceId),
     #[cfg(wayland_platform)]
     Wayland(wayland::DeviceId),
+    #[cfg(target_os = "linux")]
+    Gamepad(common::gamepad::GamepadId),
 }
 
 impl DeviceId {
@@ -329,6 +379,11 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.set_blur(blur));
     }
 
+    #[inline]
+    pub fn set_opacity(&self, opacity: f32) {
+        x11_or_wayland!(match self; Window(w) => w.set_opacity(opacity));
+    }
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         x11_or_wayland!(match self; Window(w) => w.set_visible(visible))
@@ -374,6 +429,11 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.request_activation_token())
     }
 
+    #[inline]
+    pub(crate) fn export_handle(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
+        x11_or_wayland!(match self; Window(w) => w.export_handle())
+    }
+
     #[inline]
     pub fn set_min_inner_size(&self, dimensions: Option<Size>) {
         x11_or_wayland!(match self; Window(w) => w.set_min_inner_size(dimensions))
@@ -394,6 +454,11 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.set_resize_increments(increments))
     }
 
+    #[inline]
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        x11_or_wayland!(match self; Window(w) => w.set_aspect_ratio(aspect_ratio))
+    }
+
     #[inline]
     pub fn set_resizable(&self, resizable: bool) {
         x11_or_wayland!(match self; Window(w) => w.set_resizable(resizable))
@@ -429,6 +494,25 @@ impl Window {
         x11_or_wayland!(match self; Window(window) => window.set_cursor_visible(visible))
     }
 
+    #[inline]
+    pub fn set_seat_cursor(&self, seat: SeatId, cursor: CursorIcon) {
+        x11_or_wayland!(match self; Window(window) => window.set_seat_cursor(seat, cursor))
+    }
+
+    #[inline]
+    pub fn set_seat_cursor_grab(
+        &self,
+        seat: SeatId,
+        mode: CursorGrabMode,
+    ) -> Result<(), ExternalError> {
+        x11_or_wayland!(match self; Window(window) => window.set_seat_cursor_grab(seat, mode))
+    }
+
+    #[inline]
+    pub fn reset_seat(&self, seat: SeatId) {
+        x11_or_wayland!(match self; Window(window) => window.reset_seat(seat))
+    }
+
     #[inline]
     pub fn drag_window(&self) -> Result<(), ExternalError> {
         x11_or_wayland!(match self; Window(window) => window.drag_window())
@@ -444,11 +528,46 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.show_window_menu(position))
     }
 
+    #[inline]
+    pub fn backend(&self) -> RootBackend {
+        match self {
+            #[cfg(x11_platform)]
+            Window::X(_) => RootBackend::X11,
+            #[cfg(wayland_platform)]
+            Window::Wayland(_) => RootBackend::Wayland,
+        }
+    }
+
+    #[inline]
+    pub fn capabilities(&self) -> BackendCapabilities {
+        x11_or_wayland!(match self; Window(w) => w.capabilities())
+    }
+
     #[inline]
     pub fn set_cursor_hittest(&self, hittest: bool) -> Result<(), ExternalError> {
         x11_or_wayland!(match self; Window(w) => w.set_cursor_hittest(hittest))
     }
 
+    #[inline]
+    pub fn set_input_region(&self, region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        x11_or_wayland!(match self; Window(w) => w.set_input_region(region))
+    }
+
+    #[inline]
+    pub fn set_shape(&self, shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        x11_or_wayland!(match self; Window(w) => w.set_shape(shape))
+    }
+
+    #[inline]
+    pub fn set_opaque_region(&self, region: Option<OpaqueRegion>) {
+        x11_or_wayland!(match self; Window(w) => w.set_opaque_region(region))
+    }
+
+    #[inline]
+    pub fn set_idle_inhibited(&self, inhibited: bool) {
+        x11_or_wayland!(match self; Window(w) => w.set_idle_inhibited(inhibited))
+    }
+
     #[inline]
     pub fn scale_factor(&self) -> f64 {
         x11_or_wayland!(match self; Window(w) => w.scale_factor())
@@ -504,6 +623,46 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.set_window_level(level))
     }
 
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn desktop(&self) -> Option<XDesktop> {
+        match self {
+            Window::X(w) => w.desktop(),
+            #[cfg(wayland_platform)]
+            Window::Wayland(_) => None,
+        }
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn set_desktop(&self, desktop: XDesktop) {
+        match self {
+            Window::X(w) => w.set_desktop(desktop),
+            #[cfg(wayland_platform)]
+            Window::Wayland(_) => (),
+        }
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn net_wm_state(&self) -> NetWmState {
+        match self {
+            Window::X(w) => w.net_wm_state(),
+            #[cfg(wayland_platform)]
+            Window::Wayland(_) => NetWmState::empty(),
+        }
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn set_net_wm_state(&self, state: NetWmState, enabled: bool) {
+        match self {
+            Window::X(w) => w.set_net_wm_state(state, enabled),
+            #[cfg(wayland_platform)]
+            Window::Wayland(_) => (),
+        }
+    }
+
     #[inline]
     pub fn set_window_icon(&self, window_icon: Option<Icon>) {
         x11_or_wayland!(match self; Window(w) => w.set_window_icon(window_icon.map(|icon| icon.inner)))
@@ -524,6 +683,16 @@ impl Window {
         x11_or_wayland!(match self; Window(w) => w.set_ime_allowed(allowed))
     }
 
+    #[inline]
+    pub fn set_seat_ime_allowed(&self, seat: SeatId, allowed: bool) {
+        x11_or_wayland!(match self; Window(w) => w.set_seat_ime_allowed(seat, allowed))
+    }
+
+    #[inline]
+    pub fn set_seat_ime_cursor_area(&self, seat: SeatId, position: Position, size: Size) {
+        x11_or_wayland!(match self; Window(w) => w.set_seat_ime_cursor_area(seat, position, size))
+    }
+
     #[inline]
     pub fn set_ime_purpose(&self, purpose: ImePurpose) {
         x11_or_wayland!(match self; Window(w) => w.set_ime_purpose(purpose))
@@ -719,6 +888,20 @@ impl<T: 'static> Clone for EventLoopProxy<T> {
 impl<T: 'static> EventLoop<T> {
     pub(crate) fn new(
         attributes: &PlatformSpecificEventLoopAttributes,
+    ) -> Result<Self, EventLoopError> {
+        #[allow(unused_mut)]
+        let mut event_loop = Self::new_backend(attributes)?;
+
+        #[cfg(target_os = "linux")]
+        if attributes.gamepads {
+            x11_or_wayland!(match &mut event_loop; EventLoop(evlp) => evlp.enable_gamepads());
+        }
+
+        Ok(event_loop)
+    }
+
+    fn new_backend(
+        attributes: &PlatformSpecificEventLoopAttributes,
     ) -> Result<Self, EventLoopError> {
         if !attributes.any_thread && !is_main_thread() {
             panic!(
@@ -729,10 +912,13 @@ impl<T: 'static> EventLoop<T> {
             );
         }
 
+        if let Some(preference) = &attributes.backend_preference {
+            return EventLoop::new_with_preference(preference);
+        }
+
         // NOTE: Wayland first because of X11 could be present under Wayland as well. Empty
         // variables are also treated as not set.
         let backend = match (
-            attributes.forced_backend,
             env::var("WAYLAND_DISPLAY")
                 .ok()
                 .filter(|var| !var.is_empty())
@@ -743,16 +929,14 @@ impl<T: 'static> EventLoop<T> {
                 .map(|var| !var.is_empty())
                 .unwrap_or(false),
         ) {
-            // User is forcing a backend.
-            (Some(backend), _, _) => backend,
             // Wayland is present.
             #[cfg(wayland_platform)]
-            (None, true, _) => Backend::Wayland,
+            (true, _) => Backend::Wayland,
             // X11 is present.
             #[cfg(x11_platform)]
-            (None, _, true) => Backend::X,
+            (_, true) => Backend::X,
             // No backend is present.
-            (_, wayland_display, x11_display) => {
+            (wayland_display, x11_display) => {
                 let msg = if wayland_display && !cfg!(wayland_platform) {
                     "DISPLAY is not set; note: enable the `winit/wayland` feature to support Wayland"
                 } else if x11_display && !cfg!(x11_platform) {
@@ -773,6 +957,54 @@ impl<T: 'static> EventLoop<T> {
         }
     }
 
+    fn new_with_preference(preference: &BackendPreference) -> Result<Self, EventLoopError> {
+        let mut rejections = Vec::new();
+        for &backend in &preference.backends {
+            let event_loop = match backend {
+                #[cfg(wayland_platform)]
+                RootBackend::Wayland => EventLoop::new_wayland_any_thread(),
+                #[cfg(x11_platform)]
+                RootBackend::X11 => EventLoop::new_x11_any_thread(),
+                #[allow(unreachable_patterns)]
+                _ => {
+                    rejections.push(BackendRejection {
+                        backend,
+                        reason: BackendRejectionReason::NotCompiled,
+                    });
+                    continue;
+                }
+            };
+
+            let reason = match event_loop {
+                Ok(event_loop) => {
+                    let missing = preference.required - event_loop.window_target().p.capabilities();
+                    if missing.is_empty() {
+                        return Ok(event_loop);
+                    }
+
+                    BackendRejectionReason::MissingCapabilities(missing)
+                }
+                #[cfg(x11_platform)]
+                Err(_) if backend == RootBackend::X11 => {
+                    let error = match X11_BACKEND.lock().unwrap().as_ref() {
+                        Err(error) => error.to_string(),
+                        Ok(_) => "failed to create the event loop".to_owned(),
+                    };
+                    BackendRejectionReason::Unavailable(error)
+                }
+                Err(error) => BackendRejectionReason::Unavailable(error.to_string()),
+            };
+
+            let rejection = BackendRejection { backend, reason };
+            log::warn!("Rejected backend {rejection}");
+            rejections.push(rejection);
+        }
+
+        Err(EventLoopError::Os(os_error!(OsError::NoSuitableBackend(
+            rejections
+        ))))
+    }
+
     #[cfg(wayland_platform)]
     fn new_wayland_any_thread() -> Result<EventLoop<T>, EventLoopError> {
         wayland::EventLoop::new().map(|evlp| EventLoop::Wayland(Box::new(evlp)))
@@ -780,6 +1012,18 @@ impl<T: 'static> EventLoop<T> {
 
     #[cfg(x11_platform)]
     fn new_x11_any_thread() -> Result<EventLoop<T>, EventLoopError> {
+        // Reconnect when the connection to the X server was lost. The lock must not be held
+        // while connecting, since the error handler takes it.
+        let connection_lost = matches!(
+            X11_BACKEND.lock().unwrap().as_ref(),
+            Ok(xconn) if xconn.is_connection_lost()
+        );
+        if connection_lost {
+            let backend = XConnection::new(Some(x_error_callback)).map(Arc::new);
+            let lost_backend = mem::replace(&mut *X11_BACKEND.lock().unwrap(), backend);
+            drop(lost_backend);
+        }
+
         let xconn = match X11_BACKEND.lock().unwrap().as_ref() {
             Ok(xconn) => xconn.clone(),
             Err(_) => return Err(EventLoopError::NotSupported(NotSupportedError::new())),
@@ -792,6 +1036,11 @@ impl<T: 'static> EventLoop<T> {
         x11_or_wayland!(match self; EventLoop(evlp) => evlp.create_proxy(); as EventLoopProxy)
     }
 
+    /// Register the waker of the task awaiting the event stream, woken by the proxies.
+    pub(crate) fn register_stream_waker(&self, waker: Option<Waker>) {
+        x11_or_wayland!(match self; EventLoop(evlp) => evlp.stream_waker().register(waker))
+    }
+
     pub fn run<F>(mut self, callback: F) -> Result<(), EventLoopError>
     where
         F: FnMut(crate::event::Event<T>, &RootELW),
@@ -885,6 +1134,106 @@ impl ActiveEventLoop {
         x11_or_wayland!(match self; Self(evlp) => evlp.listen_device_events(allowed))
     }
 
+    #[inline]
+    pub(crate) unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError> {
+        x11_or_wayland!(match self; Self(evlp) => unsafe { evlp.register_fd(fd, interest) })
+    }
+
+    #[inline]
+    pub(crate) fn unregister_fd(&self, token: FdToken) {
+        x11_or_wayland!(match self; Self(evlp) => evlp.unregister_fd(token))
+    }
+
+    #[inline]
+    pub(crate) fn backend(&self) -> RootBackend {
+        match self {
+            #[cfg(x11_platform)]
+            ActiveEventLoop::X(_) => RootBackend::X11,
+            #[cfg(wayland_platform)]
+            ActiveEventLoop::Wayland(_) => RootBackend::Wayland,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn capabilities(&self) -> BackendCapabilities {
+        x11_or_wayland!(match self; Self(evlp) => evlp.capabilities())
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn desktop_count(&self) -> Option<u32> {
+        match self {
+            ActiveEventLoop::X(evlp) => evlp.desktop_count(),
+            #[cfg(wayland_platform)]
+            ActiveEventLoop::Wayland(_) => None,
+        }
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn desktop_names(&self) -> Vec<String> {
+        match self {
+            ActiveEventLoop::X(evlp) => evlp.desktop_names(),
+            #[cfg(wayland_platform)]
+            ActiveEventLoop::Wayland(_) => Vec::new(),
+        }
+    }
+
+    #[cfg(x11_platform)]
+    #[inline]
+    pub fn current_desktop(&self) -> Option<u32> {
+        match self {
+            ActiveEventLoop::X(evlp) => evlp.current_desktop(),
+            #[cfg(wayland_platform)]
+            ActiveEventLoop::Wayland(_) => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn input_devices(&self) -> Vec<InputDevice> {
+        x11_or_wayland!(match self; Self(evlp) => evlp.input_devices())
+    }
+
+    #[inline]
+    pub(crate) fn seats(&self) -> Vec<Seat> {
+        x11_or_wayland!(match self; Self(evlp) => evlp.seats())
+    }
+
+    pub(crate) fn device_seat(&self, device_id: crate::event::DeviceId) -> Option<SeatId> {
+        match (self, device_id.0) {
+            #[cfg(x11_platform)]
+            (ActiveEventLoop::X(evlp), DeviceId::X(id)) => evlp.device_seat(id),
+            #[cfg(wayland_platform)]
+            (ActiveEventLoop::Wayland(_), DeviceId::Wayland(id)) => id.seat(),
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+
+    pub(crate) fn set_rumble(
+        &self,
+        device_id: crate::event::DeviceId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match device_id.0 {
+            #[cfg(target_os = "linux")]
+            DeviceId::Gamepad(id) => {
+                x11_or_wayland!(match self; Self(evlp) => evlp.set_rumble(id, strong, weak, duration))
+            }
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = (strong, weak, duration);
+                Err(ExternalError::NotSupported(NotSupportedError::new()))
+            }
+        }
+    }
+
     #[cfg(feature = "rwh_05")]
     #[inline]
     pub fn raw_display_handle_rwh_05(&self) -> rwh_05::RawDisplayHandle {
@@ -1000,6 +1349,25 @@ impl OwnedDisplayHandle {
     }
 }
 
+impl From<FdInterest> for calloop::Interest {
+    fn from(interest: FdInterest) -> Self {
+        Self {
+            readable: interest.readable,
+            writable: interest.writable,
+        }
+    }
+}
+
+impl From<calloop::Readiness> for FdReadiness {
+    fn from(readiness: calloop::Readiness) -> Self {
+        Self {
+            readable: readiness.readable,
+            writable: readiness.writable,
+            error: readiness.error,
+        }
+    }
+}
+
 /// Returns the minimum `Option<Duration>`, taking into account that `None`
 /// equates to an infinite timeout, not a zero timeout (so can't just use
 /// `Option::min`)
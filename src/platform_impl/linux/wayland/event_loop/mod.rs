@@ -10,17 +10,29 @@ use std::sync::atomic::Ordering;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+use ahash::AHashMap;
+use calloop::generic::Generic;
+use calloop::RegistrationToken;
 use sctk::reexports::calloop::Error as CalloopError;
 use sctk::reexports::calloop_wayland_source::WaylandSource;
 use sctk::reexports::client::globals;
 use sctk::reexports::client::{Connection, QueueHandle};
 
-use crate::cursor::OnlyCursorImage;
+use crate::cursor::AnimatedCursorImage;
 use crate::dpi::LogicalSize;
-use crate::error::{EventLoopError, OsError as RootOsError};
+use crate::error::{EventLoopError, ExternalError, OsError as RootOsError};
 use crate::event::{Event, InnerSizeWriter, StartCause, WindowEvent};
-use crate::event_loop::{ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents};
+use crate::event_loop::{
+    ActiveEventLoop as RootActiveEventLoop, ControlFlow, DeviceEvents, EventLoopBuilder, FdToken,
+};
+use crate::platform::backend::BackendCapabilities;
+use crate::platform::fd_source::FdInterest;
+use crate::platform::input_device::InputDevice;
 use crate::platform::pump_events::PumpStatus;
+use crate::platform::seat::Seat;
+#[cfg(target_os = "linux")]
+use crate::platform_impl::common::gamepad::{GamepadId, Gamepads};
+use crate::platform_impl::common::stream_waker::StreamWaker;
 use crate::platform_impl::platform::min_timeout;
 use crate::platform_impl::{
     ActiveEventLoop as PlatformActiveEventLoop, OsError, PlatformCustomCursor,
@@ -44,6 +56,9 @@ pub struct EventLoop<T: 'static> {
     /// Has `run` or `run_on_demand` been called or a call to `pump_events` that starts the loop
     loop_running: bool,
 
+    /// The connection to the compositor was lost.
+    connection_lost: bool,
+
     buffer_sink: EventSink,
     compositor_updates: Vec<WindowCompositorUpdate>,
     window_ids: Vec<WindowId>,
@@ -51,6 +66,9 @@ pub struct EventLoop<T: 'static> {
     /// Sender of user events.
     user_events_sender: calloop::channel::Sender<T>,
 
+    /// The waker of the task awaiting the event stream, woken by the proxies.
+    stream_waker: StreamWaker,
+
     // XXX can't remove RefCell out of here, unless we can plumb generics into the `Window`, which
     // we don't really want, since it'll break public API by a lot.
     /// Pending events from the user.
@@ -167,17 +185,20 @@ impl<T: 'static> EventLoop<T> {
             queue_handle,
             control_flow: Cell::new(ControlFlow::default()),
             exit: Cell::new(None),
+            fd_sources: Default::default(),
             state: RefCell::new(winit_state),
         };
 
         let event_loop = Self {
             loop_running: false,
+            connection_lost: false,
             compositor_updates: Vec::new(),
             buffer_sink: EventSink::default(),
             window_ids: Vec::new(),
             connection,
             wayland_dispatcher,
             user_events_sender,
+            stream_waker: StreamWaker::new(),
             pending_user_events,
             event_loop,
             window_target: RootActiveEventLoop {
@@ -195,6 +216,9 @@ impl<T: 'static> EventLoop<T> {
     {
         let exit = loop {
             match self.pump_events(None, &mut event_handler) {
+                PumpStatus::Exit(_) if self.connection_lost => {
+                    return Err(EventLoopError::ConnectionLost);
+                }
                 PumpStatus::Exit(0) => {
                     break Ok(());
                 }
@@ -235,6 +259,10 @@ impl<T: 'static> EventLoop<T> {
         if let Some(code) = self.exit_code() {
             self.loop_running = false;
 
+            if self.connection_lost {
+                callback(Event::ConnectionLost, self.window_target());
+            }
+
             callback(Event::LoopExiting, self.window_target());
 
             PumpStatus::Exit(code)
@@ -268,19 +296,18 @@ impl<T: 'static> EventLoop<T> {
             // Checking for flush error is essential to perform an exit with error, since
             // once we have a protocol error, we could get stuck retrying...
             if self.connection.flush().is_err() {
-                self.set_exit_code(1);
+                self.exit_with_error(1);
                 return;
             }
 
             if let Err(error) = self.loop_dispatch(timeout) {
                 // NOTE We exit on errors from dispatches, since if we've got protocol error
                 // libwayland-client/wayland-rs will inform us anyway, but crashing downstream is not
-                // really an option. Instead we inform that the event loop got destroyed. We may
-                // communicate an error that something was terminated, but winit doesn't provide us
-                // with an API to do that via some event.
+                // really an option. Instead we inform that the event loop got destroyed, with
+                // `Event::ConnectionLost` when the compositor went away.
                 // Still, we set the exit code to the error's OS error code, or to 1 if not possible.
                 let exit_code = error.raw_os_error().unwrap_or(1);
-                self.set_exit_code(exit_code);
+                self.exit_with_error(exit_code);
                 return;
             }
 
@@ -347,8 +374,16 @@ impl<T: 'static> EventLoop<T> {
             callback(Event::UserEvent(user_event), &self.window_target);
         }
 
-        // Drain the pending compositor updates.
-        self.with_state(|state| compositor_updates.append(&mut state.window_compositor_updates));
+        // Drain the pending compositor updates, along with the resizes done by the client.
+        self.with_state(|state| {
+            for (window_id, window_requests) in state.window_requests.get_mut().iter() {
+                if window_requests.take_resize_requested() {
+                    WinitState::queue_resize(&mut state.window_compositor_updates, *window_id);
+                }
+            }
+
+            compositor_updates.append(&mut state.window_compositor_updates)
+        });
 
         for mut compositor_update in compositor_updates.drain(..) {
             let window_id = compositor_update.window_id;
@@ -428,6 +463,22 @@ impl<T: 'static> EventLoop<T> {
                 );
             }
 
+            if compositor_update.state_changed {
+                let window_state = self.with_state(|state| {
+                    let windows = state.windows.get_mut();
+                    let window = windows.get(&window_id).unwrap().lock().unwrap();
+                    window.window_state()
+                });
+
+                callback(
+                    Event::WindowEvent {
+                        window_id: crate::window::WindowId(window_id),
+                        event: WindowEvent::StateChanged(window_state),
+                    },
+                    &self.window_target,
+                );
+            }
+
             if compositor_update.close_window {
                 callback(
                     Event::WindowEvent {
@@ -453,6 +504,13 @@ impl<T: 'static> EventLoop<T> {
             buffer_sink.append(&mut state.events_sink);
         });
         for event in buffer_sink.drain() {
+            if let Event::FdReady { token, .. } = event {
+                if !self.fd_registered(token) {
+                    // Unregistered while the event was pending.
+                    continue;
+                }
+            }
+
             let event = event.map_nonuser_event().unwrap();
             callback(event, &self.window_target);
         }
@@ -558,7 +616,11 @@ impl<T: 'static> EventLoop<T> {
 
     #[inline]
     pub fn create_proxy(&self) -> EventLoopProxy<T> {
-        EventLoopProxy::new(self.user_events_sender.clone())
+        EventLoopProxy::new(self.user_events_sender.clone(), self.stream_waker.clone())
+    }
+
+    pub(crate) fn stream_waker(&self) -> &StreamWaker {
+        &self.stream_waker
     }
 
     #[inline]
@@ -566,6 +628,52 @@ impl<T: 'static> EventLoop<T> {
         &self.window_target
     }
 
+    #[cfg(target_os = "linux")]
+    pub(crate) fn enable_gamepads(&mut self) {
+        let gamepads = match Gamepads::new() {
+            Ok(gamepads) => Rc::new(gamepads),
+            Err(error) => {
+                log::warn!("Failed to read the gamepads: {error}");
+                return;
+            }
+        };
+
+        // SAFETY: the gamepads are kept alive by the callback of the source.
+        let fd = unsafe { BorrowedFd::borrow_raw(gamepads.as_fd().as_raw_fd()) };
+        let source = Generic::new(fd, calloop::Interest::READ, calloop::Mode::Level);
+        let source_gamepads = gamepads.clone();
+        let result =
+            self.event_loop
+                .handle()
+                .insert_source(source, move |_, _, state: &mut WinitState| {
+                    let allowed = state.device_events_allowed();
+                    source_gamepads.dispatch(|device_id, event| {
+                        // The connection changes are delivered regardless, like for the other devices.
+                        use crate::event::DeviceEvent;
+                        if allowed || matches!(event, DeviceEvent::Added | DeviceEvent::Removed) {
+                            state
+                                .events_sink
+                                .push_external_device_event(event, device_id);
+                            state.dispatched_events = true;
+                        }
+                    });
+                    Ok(calloop::PostAction::Continue)
+                });
+        if let Err(error) = result {
+            log::warn!("Failed to poll the gamepads: {}", error.error);
+            return;
+        }
+
+        self.with_state(|state| {
+            for device_id in gamepads.device_ids() {
+                state
+                    .events_sink
+                    .push_external_device_event(crate::event::DeviceEvent::Added, device_id);
+            }
+            state.gamepads = Some(gamepads);
+        });
+    }
+
     fn with_state<'a, U: 'a, F: FnOnce(&'a mut WinitState) -> U>(&'a mut self, callback: F) -> U {
         let state = match &mut self.window_target.p {
             PlatformActiveEventLoop::Wayland(window_target) => window_target.state.get_mut(),
@@ -576,6 +684,16 @@ impl<T: 'static> EventLoop<T> {
         callback(state)
     }
 
+    fn fd_registered(&self, token: FdToken) -> bool {
+        match &self.window_target.p {
+            PlatformActiveEventLoop::Wayland(window_target) => {
+                window_target.fd_sources.borrow().contains_key(&token)
+            }
+            #[cfg(x11_platform)]
+            _ => unreachable!(),
+        }
+    }
+
     fn loop_dispatch<D: Into<Option<std::time::Duration>>>(&mut self, timeout: D) -> IOResult<()> {
         let state = match &mut self.window_target.p {
             PlatformActiveEventLoop::Wayland(window_target) => window_target.state.get_mut(),
@@ -617,6 +735,17 @@ impl<T: 'static> EventLoop<T> {
         self.window_target.p.set_exit_code(code)
     }
 
+    fn exit_with_error(&mut self, code: i32) {
+        // Protocol errors are on our side, otherwise the compositor went away.
+        if self.connection.protocol_error().is_none() {
+            self.connection_lost = true;
+            // The windows are gone with the connection, allow to reconnect with a new event loop.
+            EventLoopBuilder::<()>::allow_event_loop_recreation();
+        }
+
+        self.set_exit_code(code);
+    }
+
     fn exit_code(&self) -> Option<i32> {
         self.window_target.p.exit_code()
     }
@@ -647,6 +776,9 @@ pub struct ActiveEventLoop {
     /// The application's exit state.
     pub(crate) exit: Cell<Option<i32>>,
 
+    /// The file descriptors registered by the user.
+    pub(crate) fd_sources: RefCell<AHashMap<FdToken, RegistrationToken>>,
+
     // TODO remove that RefCell once we can pass `&mut` in `Window::new`.
     /// Winit state.
     pub state: RefCell<WinitState>,
@@ -688,11 +820,76 @@ impl ActiveEventLoop {
     }
 
     #[inline]
-    pub fn listen_device_events(&self, _allowed: DeviceEvents) {}
+    pub fn listen_device_events(&self, allowed: DeviceEvents) {
+        self.state.borrow_mut().device_events = allowed;
+    }
+
+    pub(crate) unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError> {
+        let token = FdToken::get();
+
+        // SAFETY: the caller guarantees that the fd stays open while registered.
+        let fd = unsafe { BorrowedFd::borrow_raw(fd.as_raw_fd()) };
+        let source = Generic::new(fd, interest.into(), calloop::Mode::Level);
+        let registration = self
+            .state
+            .borrow()
+            .loop_handle
+            .insert_source(source, move |readiness, _, state: &mut WinitState| {
+                // The source could be unregistered while the event is still pending, thus stale
+                // tokens are filtered out when the sink is drained.
+                state.events_sink.push_fd_event(token, readiness.into());
+                state.dispatched_events = true;
+                Ok(calloop::PostAction::Continue)
+            })
+            .map_err(|error| {
+                ExternalError::Os(os_error!(WaylandError::Calloop(error.error).into()))
+            })?;
+
+        self.fd_sources.borrow_mut().insert(token, registration);
+        Ok(token)
+    }
+
+    pub(crate) fn unregister_fd(&self, token: FdToken) {
+        if let Some(registration) = self.fd_sources.borrow_mut().remove(&token) {
+            self.state.borrow().loop_handle.remove(registration);
+        }
+    }
+
+    pub(crate) fn capabilities(&self) -> BackendCapabilities {
+        *self.state.borrow().capabilities.lock().unwrap()
+    }
+
+    pub(crate) fn input_devices(&self) -> Vec<InputDevice> {
+        self.state.borrow().input_devices()
+    }
+
+    pub(crate) fn seats(&self) -> Vec<Seat> {
+        self.state.borrow().seats()
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn set_rumble(
+        &self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match &self.state.borrow().gamepads {
+            Some(gamepads) => gamepads.set_rumble(id, strong, weak, duration),
+            None => Err(ExternalError::NotSupported(
+                crate::error::NotSupportedError::new(),
+            )),
+        }
+    }
 
     pub(crate) fn create_custom_cursor(&self, cursor: CustomCursorSource) -> RootCustomCursor {
         RootCustomCursor {
-            inner: PlatformCustomCursor::Wayland(OnlyCursorImage(Arc::from(cursor.inner.0))),
+            inner: PlatformCustomCursor::Wayland(AnimatedCursorImage(Arc::new(cursor.inner))),
         }
     }
 
@@ -5,28 +5,36 @@ use std::sync::mpsc::SendError;
 use sctk::reexports::calloop::channel::Sender;
 
 use crate::event_loop::EventLoopClosed;
+use crate::platform_impl::common::stream_waker::StreamWaker;
 
 /// A handle that can be sent across the threads and used to wake up the `EventLoop`.
 pub struct EventLoopProxy<T: 'static> {
     user_events_sender: Sender<T>,
+    stream_waker: StreamWaker,
 }
 
 impl<T: 'static> Clone for EventLoopProxy<T> {
     fn clone(&self) -> Self {
         EventLoopProxy {
             user_events_sender: self.user_events_sender.clone(),
+            stream_waker: self.stream_waker.clone(),
         }
     }
 }
 
 impl<T: 'static> EventLoopProxy<T> {
-    pub fn new(user_events_sender: Sender<T>) -> Self {
-        Self { user_events_sender }
+    pub fn new(user_events_sender: Sender<T>, stream_waker: StreamWaker) -> Self {
+        Self {
+            user_events_sender,
+            stream_waker,
+        }
     }
 
     pub fn send_event(&self, event: T) -> Result<(), EventLoopClosed<T>> {
         self.user_events_sender
             .send(event)
-            .map_err(|SendError(error)| EventLoopClosed(error))
+            .map_err(|SendError(error)| EventLoopClosed(error))?;
+        self.stream_waker.wake();
+        Ok(())
     }
 }
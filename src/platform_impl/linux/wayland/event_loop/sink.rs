@@ -3,6 +3,7 @@
 use std::vec::Drain;
 
 use crate::event::{DeviceEvent, DeviceId as RootDeviceId, Event, WindowEvent};
+use crate::event_loop::{FdReadiness, FdToken};
 use crate::platform_impl::platform::DeviceId as PlatformDeviceId;
 use crate::window::WindowId as RootWindowId;
 
@@ -35,6 +36,13 @@ impl EventSink {
         });
     }
 
+    /// Add new device event of a device not handled by the compositor to a queue.
+    #[inline]
+    pub fn push_external_device_event(&mut self, event: DeviceEvent, device_id: RootDeviceId) {
+        self.window_events
+            .push(Event::DeviceEvent { event, device_id });
+    }
+
     /// Add new window event to a queue.
     #[inline]
     pub fn push_window_event(&mut self, event: WindowEvent, window_id: WindowId) {
@@ -44,6 +52,12 @@ impl EventSink {
         });
     }
 
+    /// Add new file descriptor readiness event to a queue.
+    #[inline]
+    pub fn push_fd_event(&mut self, token: FdToken, readiness: FdReadiness) {
+        self.window_events.push(Event::FdReady { token, readiness });
+    }
+
     #[inline]
     pub fn append(&mut self, other: &mut Self) {
         self.window_events.append(&mut other.window_events);
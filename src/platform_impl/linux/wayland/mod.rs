@@ -6,11 +6,13 @@ use std::fmt::Display;
 use std::sync::Arc;
 
 use sctk::reexports::client::globals::{BindError, GlobalError};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{self, ConnectError, DispatchError, Proxy};
 
-pub(super) use crate::cursor::OnlyCursorImage as CustomCursor;
+pub(super) use crate::cursor::AnimatedCursorImage as CustomCursor;
 use crate::dpi::{LogicalSize, PhysicalSize};
+use crate::platform::seat::SeatId;
 pub use crate::platform_impl::platform::{OsError, WindowId};
 pub use event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
 pub use output::{MonitorHandle, VideoModeHandle};
@@ -63,14 +65,31 @@ impl From<WaylandError> for OsError {
     }
 }
 
-/// Dummy device id, since Wayland doesn't have device events.
+/// The device id of a seat, since Wayland doesn't expose the physical devices.
+///
+/// Holds the protocol id of the `wl_seat`, which is never `0`.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct DeviceId;
+pub struct DeviceId(u32);
 
 impl DeviceId {
     pub const unsafe fn dummy() -> Self {
-        DeviceId
+        DeviceId(0)
     }
+
+    /// The device id of the given seat.
+    pub fn from_seat(seat: &WlSeat) -> Self {
+        DeviceId(seat.id().protocol_id())
+    }
+
+    /// The seat of the device.
+    pub fn seat(self) -> Option<SeatId> {
+        (self.0 != 0).then_some(SeatId(self.0))
+    }
+}
+
+/// The id of the given seat.
+fn seat_id(seat: &WlSeat) -> SeatId {
+    SeatId(seat.id().protocol_id())
 }
 
 /// Get the WindowId out of the surface.
@@ -1,7 +1,7 @@
 //! The keyboard input handling.
 
 use std::sync::Mutex;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use calloop::timer::{TimeoutAction, Timer};
 use calloop::{LoopHandle, RegistrationToken};
@@ -14,10 +14,10 @@ use sctk::reexports::client::protocol::wl_keyboard::{
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
 
-use crate::event::{ElementState, WindowEvent};
+use crate::event::{DeviceEvent, ElementState, RawKeyEvent, WindowEvent};
 use crate::keyboard::ModifiersState;
 
-use crate::platform_impl::common::xkb::Context;
+use crate::platform_impl::common::xkb::{self, Context};
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::seat::WinitSeatState;
 use crate::platform_impl::wayland::state::WinitState;
@@ -32,6 +32,9 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
         _: &Connection,
         _: &QueueHandle<WinitState>,
     ) {
+        let device_events =
+            matches!(event, WlKeyboardEvent::Key { .. }) && state.device_events_allowed();
+
         let seat_state = match state.seats.get_mut(&data.seat.id()) {
             Some(seat_state) => seat_state,
             None => return,
@@ -132,9 +135,21 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
             WlKeyboardEvent::Key {
                 key,
                 state: WEnum::Value(WlKeyState::Pressed),
+                time,
                 ..
             } => {
                 let key = key + 8;
+                let timestamp = Some(state.server_clock.instant(time));
+
+                if device_events {
+                    let event = DeviceEvent::Key(RawKeyEvent {
+                        physical_key: xkb::raw_keycode_to_physicalkey(key),
+                        state: ElementState::Pressed,
+                    });
+                    state
+                        .events_sink
+                        .push_device_event(event, DeviceId::from_seat(&data.seat));
+                }
 
                 key_input(
                     seat_state,
@@ -143,6 +158,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     key,
                     ElementState::Pressed,
                     false,
+                    timestamp,
                 );
 
                 let keyboard_state = seat_state.keyboard_state.as_mut().unwrap();
@@ -194,6 +210,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                             repeat_keycode,
                             ElementState::Pressed,
                             true,
+                            None,
                         );
 
                         // NOTE: the gap could change dynamically while repeat is going.
@@ -207,9 +224,21 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
             WlKeyboardEvent::Key {
                 key,
                 state: WEnum::Value(WlKeyState::Released),
+                time,
                 ..
             } => {
                 let key = key + 8;
+                let timestamp = Some(state.server_clock.instant(time));
+
+                if device_events {
+                    let event = DeviceEvent::Key(RawKeyEvent {
+                        physical_key: xkb::raw_keycode_to_physicalkey(key),
+                        state: ElementState::Released,
+                    });
+                    state
+                        .events_sink
+                        .push_device_event(event, DeviceId::from_seat(&data.seat));
+                }
 
                 key_input(
                     seat_state,
@@ -218,6 +247,7 @@ impl Dispatch<WlKeyboard, KeyboardData, WinitState> for WinitState {
                     key,
                     ElementState::Released,
                     false,
+                    timestamp,
                 );
 
                 let keyboard_state = seat_state.keyboard_state.as_mut().unwrap();
@@ -386,6 +416,7 @@ fn key_input(
     keycode: u32,
     state: ElementState,
     repeat: bool,
+    timestamp: Option<Instant>,
 ) {
     let window_id = match *data.window_id.lock().unwrap() {
         Some(window_id) => window_id,
@@ -394,9 +425,11 @@ fn key_input(
 
     let keyboard_state = seat_state.keyboard_state.as_mut().unwrap();
 
-    let device_id = crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(DeviceId));
+    let device_id = crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
+        DeviceId::from_seat(&data.seat),
+    ));
     if let Some(mut key_context) = keyboard_state.xkb_context.key_context() {
-        let event = key_context.process_key_event(keycode, state, repeat);
+        let event = key_context.process_key_event(keycode, state, repeat, timestamp);
         let event = WindowEvent::KeyboardInput {
             device_id,
             event,
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use ahash::AHashMap;
 
 use sctk::reexports::client::backend::ObjectId;
+use sctk::reexports::client::protocol::wl_pointer::WlPointer;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_touch::WlTouch;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
@@ -16,7 +17,10 @@ use sctk::seat::{Capability as SeatCapability, SeatHandler, SeatState};
 
 use crate::event::WindowEvent;
 use crate::keyboard::ModifiersState;
+use crate::platform::input_device::{InputDevice, InputDeviceCapabilities, InputDeviceKind};
+use crate::platform::seat::Seat;
 use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, DeviceId};
 
 mod keyboard;
 mod pointer;
@@ -24,6 +28,7 @@ mod text_input;
 mod touch;
 
 pub use pointer::relative_pointer::RelativePointerState;
+pub use pointer::scroll_pointer::ScrollPointerState;
 pub use pointer::{PointerConstraintsState, WinitPointerData, WinitPointerDataExt};
 pub use text_input::{TextInputState, ZwpTextInputV3Ext};
 
@@ -36,6 +41,9 @@ pub struct WinitSeatState {
     /// The pointer bound on the seat.
     pointer: Option<Arc<ThemedPointer<WinitPointerData>>>,
 
+    /// The pointer delivering the high-resolution scroll of the seat.
+    scroll_pointer: Option<WlPointer>,
+
     /// The touch bound on the seat.
     touch: Option<WlTouch>,
 
@@ -64,6 +72,80 @@ impl WinitSeatState {
     }
 }
 
+impl WinitState {
+    /// List the capabilities of the seats as input devices.
+    pub fn input_devices(&self) -> Vec<InputDevice> {
+        let mut devices = Vec::new();
+        for seat in self.seat_state.seats() {
+            let info = match self.seat_state.info(&seat) {
+                Some(info) => info,
+                None => continue,
+            };
+
+            let device_id = crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
+                DeviceId::from_seat(&seat),
+            ));
+            let name = info.name.unwrap_or_else(|| String::from("seat"));
+            let mut push_device = |kind, capabilities| {
+                devices.push(InputDevice {
+                    id: device_id,
+                    name: name.clone(),
+                    kind,
+                    vendor_id: None,
+                    product_id: None,
+                    capabilities,
+                })
+            };
+
+            if info.has_keyboard {
+                push_device(InputDeviceKind::Keyboard, InputDeviceCapabilities::KEYS);
+            }
+
+            if info.has_pointer {
+                let mut capabilities =
+                    InputDeviceCapabilities::BUTTONS | InputDeviceCapabilities::SCROLL;
+                capabilities.set(
+                    InputDeviceCapabilities::RELATIVE_MOTION,
+                    self.relative_pointer.is_some(),
+                );
+                push_device(InputDeviceKind::Pointer, capabilities);
+            }
+
+            if info.has_touch {
+                push_device(
+                    InputDeviceKind::Touchscreen,
+                    InputDeviceCapabilities::TOUCH | InputDeviceCapabilities::ABSOLUTE_MOTION,
+                );
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(gamepads) = &self.gamepads {
+            devices.extend(gamepads.input_devices());
+        }
+
+        devices
+    }
+
+    /// List the seats.
+    pub fn seats(&self) -> Vec<Seat> {
+        self.seat_state
+            .seats()
+            .map(|seat| {
+                let name = self
+                    .seat_state
+                    .info(&seat)
+                    .and_then(|info| info.name)
+                    .unwrap_or_else(|| String::from("seat"));
+                Seat {
+                    id: wayland::seat_id(&seat),
+                    name,
+                }
+            })
+            .collect()
+    }
+}
+
 impl SeatHandler for WinitState {
     fn seat_state(&mut self) -> &mut SeatState {
         &mut self.seat_state
@@ -131,7 +213,7 @@ impl SeatHandler for WinitState {
             seat_state.text_input = Some(Arc::new(text_input_state.get_text_input(
                 &seat,
                 queue_handle,
-                TextInputData::default(),
+                TextInputData::new(seat.clone()),
             )));
         }
     }
@@ -201,7 +283,13 @@ impl SeatHandler for WinitState {
         _queue_handle: &QueueHandle<Self>,
         seat: WlSeat,
     ) {
-        let _ = self.seats.remove(&seat.id());
+        if let Some(pointer) = self
+            .seats
+            .remove(&seat.id())
+            .and_then(|seat_state| seat_state.scroll_pointer)
+        {
+            pointer.release();
+        }
         self.on_keyboard_destroy(&seat.id());
     }
 }
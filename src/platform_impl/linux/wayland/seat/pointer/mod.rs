@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use sctk::reexports::client::delegate_dispatch;
-use sctk::reexports::client::protocol::wl_pointer::WlPointer;
+use sctk::reexports::client::protocol::wl_pointer::{AxisSource, WlPointer};
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle, Dispatch};
@@ -19,17 +19,21 @@ use sctk::reexports::csd_frame::FrameClick;
 
 use sctk::compositor::SurfaceData;
 use sctk::globals::GlobalData;
-use sctk::seat::pointer::{PointerData, PointerDataExt};
+use sctk::seat::pointer::{AxisScroll, PointerData, PointerDataExt};
 use sctk::seat::pointer::{PointerEvent, PointerEventKind, PointerHandler};
 use sctk::seat::SeatState;
 
 use crate::dpi::{LogicalPosition, PhysicalPosition};
-use crate::event::{ElementState, MouseButton, MouseScrollDelta, TouchPhase, WindowEvent};
+use crate::event::{
+    DeviceEvent, ElementState, MouseButton, MouseScrollDelta, MouseScrollSource, TouchPhase,
+    WindowEvent,
+};
 
 use crate::platform_impl::wayland::state::WinitState;
 use crate::platform_impl::wayland::{self, DeviceId, WindowId};
 
 pub mod relative_pointer;
+pub mod scroll_pointer;
 
 impl PointerHandler for WinitState {
     fn pointer_frame(
@@ -39,12 +43,27 @@ impl PointerHandler for WinitState {
         pointer: &WlPointer,
         events: &[PointerEvent],
     ) {
+        let device_events = self.device_events_allowed();
+
         let seat = pointer.winit_data().seat();
         let seat_state = self.seats.get(&seat.id()).unwrap();
 
-        let device_id = crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(DeviceId));
+        let seat_device_id = DeviceId::from_seat(seat);
+        let device_id =
+            crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(seat_device_id));
 
         for event in events {
+            // The scroll is delivered by the high-resolution pointer of the seat.
+            let scroll_elsewhere = seat_state.scroll_pointer.is_some()
+                && matches!(event.kind, PointerEventKind::Axis { .. });
+            if let Some(device_event) = (device_events && !scroll_elsewhere)
+                .then(|| pointer_device_event(&event.kind))
+                .flatten()
+            {
+                self.events_sink
+                    .push_device_event(device_event, seat_device_id);
+            }
+
             let surface = &event.surface;
 
             // The parent surface.
@@ -129,6 +148,7 @@ impl PointerHandler for WinitState {
                         WindowEvent::CursorMoved {
                             device_id,
                             position,
+                            timestamp: None,
                         },
                         window_id,
                     );
@@ -144,17 +164,27 @@ impl PointerHandler for WinitState {
                     self.events_sink
                         .push_window_event(WindowEvent::CursorLeft { device_id }, window_id);
                 }
-                PointerEventKind::Motion { .. } => {
+                PointerEventKind::Motion { time } => {
+                    let timestamp = Some(self.server_clock.instant(time));
                     self.events_sink.push_window_event(
                         WindowEvent::CursorMoved {
                             device_id,
                             position,
+                            timestamp,
                         },
                         window_id,
                     );
                 }
-                ref kind @ PointerEventKind::Press { button, serial, .. }
-                | ref kind @ PointerEventKind::Release { button, serial, .. } => {
+                ref kind @ PointerEventKind::Press {
+                    button,
+                    serial,
+                    time,
+                }
+                | ref kind @ PointerEventKind::Release {
+                    button,
+                    serial,
+                    time,
+                } => {
                     // Update the last button serial.
                     pointer
                         .winit_data()
@@ -169,11 +199,13 @@ impl PointerHandler for WinitState {
                     } else {
                         ElementState::Released
                     };
+                    let timestamp = Some(self.server_clock.instant(time));
                     self.events_sink.push_window_event(
                         WindowEvent::MouseInput {
                             device_id,
                             state,
                             button,
+                            timestamp,
                         },
                         window_id,
                     );
@@ -181,55 +213,23 @@ impl PointerHandler for WinitState {
                 PointerEventKind::Axis {
                     horizontal,
                     vertical,
+                    source,
                     ..
                 } => {
-                    // Get the current phase.
-                    let mut pointer_data = pointer.winit_data().inner.lock().unwrap();
-
-                    let has_discrete_scroll = horizontal.discrete != 0 || vertical.discrete != 0;
-
-                    // Figure out what to do about start/ended phases here.
-                    //
-                    // Figure out how to deal with `Started`. Also the `Ended` is not guaranteed
-                    // to be sent for mouse wheels.
-                    let phase = if horizontal.stop || vertical.stop {
-                        TouchPhase::Ended
-                    } else {
-                        match pointer_data.phase {
-                            // Discrete scroll only results in moved events.
-                            _ if has_discrete_scroll => TouchPhase::Moved,
-                            TouchPhase::Started | TouchPhase::Moved => TouchPhase::Moved,
-                            _ => TouchPhase::Started,
-                        }
-                    };
-
-                    // Update the phase.
-                    pointer_data.phase = phase;
-
-                    // Mice events have both pixel and discrete delta's at the same time. So prefer
-                    // the descrite values if they are present.
-                    let delta = if has_discrete_scroll {
-                        // XXX Wayland sign convention is the inverse of winit.
-                        MouseScrollDelta::LineDelta(
-                            (-horizontal.discrete) as f32,
-                            (-vertical.discrete) as f32,
-                        )
-                    } else {
-                        // XXX Wayland sign convention is the inverse of winit.
-                        MouseScrollDelta::PixelDelta(
-                            LogicalPosition::new(-horizontal.absolute, -vertical.absolute)
-                                .to_physical(scale_factor),
-                        )
-                    };
+                    // The scroll is delivered by the high-resolution pointer of the seat.
+                    if seat_state.scroll_pointer.is_some() {
+                        continue;
+                    }
 
-                    self.events_sink.push_window_event(
-                        WindowEvent::MouseWheel {
-                            device_id,
-                            delta,
-                            phase,
-                        },
-                        window_id,
-                    )
+                    let frame = ScrollFrame::from_axis(&horizontal, &vertical, source);
+                    let event = pointer
+                        .winit_data()
+                        .inner
+                        .lock()
+                        .unwrap()
+                        .scroll
+                        .mouse_wheel(&frame, device_id, scale_factor);
+                    self.events_sink.push_window_event(event, window_id)
                 }
             }
         }
@@ -352,8 +352,8 @@ pub struct WinitPointerDataInner {
     /// Currently focused window.
     surface: Option<WindowId>,
 
-    /// Current axis phase.
-    phase: TouchPhase,
+    /// The current scroll sequence.
+    scroll: ScrollState,
 }
 
 impl Drop for WinitPointerDataInner {
@@ -375,22 +375,33 @@ impl Default for WinitPointerDataInner {
             locked_pointer: None,
             confined_pointer: None,
             latest_button_serial: 0,
-            phase: TouchPhase::Ended,
+            scroll: ScrollState::default(),
         }
     }
 }
 
+/// Convert the Wayland axis source into winit.
+fn wayland_source_to_winit(source: AxisSource) -> MouseScrollSource {
+    match source {
+        AxisSource::Wheel => MouseScrollSource::Wheel,
+        AxisSource::Finger => MouseScrollSource::Finger,
+        AxisSource::Continuous => MouseScrollSource::Continuous,
+        AxisSource::WheelTilt => MouseScrollSource::WheelTilt,
+        _ => MouseScrollSource::Unknown,
+    }
+}
+
 /// Convert the Wayland button into winit.
-fn wayland_button_to_winit(button: u32) -> MouseButton {
-    // These values are coming from <linux/input-event-codes.h>.
-    const BTN_LEFT: u32 = 0x110;
-    const BTN_RIGHT: u32 = 0x111;
-    const BTN_MIDDLE: u32 = 0x112;
-    const BTN_SIDE: u32 = 0x113;
-    const BTN_EXTRA: u32 = 0x114;
-    const BTN_FORWARD: u32 = 0x115;
-    const BTN_BACK: u32 = 0x116;
+// These values are coming from <linux/input-event-codes.h>.
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+const BTN_SIDE: u32 = 0x113;
+const BTN_EXTRA: u32 = 0x114;
+const BTN_FORWARD: u32 = 0x115;
+const BTN_BACK: u32 = 0x116;
 
+fn wayland_button_to_winit(button: u32) -> MouseButton {
     match button {
         BTN_LEFT => MouseButton::Left,
         BTN_RIGHT => MouseButton::Right,
@@ -401,6 +412,176 @@ fn wayland_button_to_winit(button: u32) -> MouseButton {
     }
 }
 
+/// Map the button to the X11 button number, to report the same raw buttons on both backends.
+///
+/// Follows the numbering of the X11 input drivers, where the buttons after the wheel ones
+/// (4 to 7) are numbered in the evdev order, thus `BTN_FORWARD` is 10, `BTN_BACK` is 11 and
+/// `BTN_TASK` is 12.
+fn wayland_button_to_x11(button: u32) -> u32 {
+    match button {
+        BTN_LEFT => 1,
+        BTN_MIDDLE => 2,
+        BTN_RIGHT => 3,
+        button if button >= BTN_SIDE => button - BTN_SIDE + 8,
+        // The misc buttons aren't sent by pointers and don't have an X11 number.
+        button => button,
+    }
+}
+
+/// The raw device event of the pointer event.
+fn pointer_device_event(kind: &PointerEventKind) -> Option<DeviceEvent> {
+    match *kind {
+        PointerEventKind::Press { button, .. } => Some(DeviceEvent::Button {
+            button: wayland_button_to_x11(button),
+            state: ElementState::Pressed,
+        }),
+        PointerEventKind::Release { button, .. } => Some(DeviceEvent::Button {
+            button: wayland_button_to_x11(button),
+            state: ElementState::Released,
+        }),
+        PointerEventKind::Axis {
+            horizontal,
+            vertical,
+            source,
+            ..
+        } => ScrollFrame::from_axis(&horizontal, &vertical, source).device_event(),
+        _ => None,
+    }
+}
+
+/// The scroll accumulated over a pointer frame, in the Wayland direction.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ScrollFrame {
+    /// The scroll in surface-local coordinates.
+    absolute: (f64, f64),
+
+    /// The wheel steps, in 120ths of a step.
+    value120: (i32, i32),
+
+    /// The scroll sequence was stopped, like when the fingers were lifted.
+    stop: bool,
+
+    /// The source of the scroll, when sent in this frame.
+    source: Option<AxisSource>,
+}
+
+impl ScrollFrame {
+    /// The frame of a pointer bound below the high-resolution scroll, made of whole steps.
+    fn from_axis(
+        horizontal: &AxisScroll,
+        vertical: &AxisScroll,
+        source: Option<AxisSource>,
+    ) -> Self {
+        Self {
+            absolute: (horizontal.absolute, vertical.absolute),
+            value120: (horizontal.discrete * 120, vertical.discrete * 120),
+            stop: horizontal.stop || vertical.stop,
+            source,
+        }
+    }
+
+    fn is_discrete(&self) -> bool {
+        self.value120 != (0, 0)
+    }
+
+    /// The raw wheel event of the frame.
+    fn device_event(&self) -> Option<DeviceEvent> {
+        // XXX Wayland sign convention is the inverse of winit.
+        let delta = if self.is_discrete() {
+            MouseScrollDelta::LineDelta(
+                -self.value120.0 as f32 / 120.,
+                -self.value120.1 as f32 / 120.,
+            )
+        } else if self.absolute != (0., 0.) {
+            // Device events aren't tied to a window, thus aren't scaled.
+            MouseScrollDelta::PixelDelta(PhysicalPosition::new(-self.absolute.0, -self.absolute.1))
+        } else {
+            // Only the end of the scroll.
+            return None;
+        };
+
+        Some(DeviceEvent::MouseWheel { delta })
+    }
+}
+
+/// The state of the scroll sequence of a pointer.
+#[derive(Debug, Clone, Copy)]
+pub struct ScrollState {
+    /// Current axis phase.
+    phase: TouchPhase,
+
+    /// The source of the current scroll sequence.
+    source: MouseScrollSource,
+}
+
+impl Default for ScrollState {
+    fn default() -> Self {
+        Self {
+            phase: TouchPhase::Ended,
+            source: MouseScrollSource::Unknown,
+        }
+    }
+}
+
+impl ScrollState {
+    /// Convert the frame into the wheel event, moving the scroll sequence along.
+    fn mouse_wheel(
+        &mut self,
+        frame: &ScrollFrame,
+        device_id: crate::event::DeviceId,
+        scale_factor: f64,
+    ) -> WindowEvent {
+        // The source isn't necessarily sent in every frame, keep the last one.
+        if let Some(source) = frame.source {
+            self.source = wayland_source_to_winit(source);
+        }
+
+        // Figure out what to do about start/ended phases here.
+        //
+        // Figure out how to deal with `Started`. Also the `Ended` is not guaranteed
+        // to be sent for mouse wheels.
+        let phase = if frame.stop {
+            TouchPhase::Ended
+        } else {
+            match self.phase {
+                // Discrete scroll only results in moved events.
+                _ if frame.is_discrete() => TouchPhase::Moved,
+                TouchPhase::Started | TouchPhase::Moved => TouchPhase::Moved,
+                _ => TouchPhase::Started,
+            }
+        };
+
+        // Update the phase.
+        self.phase = phase;
+
+        // Mice events have both pixel and discrete delta's at the same time. So prefer
+        // the descrite values if they are present.
+        //
+        // XXX Wayland sign convention is the inverse of winit.
+        let (delta, value120) = if frame.is_discrete() {
+            let delta = MouseScrollDelta::LineDelta(
+                -frame.value120.0 as f32 / 120.,
+                -frame.value120.1 as f32 / 120.,
+            );
+            (delta, Some((-frame.value120.0, -frame.value120.1)))
+        } else {
+            let delta = MouseScrollDelta::PixelDelta(
+                LogicalPosition::new(-frame.absolute.0, -frame.absolute.1)
+                    .to_physical(scale_factor),
+            );
+            (delta, None)
+        };
+
+        WindowEvent::MouseWheel {
+            device_id,
+            delta,
+            phase,
+            source: self.source,
+            value120,
+        }
+    }
+}
+
 pub trait WinitPointerDataExt {
     fn winit_data(&self) -> &WinitPointerData;
 }
@@ -503,3 +684,128 @@ delegate_dispatch!(WinitState: [ WpCursorShapeDeviceV1: GlobalData] => SeatState
 delegate_dispatch!(WinitState: [ZwpPointerConstraintsV1: GlobalData] => PointerConstraintsState);
 delegate_dispatch!(WinitState: [ZwpLockedPointerV1: GlobalData] => PointerConstraintsState);
 delegate_dispatch!(WinitState: [ZwpConfinedPointerV1: GlobalData] => PointerConstraintsState);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn button_to_x11() {
+        assert_eq!(wayland_button_to_x11(BTN_LEFT), 1);
+        assert_eq!(wayland_button_to_x11(BTN_MIDDLE), 2);
+        assert_eq!(wayland_button_to_x11(BTN_RIGHT), 3);
+        assert_eq!(wayland_button_to_x11(BTN_SIDE), 8);
+        assert_eq!(wayland_button_to_x11(BTN_EXTRA), 9);
+        assert_eq!(wayland_button_to_x11(BTN_FORWARD), 10);
+        assert_eq!(wayland_button_to_x11(BTN_BACK), 11);
+        // BTN_TASK.
+        assert_eq!(wayland_button_to_x11(0x117), 12);
+    }
+
+    fn mouse_wheel(
+        state: &mut ScrollState,
+        frame: ScrollFrame,
+    ) -> (
+        MouseScrollDelta,
+        TouchPhase,
+        MouseScrollSource,
+        Option<(i32, i32)>,
+    ) {
+        let device_id = unsafe { crate::event::DeviceId::dummy() };
+        match state.mouse_wheel(&frame, device_id, 2.) {
+            WindowEvent::MouseWheel {
+                delta,
+                phase,
+                source,
+                value120,
+                ..
+            } => (delta, phase, source, value120),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn high_resolution_wheel() {
+        let mut state = ScrollState::default();
+        let frame = ScrollFrame {
+            absolute: (0., 5.),
+            value120: (0, 30),
+            stop: false,
+            source: Some(AxisSource::Wheel),
+        };
+
+        assert_eq!(
+            mouse_wheel(&mut state, frame),
+            (
+                MouseScrollDelta::LineDelta(0., -0.25),
+                TouchPhase::Moved,
+                MouseScrollSource::Wheel,
+                Some((0, -30))
+            )
+        );
+        assert_eq!(
+            frame.device_event(),
+            Some(DeviceEvent::MouseWheel {
+                delta: MouseScrollDelta::LineDelta(0., -0.25)
+            })
+        );
+    }
+
+    #[test]
+    fn legacy_wheel() {
+        let vertical = AxisScroll {
+            absolute: 10.,
+            discrete: 1,
+            stop: false,
+        };
+        let frame = ScrollFrame::from_axis(&AxisScroll::default(), &vertical, None);
+
+        assert_eq!(frame.value120, (0, 120));
+        assert_eq!(
+            mouse_wheel(&mut ScrollState::default(), frame),
+            (
+                MouseScrollDelta::LineDelta(0., -1.),
+                TouchPhase::Moved,
+                MouseScrollSource::Unknown,
+                Some((0, -120))
+            )
+        );
+    }
+
+    #[test]
+    fn finger_scroll() {
+        let mut state = ScrollState::default();
+        let frame = ScrollFrame {
+            absolute: (3., 0.),
+            source: Some(AxisSource::Finger),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            mouse_wheel(&mut state, frame),
+            (
+                MouseScrollDelta::PixelDelta(PhysicalPosition::new(-6., 0.)),
+                TouchPhase::Started,
+                MouseScrollSource::Finger,
+                None
+            )
+        );
+        // The source is kept along the sequence.
+        let frame = ScrollFrame {
+            absolute: (1., 0.),
+            ..Default::default()
+        };
+        let (_, phase, source, _) = mouse_wheel(&mut state, frame);
+        assert_eq!(
+            (phase, source),
+            (TouchPhase::Moved, MouseScrollSource::Finger)
+        );
+        // The fingers were lifted.
+        let frame = ScrollFrame {
+            stop: true,
+            ..Default::default()
+        };
+        let (_, phase, _, _) = mouse_wheel(&mut state, frame);
+        assert_eq!(phase, TouchPhase::Ended);
+    }
+}
@@ -54,39 +54,60 @@ impl Dispatch<ZwpRelativePointerManagerV1, GlobalData, WinitState> for RelativeP
 impl Dispatch<ZwpRelativePointerV1, GlobalData, WinitState> for RelativePointerState {
     fn event(
         state: &mut WinitState,
-        _proxy: &ZwpRelativePointerV1,
+        proxy: &ZwpRelativePointerV1,
         event: <ZwpRelativePointerV1 as wayland_client::Proxy>::Event,
         _data: &GlobalData,
         _conn: &Connection,
         _qhandle: &QueueHandle<WinitState>,
     ) {
-        let (dx_unaccel, dy_unaccel) = match event {
+        let (utime, dx_unaccel, dy_unaccel) = match event {
             zwp_relative_pointer_v1::Event::RelativeMotion {
+                utime_hi,
+                utime_lo,
                 dx_unaccel,
                 dy_unaccel,
                 ..
-            } => (dx_unaccel, dy_unaccel),
+            } => (
+                (utime_hi as u64) << 32 | utime_lo as u64,
+                dx_unaccel,
+                dy_unaccel,
+            ),
             _ => return,
         };
+
+        if !state.device_events_allowed() {
+            return;
+        }
+
+        // The relative pointer is only bound to its seat on the compositor side.
+        let device_id = state
+            .seats
+            .iter()
+            .find(|(_, seat_state)| seat_state.relative_pointer.as_ref() == Some(proxy))
+            .map(|(seat, _)| super::DeviceId(seat.protocol_id()))
+            .unwrap_or(unsafe { super::DeviceId::dummy() });
+
         state.events_sink.push_device_event(
             DeviceEvent::Motion {
                 axis: 0,
                 value: dx_unaccel,
             },
-            super::DeviceId,
+            device_id,
         );
         state.events_sink.push_device_event(
             DeviceEvent::Motion {
                 axis: 1,
                 value: dy_unaccel,
             },
-            super::DeviceId,
+            device_id,
         );
+        let timestamp = Some(state.relative_pointer_clock.instant(utime));
         state.events_sink.push_device_event(
             DeviceEvent::MouseMotion {
                 delta: (dx_unaccel, dy_unaccel),
+                timestamp,
             },
-            super::DeviceId,
+            device_id,
         );
     }
 }
@@ -0,0 +1,281 @@
+//! High-resolution scroll.
+//!
+//! The seats are bound by sctk below the version sending `wl_pointer.axis_value120`, thus the
+//! seats providing it are bound once more, with a pointer only delivering the scroll while the
+//! pointer of sctk delivers the rest.
+
+use std::mem;
+use std::sync::Mutex;
+
+use ahash::AHashMap;
+
+use sctk::reexports::client::globals::GlobalList;
+use sctk::reexports::client::protocol::wl_pointer::{self, WlPointer};
+use sctk::reexports::client::protocol::wl_registry::WlRegistry;
+use sctk::reexports::client::protocol::wl_seat::{self, WlSeat};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::{delegate_dispatch, Dispatch, WEnum};
+use sctk::reexports::client::{Connection, Proxy, QueueHandle};
+
+use sctk::compositor::SurfaceData;
+
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::wayland::{self, DeviceId};
+
+use super::{ScrollFrame, ScrollState};
+
+/// The first version of the seat sending `wl_pointer.axis_value120`.
+const SEAT_VERSION: u32 = 8;
+
+/// The seats bound with the high-resolution scroll.
+#[derive(Debug, Default)]
+pub struct ScrollPointerState {
+    /// The seats by the name of their global.
+    seats: AHashMap<u32, WlSeat>,
+}
+
+impl ScrollPointerState {
+    /// Bind the seats providing the high-resolution scroll.
+    pub fn new(globals: &GlobalList, queue_handle: &QueueHandle<WinitState>) -> Self {
+        let names: Vec<(u32, u32)> = globals.contents().with_list(|globals| {
+            globals
+                .iter()
+                .filter(|global| global.interface == WlSeat::interface().name)
+                .map(|global| (global.name, global.version))
+                .collect()
+        });
+
+        let mut state = Self::default();
+        for (name, version) in names {
+            state.bind(globals.registry(), name, version, queue_handle);
+        }
+        state
+    }
+
+    /// Bind the seat global of the given name when it provides the high-resolution scroll.
+    pub fn bind(
+        &mut self,
+        registry: &WlRegistry,
+        name: u32,
+        version: u32,
+        queue_handle: &QueueHandle<WinitState>,
+    ) {
+        if version < SEAT_VERSION {
+            return;
+        }
+
+        let seat = registry.bind(name, SEAT_VERSION, queue_handle, ScrollSeatData::default());
+        self.seats.insert(name, seat);
+    }
+
+    /// Remove the seat global of the given name.
+    pub fn remove(&mut self, name: u32) -> Option<WlSeat> {
+        self.seats.remove(&name)
+    }
+}
+
+/// The data of a seat bound with the high-resolution scroll.
+#[derive(Debug, Default)]
+pub struct ScrollSeatData {
+    inner: Mutex<ScrollSeatDataInner>,
+}
+
+#[derive(Debug, Default)]
+struct ScrollSeatDataInner {
+    /// The name of the seat.
+    name: Option<String>,
+
+    /// Whether the seat has a pointer.
+    has_pointer: bool,
+
+    /// The seat bound by sctk with the same name.
+    seat: Option<WlSeat>,
+}
+
+/// The data of a pointer only delivering the scroll.
+#[derive(Debug)]
+pub struct ScrollPointerData {
+    /// The seat bound by sctk, identifying the device.
+    seat: WlSeat,
+
+    inner: Mutex<ScrollPointerDataInner>,
+}
+
+impl ScrollPointerData {
+    fn new(seat: WlSeat) -> Self {
+        Self {
+            seat,
+            inner: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ScrollPointerDataInner {
+    /// The surface the pointer is over.
+    surface: Option<WlSurface>,
+
+    /// The scroll of the pending frame.
+    frame: ScrollFrame,
+
+    /// The current scroll sequence.
+    scroll: ScrollState,
+}
+
+impl WinitState {
+    /// Create or release the scroll pointer once the seat is matched with the one of sctk.
+    fn update_scroll_pointer(&mut self, scroll_seat: &WlSeat, queue_handle: &QueueHandle<Self>) {
+        let mut inner = scroll_seat
+            .data::<ScrollSeatData>()
+            .unwrap()
+            .inner
+            .lock()
+            .unwrap();
+
+        // The names of the seats are unique. The seat of sctk is bound first, thus its name is
+        // known by the time the one of this seat is.
+        if inner.seat.is_none() && inner.name.is_some() {
+            inner.seat = self.seat_state.seats().find(|seat| {
+                self.seat_state
+                    .info(seat)
+                    .is_some_and(|info| info.name == inner.name)
+            });
+        }
+
+        let seat = match inner.seat.as_ref() {
+            Some(seat) => seat,
+            None => return,
+        };
+        let seat_state = match self.seats.get_mut(&seat.id()) {
+            Some(seat_state) => seat_state,
+            None => return,
+        };
+
+        if !inner.has_pointer {
+            if let Some(pointer) = seat_state.scroll_pointer.take() {
+                pointer.release();
+            }
+        } else if seat_state.scroll_pointer.is_none() {
+            let data = ScrollPointerData::new(seat.clone());
+            seat_state.scroll_pointer = Some(scroll_seat.get_pointer(queue_handle, data));
+        }
+    }
+
+    /// Deliver the scroll of the frame.
+    fn scroll_frame(
+        &mut self,
+        pointer: &WlPointer,
+        surface: Option<WlSurface>,
+        frame: ScrollFrame,
+    ) {
+        let data = pointer.data::<ScrollPointerData>().unwrap();
+        let seat_device_id = DeviceId::from_seat(&data.seat);
+
+        if self.device_events_allowed() {
+            if let Some(device_event) = frame.device_event() {
+                self.events_sink
+                    .push_device_event(device_event, seat_device_id);
+            }
+        }
+
+        // The parent surface.
+        let surface = match surface {
+            Some(surface) => surface,
+            None => return,
+        };
+        let parent_surface = match surface.data::<SurfaceData>() {
+            Some(data) => data.parent_surface().unwrap_or(&surface),
+            None => return,
+        };
+
+        let window_id = wayland::make_wid(parent_surface);
+        let scale_factor = match self.windows.get_mut().get(&window_id) {
+            Some(window) => window.lock().unwrap().scale_factor(),
+            None => return,
+        };
+
+        let device_id =
+            crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(seat_device_id));
+        let event = data
+            .inner
+            .lock()
+            .unwrap()
+            .scroll
+            .mouse_wheel(&frame, device_id, scale_factor);
+        self.events_sink.push_window_event(event, window_id);
+    }
+}
+
+impl Dispatch<WlSeat, ScrollSeatData, WinitState> for ScrollPointerState {
+    fn event(
+        state: &mut WinitState,
+        seat: &WlSeat,
+        event: wl_seat::Event,
+        data: &ScrollSeatData,
+        _: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            wl_seat::Event::Name { name } => inner.name = Some(name),
+            wl_seat::Event::Capabilities {
+                capabilities: WEnum::Value(capabilities),
+            } => inner.has_pointer = capabilities.contains(wl_seat::Capability::Pointer),
+            _ => return,
+        }
+
+        drop(inner);
+        state.update_scroll_pointer(seat, queue_handle);
+    }
+}
+
+impl Dispatch<WlPointer, ScrollPointerData, WinitState> for ScrollPointerState {
+    fn event(
+        state: &mut WinitState,
+        pointer: &WlPointer,
+        event: wl_pointer::Event,
+        data: &ScrollPointerData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        let mut inner = data.inner.lock().unwrap();
+        match event {
+            wl_pointer::Event::Enter { surface, .. } => inner.surface = Some(surface),
+            wl_pointer::Event::Leave { .. } => inner.surface = None,
+            wl_pointer::Event::Axis {
+                axis: WEnum::Value(axis),
+                value,
+                ..
+            } => match axis {
+                wl_pointer::Axis::HorizontalScroll => inner.frame.absolute.0 += value,
+                wl_pointer::Axis::VerticalScroll => inner.frame.absolute.1 += value,
+                _ => (),
+            },
+            wl_pointer::Event::AxisValue120 {
+                axis: WEnum::Value(axis),
+                value120,
+            } => match axis {
+                wl_pointer::Axis::HorizontalScroll => inner.frame.value120.0 += value120,
+                wl_pointer::Axis::VerticalScroll => inner.frame.value120.1 += value120,
+                _ => (),
+            },
+            wl_pointer::Event::AxisSource {
+                axis_source: WEnum::Value(source),
+            } => inner.frame.source = Some(source),
+            wl_pointer::Event::AxisStop { .. } => inner.frame.stop = true,
+            wl_pointer::Event::Frame => {
+                let frame = mem::take(&mut inner.frame);
+                let surface = inner.surface.clone();
+                drop(inner);
+
+                if frame != ScrollFrame::default() {
+                    state.scroll_frame(pointer, surface, frame);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+delegate_dispatch!(WinitState: [WlSeat: ScrollSeatData] => ScrollPointerState);
+delegate_dispatch!(WinitState: [WlPointer: ScrollPointerData] => ScrollPointerState);
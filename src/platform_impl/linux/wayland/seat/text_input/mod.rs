@@ -5,6 +5,7 @@ use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 
 use sctk::reexports::client::delegate_dispatch;
 use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::Dispatch;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_manager_v3::ZwpTextInputManagerV3;
@@ -14,6 +15,7 @@ use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::
 };
 
 use crate::event::{Ime, WindowEvent};
+use crate::platform::seat::SeatId;
 use crate::platform_impl::wayland;
 use crate::platform_impl::wayland::state::WinitState;
 use crate::window::ImePurpose;
@@ -73,7 +75,17 @@ impl Dispatch<ZwpTextInputV3, TextInputData, WinitState> for TextInputState {
                     None => return,
                 };
 
-                if window.ime_allowed() {
+                let seat = text_input.seat_id();
+                if let Some((position, size)) = window.seat_ime_cursor_area(seat) {
+                    text_input.set_cursor_rectangle(
+                        position.x as i32,
+                        position.y as i32,
+                        size.width as i32,
+                        size.height as i32,
+                    );
+                }
+
+                if window.seat_ime_allowed(seat) {
                     text_input.enable();
                     text_input.set_content_type_by_purpose(window.ime_purpose());
                     text_input.commit();
@@ -170,6 +182,9 @@ impl Dispatch<ZwpTextInputV3, TextInputData, WinitState> for TextInputState {
 
 pub trait ZwpTextInputV3Ext {
     fn set_content_type_by_purpose(&self, purpose: ImePurpose);
+
+    /// The seat the text input was created for.
+    fn seat_id(&self) -> SeatId;
 }
 
 impl ZwpTextInputV3Ext for ZwpTextInputV3 {
@@ -181,12 +196,30 @@ impl ZwpTextInputV3Ext for ZwpTextInputV3 {
         };
         self.set_content_type(hint, purpose);
     }
+
+    fn seat_id(&self) -> SeatId {
+        let data = self
+            .data::<TextInputData>()
+            .expect("failed to get text input data.");
+        wayland::seat_id(&data.seat)
+    }
 }
 
 /// The Data associated with the text input.
-#[derive(Default)]
 pub struct TextInputData {
     inner: std::sync::Mutex<TextInputDataInner>,
+
+    /// The seat used to create this text input.
+    seat: WlSeat,
+}
+
+impl TextInputData {
+    pub fn new(seat: WlSeat) -> Self {
+        Self {
+            inner: Default::default(),
+            seat,
+        }
+    }
 }
 
 #[derive(Default)]
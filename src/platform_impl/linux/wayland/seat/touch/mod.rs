@@ -20,7 +20,7 @@ impl TouchHandler for WinitState {
         _: &QueueHandle<Self>,
         touch: &WlTouch,
         _: u32,
-        _: u32,
+        time: u32,
         surface: WlSurface,
         id: i32,
         position: (f64, f64),
@@ -40,15 +40,17 @@ impl TouchHandler for WinitState {
             .touch_map
             .insert(id, TouchPoint { surface, location });
 
+        let timestamp = Some(self.server_clock.instant(time));
         self.events_sink.push_window_event(
             WindowEvent::Touch(Touch {
                 device_id: crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
-                    DeviceId,
+                    DeviceId::from_seat(touch.seat()),
                 )),
                 phase: TouchPhase::Started,
                 location: location.to_physical(scale_factor),
                 force: None,
                 id: id as u64,
+                timestamp,
             }),
             window_id,
         );
@@ -60,7 +62,7 @@ impl TouchHandler for WinitState {
         _: &QueueHandle<Self>,
         touch: &WlTouch,
         _: u32,
-        _: u32,
+        time: u32,
         id: i32,
     ) {
         let seat_state = self.seats.get_mut(&touch.seat().id()).unwrap();
@@ -77,15 +79,17 @@ impl TouchHandler for WinitState {
             None => return,
         };
 
+        let timestamp = Some(self.server_clock.instant(time));
         self.events_sink.push_window_event(
             WindowEvent::Touch(Touch {
                 device_id: crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
-                    DeviceId,
+                    DeviceId::from_seat(touch.seat()),
                 )),
                 phase: TouchPhase::Ended,
                 location: touch_point.location.to_physical(scale_factor),
                 force: None,
                 id: id as u64,
+                timestamp,
             }),
             window_id,
         );
@@ -96,7 +100,7 @@ impl TouchHandler for WinitState {
         _: &Connection,
         _: &QueueHandle<Self>,
         touch: &WlTouch,
-        _: u32,
+        time: u32,
         id: i32,
         position: (f64, f64),
     ) {
@@ -116,15 +120,17 @@ impl TouchHandler for WinitState {
 
         touch_point.location = LogicalPosition::<f64>::from(position);
 
+        let timestamp = Some(self.server_clock.instant(time));
         self.events_sink.push_window_event(
             WindowEvent::Touch(Touch {
                 device_id: crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
-                    DeviceId,
+                    DeviceId::from_seat(touch.seat()),
                 )),
                 phase: TouchPhase::Moved,
                 location: touch_point.location.to_physical(scale_factor),
                 force: None,
                 id: id as u64,
+                timestamp,
             }),
             window_id,
         );
@@ -145,12 +151,13 @@ impl TouchHandler for WinitState {
             self.events_sink.push_window_event(
                 WindowEvent::Touch(Touch {
                     device_id: crate::event::DeviceId(crate::platform_impl::DeviceId::Wayland(
-                        DeviceId,
+                        DeviceId::from_seat(touch.seat()),
                     )),
                     phase: TouchPhase::Cancelled,
                     location,
                     force: None,
                     id: id as u64,
+                    timestamp: None,
                 }),
                 window_id,
             );
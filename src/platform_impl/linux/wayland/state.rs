@@ -4,16 +4,19 @@ use std::sync::{Arc, Mutex};
 
 use ahash::AHashMap;
 
-use sctk::reexports::calloop::LoopHandle;
+use sctk::reexports::calloop::channel::{self, Sender};
+use sctk::reexports::calloop::timer::{TimeoutAction, Timer};
+use sctk::reexports::calloop::{LoopHandle, RegistrationToken};
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::globals::GlobalList;
 use sctk::reexports::client::protocol::wl_output::WlOutput;
+use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 
 use sctk::compositor::{CompositorHandler, CompositorState};
 use sctk::output::{OutputHandler, OutputState};
-use sctk::registry::{ProvidesRegistryState, RegistryState};
+use sctk::registry::{ProvidesRegistryState, RegistryHandler, RegistryState};
 use sctk::seat::pointer::ThemedPointer;
 use sctk::seat::SeatState;
 use sctk::shell::xdg::window::{Window, WindowConfigure, WindowHandler};
@@ -23,16 +26,25 @@ use sctk::shm::slot::SlotPool;
 use sctk::shm::{Shm, ShmHandler};
 use sctk::subcompositor::SubcompositorState;
 
+use crate::event_loop::DeviceEvents;
+use crate::platform::backend::BackendCapabilities;
+#[cfg(target_os = "linux")]
+use crate::platform_impl::common::gamepad::Gamepads;
+use crate::platform_impl::common::timestamp::{MicrosecondServerClock, ServerClock};
 use crate::platform_impl::wayland::event_loop::sink::EventSink;
 use crate::platform_impl::wayland::output::MonitorHandle;
 use crate::platform_impl::wayland::seat::{
-    PointerConstraintsState, RelativePointerState, TextInputState, WinitPointerData,
-    WinitPointerDataExt, WinitSeatState,
+    PointerConstraintsState, RelativePointerState, ScrollPointerState, TextInputState,
+    WinitPointerData, WinitPointerDataExt, WinitSeatState,
 };
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
+use crate::platform_impl::wayland::types::wp_alpha_modifier::AlphaModifierManager;
 use crate::platform_impl::wayland::types::wp_fractional_scaling::FractionalScalingManager;
+use crate::platform_impl::wayland::types::wp_idle_inhibit::IdleInhibitManager;
 use crate::platform_impl::wayland::types::wp_viewporter::ViewporterState;
 use crate::platform_impl::wayland::types::xdg_activation::XdgActivationState;
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
+use crate::platform_impl::wayland::types::xdg_foreign::XdgForeignState;
 use crate::platform_impl::wayland::window::{WindowRequests, WindowState};
 use crate::platform_impl::wayland::{WaylandError, WindowId};
 use crate::platform_impl::OsError;
@@ -91,12 +103,31 @@ pub struct WinitState {
     /// event loop run.
     pub events_sink: EventSink,
 
+    /// The clock of the compositor timestamping the input events.
+    pub server_clock: ServerClock,
+
+    /// The clock of the compositor timestamping the relative pointer events.
+    pub relative_pointer_clock: MicrosecondServerClock,
+
+    /// When the device events are delivered.
+    pub device_events: DeviceEvents,
+
+    /// The gamepads, when enabled.
+    #[cfg(target_os = "linux")]
+    pub gamepads: Option<std::rc::Rc<Gamepads>>,
+
     /// Xdg activation.
     pub xdg_activation: Option<XdgActivationState>,
 
+    /// Xdg foreign to parent windows across processes.
+    pub xdg_foreign: Option<XdgForeignState>,
+
     /// Relative pointer.
     pub relative_pointer: Option<RelativePointerState>,
 
+    /// The seats bound with the high-resolution scroll.
+    pub scroll_pointer_state: ScrollPointerState,
+
     /// Pointer constraints to handle pointer locking and confining.
     pub pointer_constraints: Option<Arc<PointerConstraintsState>>,
 
@@ -109,9 +140,27 @@ pub struct WinitState {
     /// KWin blur manager.
     pub kwin_blur_manager: Option<KWinBlurManager>,
 
+    /// Alpha modifier manager.
+    pub alpha_modifier: Option<AlphaModifierManager>,
+
+    /// Xdg dialog manager.
+    pub xdg_dialog: Option<XdgDialogManager>,
+
+    /// Idle inhibit manager.
+    pub idle_inhibit_manager: Option<IdleInhibitManager>,
+
+    /// The capabilities provided by the compositor.
+    pub capabilities: Arc<Mutex<BackendCapabilities>>,
+
     /// Loop handle to re-register event sources, such as keyboard repeat.
     pub loop_handle: LoopHandle<'static, Self>,
 
+    /// The timers switching the frames of the animated cursors of the windows.
+    pub cursor_animations: AHashMap<WindowId, RegistrationToken>,
+
+    /// Sender used by the windows to start the animation of their cursor.
+    pub cursor_animation_sender: Sender<WindowId>,
+
     /// Whether we have dispatched events to the user thus we want to
     /// send `AboutToWait` and normally wakeup the user.
     pub dispatched_events: bool,
@@ -156,9 +205,21 @@ impl WinitState {
             };
 
         let shm = Shm::bind(globals, queue_handle).map_err(WaylandError::Bind)?;
+
+        let (cursor_animation_sender, cursor_animation_channel) = channel::channel();
+        loop_handle
+            .insert_source(
+                cursor_animation_channel,
+                |event, _, state: &mut WinitState| {
+                    if let channel::Event::Msg(window_id) = event {
+                        state.animate_cursor(window_id);
+                    }
+                },
+            )
+            .map_err(|error| WaylandError::Calloop(error.error))?;
         let custom_cursor_pool = Arc::new(Mutex::new(SlotPool::new(2, &shm).unwrap()));
 
-        Ok(Self {
+        let mut state = Self {
             registry_state,
             compositor_state: Arc::new(compositor_state),
             subcompositor_state: subcompositor_state.map(Arc::new),
@@ -169,6 +230,7 @@ impl WinitState {
 
             xdg_shell: XdgShell::bind(globals, queue_handle).map_err(WaylandError::Bind)?,
             xdg_activation: XdgActivationState::bind(globals, queue_handle).ok(),
+            xdg_foreign: XdgForeignState::bind(globals, queue_handle).ok(),
 
             windows: Default::default(),
             window_requests: Default::default(),
@@ -177,22 +239,152 @@ impl WinitState {
             viewporter_state,
             fractional_scaling_manager,
             kwin_blur_manager: KWinBlurManager::new(globals, queue_handle).ok(),
+            alpha_modifier: AlphaModifierManager::new(globals, queue_handle).ok(),
+            xdg_dialog: XdgDialogManager::new(globals, queue_handle).ok(),
+            idle_inhibit_manager: IdleInhibitManager::new(globals, queue_handle).ok(),
 
             seats,
             text_input_state: TextInputState::new(globals, queue_handle).ok(),
 
             relative_pointer: RelativePointerState::new(globals, queue_handle).ok(),
+            scroll_pointer_state: ScrollPointerState::new(globals, queue_handle),
             pointer_constraints: PointerConstraintsState::new(globals, queue_handle)
                 .map(Arc::new)
                 .ok(),
             pointer_surfaces: Default::default(),
 
             monitors: Arc::new(Mutex::new(monitors)),
+            capabilities: Default::default(),
             events_sink: EventSink::new(),
+            server_clock: ServerClock::new(),
+            relative_pointer_clock: MicrosecondServerClock::new(),
+            device_events: Default::default(),
+            #[cfg(target_os = "linux")]
+            gamepads: None,
             loop_handle,
+            cursor_animations: Default::default(),
+            cursor_animation_sender,
             // Make it true by default.
             dispatched_events: true,
-        })
+        };
+
+        state.update_capabilities();
+
+        Ok(state)
+    }
+
+    /// Update the capabilities from the bound globals still advertised by the compositor.
+    pub fn update_capabilities(&mut self) {
+        let has_global = |interface: &str| {
+            self.registry_state
+                .globals_by_interface(interface)
+                .next()
+                .is_some()
+        };
+
+        // No protocol lets the regular clients set the window level, thus `WINDOW_LEVEL` is never
+        // reported.
+        let mut capabilities = BackendCapabilities::WINDOW_MENU
+            | BackendCapabilities::DRAG_RESIZE
+            | BackendCapabilities::RESIZE_INCREMENTS
+            | BackendCapabilities::ENABLED_BUTTONS;
+        capabilities.set(
+            BackendCapabilities::SERVER_SIDE_DECORATIONS,
+            has_global("zxdg_decoration_manager_v1"),
+        );
+        capabilities.set(
+            BackendCapabilities::ACTIVATION,
+            self.xdg_activation.is_some() && has_global("xdg_activation_v1"),
+        );
+        capabilities.set(
+            BackendCapabilities::FOREIGN_PARENT,
+            self.xdg_foreign.is_some()
+                && has_global("zxdg_exporter_v2")
+                && has_global("zxdg_importer_v2"),
+        );
+        capabilities.set(
+            BackendCapabilities::FRACTIONAL_SCALE,
+            self.fractional_scaling_manager.is_some()
+                && self.viewporter_state.is_some()
+                && has_global("wp_fractional_scale_manager_v1")
+                && has_global("wp_viewporter"),
+        );
+        capabilities.set(
+            BackendCapabilities::CURSOR_LOCK | BackendCapabilities::CURSOR_CONFINE,
+            self.pointer_constraints.is_some() && has_global("zwp_pointer_constraints_v1"),
+        );
+        capabilities.set(
+            BackendCapabilities::RAW_POINTER_MOTION,
+            self.relative_pointer.is_some() && has_global("zwp_relative_pointer_manager_v1"),
+        );
+        capabilities.set(
+            BackendCapabilities::IME,
+            self.text_input_state.is_some() && has_global("zwp_text_input_manager_v3"),
+        );
+        capabilities.set(
+            BackendCapabilities::BLUR,
+            self.kwin_blur_manager.is_some() && has_global("org_kde_kwin_blur_manager"),
+        );
+        capabilities.set(
+            BackendCapabilities::OPACITY,
+            self.alpha_modifier.is_some() && has_global("wp_alpha_modifier_v1"),
+        );
+
+        *self.capabilities.lock().unwrap() = capabilities;
+    }
+
+    /// Whether the device events should be delivered according to `DeviceEvents`.
+    pub fn device_events_allowed(&self) -> bool {
+        match self.device_events {
+            DeviceEvents::Always => true,
+            DeviceEvents::WhenFocused => self
+                .windows
+                .borrow()
+                .values()
+                .any(|window| window.lock().unwrap().has_focus()),
+            DeviceEvents::Never => false,
+        }
+    }
+
+    /// Start the timer switching the frames of the animated cursor of the window.
+    fn animate_cursor(&mut self, window_id: WindowId) {
+        if let Some(token) = self.cursor_animations.remove(&window_id) {
+            self.loop_handle.remove(token);
+        }
+
+        let duration = match self.windows.get_mut().get(&window_id) {
+            Some(window) => window.lock().unwrap().cursor_frame_duration(),
+            None => return,
+        };
+        let duration = match duration {
+            Some(duration) => duration,
+            None => return,
+        };
+
+        let timer = Timer::from_duration(duration);
+        let result = self
+            .loop_handle
+            .insert_source(timer, move |_, _, state: &mut WinitState| {
+                let duration = state
+                    .windows
+                    .get_mut()
+                    .get(&window_id)
+                    .and_then(|window| window.lock().unwrap().advance_cursor_frame());
+                match duration {
+                    Some(duration) => TimeoutAction::ToDuration(duration),
+                    None => {
+                        state.cursor_animations.remove(&window_id);
+                        TimeoutAction::Drop
+                    }
+                }
+            });
+
+        match result {
+            Ok(token) => {
+                self.cursor_animations.insert(window_id, token);
+            }
+            Err(error) => log::warn!("Failed to animate the cursor: {}", error.error),
+        }
     }
 
     pub fn scale_factor_changed(
@@ -252,6 +444,20 @@ impl WinitState {
 
         updates[pos].close_window = true;
     }
+
+    pub fn queue_resize(updates: &mut Vec<WindowCompositorUpdate>, window_id: WindowId) {
+        let pos = if let Some(pos) = updates
+            .iter()
+            .position(|update| update.window_id == window_id)
+        {
+            pos
+        } else {
+            updates.push(WindowCompositorUpdate::new(window_id));
+            updates.len() - 1
+        };
+
+        updates[pos].resized = true;
+    }
 }
 
 impl ShmHandler for WinitState {
@@ -289,14 +495,18 @@ impl WindowHandler for WinitState {
         };
 
         // Populate the configure to the window.
-        self.window_compositor_updates[pos].resized |= self
+        let mut window_state = self
             .windows
             .get_mut()
             .get_mut(&window_id)
             .expect("got configure for dead window.")
             .lock()
-            .unwrap()
-            .configure(configure, &self.shm, &self.subcompositor_state);
+            .unwrap();
+        let old_state = window_state.window_state();
+        let update = &mut self.window_compositor_updates[pos];
+        update.resized |= window_state.configure(configure, &self.shm, &self.subcompositor_state);
+        update.state_changed |= window_state.window_state() != old_state;
+        drop(window_state);
 
         // NOTE: configure demands wl_surface::commit, however winit doesn't commit on behalf of the
         // users, since it can break a lot of things, thus it'll ask users to redraw instead.
@@ -392,7 +602,45 @@ impl ProvidesRegistryState for WinitState {
         &mut self.registry_state
     }
 
-    sctk::registry_handlers![OutputState, SeatState];
+    sctk::registry_handlers![OutputState, SeatState, WinitState];
+}
+
+impl RegistryHandler<WinitState> for WinitState {
+    fn new_global(
+        state: &mut WinitState,
+        _: &Connection,
+        queue_handle: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+        version: u32,
+    ) {
+        if interface == WlSeat::interface().name {
+            state.scroll_pointer_state.bind(
+                state.registry_state.registry(),
+                name,
+                version,
+                queue_handle,
+            );
+        }
+
+        state.update_capabilities();
+    }
+
+    fn remove_global(
+        state: &mut WinitState,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+        name: u32,
+        interface: &str,
+    ) {
+        if interface == WlSeat::interface().name {
+            if let Some(seat) = state.scroll_pointer_state.remove(name) {
+                seat.release();
+            }
+        }
+
+        state.update_capabilities();
+    }
 }
 
 // The window update coming from the compositor.
@@ -407,6 +655,9 @@ pub struct WindowCompositorUpdate {
     /// New scale factor.
     pub scale_changed: bool,
 
+    /// New window state.
+    pub state_changed: bool,
+
     /// Close the window.
     pub close_window: bool,
 }
@@ -417,6 +668,7 @@ impl WindowCompositorUpdate {
             window_id,
             resized: false,
             scale_changed: false,
+            state_changed: false,
             close_window: false,
         }
     }
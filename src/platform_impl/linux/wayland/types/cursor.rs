@@ -1,9 +1,11 @@
+use std::time::Duration;
+
 use cursor_icon::CursorIcon;
 
 use sctk::reexports::client::protocol::wl_shm::Format;
 use sctk::shm::slot::{Buffer, SlotPool};
 
-use crate::cursor::CursorImage;
+use crate::cursor::{AnimatedCursorImage, CursorImage};
 
 #[derive(Debug)]
 pub enum SelectedCursor {
@@ -19,15 +21,59 @@ impl Default for SelectedCursor {
 
 #[derive(Debug)]
 pub struct CustomCursor {
+    pub source: AnimatedCursorImage,
+    /// The variant of the source the frames are created from.
+    pub variant: usize,
+    pub frames: Vec<CursorFrame>,
+    pub current_frame: usize,
+}
+
+impl CustomCursor {
+    pub(crate) fn new(pool: &mut SlotPool, source: AnimatedCursorImage, scale_factor: f64) -> Self {
+        let variant = source.0.variant_index(scale_factor);
+        let frames = source.0.variants[variant]
+            .frames
+            .iter()
+            .map(|frame| CursorFrame::new(pool, &frame.image, frame.duration))
+            .collect();
+
+        CustomCursor {
+            source,
+            variant,
+            frames,
+            current_frame: 0,
+        }
+    }
+
+    /// The frame currently shown.
+    pub fn frame(&self) -> &CursorFrame {
+        &self.frames[self.current_frame]
+    }
+
+    /// Whether the cursor has several frames to cycle through.
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
+    /// Move to the next frame, returning it.
+    pub fn advance(&mut self) -> &CursorFrame {
+        self.current_frame = (self.current_frame + 1) % self.frames.len();
+        self.frame()
+    }
+}
+
+#[derive(Debug)]
+pub struct CursorFrame {
     pub buffer: Buffer,
     pub w: i32,
     pub h: i32,
     pub hotspot_x: i32,
     pub hotspot_y: i32,
+    pub duration: Duration,
 }
 
-impl CustomCursor {
-    pub(crate) fn new(pool: &mut SlotPool, image: &CursorImage) -> Self {
+impl CursorFrame {
+    fn new(pool: &mut SlotPool, image: &CursorImage, duration: Duration) -> Self {
         let (buffer, canvas) = pool
             .create_buffer(
                 image.width as i32,
@@ -48,12 +94,13 @@ impl CustomCursor {
             *array = color.to_le_bytes();
         }
 
-        CustomCursor {
+        CursorFrame {
             buffer,
             w: image.width as i32,
             h: image.height as i32,
             hotspot_x: image.hotspot_x as i32,
             hotspot_y: image.hotspot_y as i32,
+            duration,
         }
     }
 }
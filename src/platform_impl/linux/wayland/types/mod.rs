@@ -2,6 +2,10 @@
 
 pub mod cursor;
 pub mod kwin_blur;
+pub mod wp_alpha_modifier;
 pub mod wp_fractional_scaling;
+pub mod wp_idle_inhibit;
 pub mod wp_viewporter;
 pub mod xdg_activation;
+pub mod xdg_dialog;
+pub mod xdg_foreign;
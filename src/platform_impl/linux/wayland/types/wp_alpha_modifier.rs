@@ -0,0 +1,92 @@
+//! Handling of the wp-alpha-modifier.
+
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::Dispatch;
+use sctk::reexports::client::{delegate_dispatch, Connection, Proxy, QueueHandle};
+
+use sctk::globals::GlobalData;
+
+use crate::platform_impl::wayland::state::WinitState;
+
+use self::protocol::wp_alpha_modifier_surface_v1::WpAlphaModifierSurfaceV1;
+use self::protocol::wp_alpha_modifier_v1::WpAlphaModifierV1;
+
+/// The bindings of the protocol, which isn't part of `wayland-protocols` yet.
+#[allow(missing_docs, clippy::all)]
+pub mod protocol {
+    use sctk::reexports::client as wayland_client;
+    use sctk::reexports::client::protocol::*;
+
+    pub mod __interfaces {
+        use sctk::reexports::client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!(
+            "src/platform_impl/linux/wayland/protocols/alpha-modifier-v1.xml"
+        );
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!(
+        "src/platform_impl/linux/wayland/protocols/alpha-modifier-v1.xml"
+    );
+}
+
+/// Alpha modifier manager.
+#[derive(Debug, Clone)]
+pub struct AlphaModifierManager {
+    manager: WpAlphaModifierV1,
+}
+
+impl AlphaModifierManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    /// Get the alpha modifier for the given surface.
+    pub fn get_surface(
+        &self,
+        surface: &WlSurface,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> WpAlphaModifierSurfaceV1 {
+        self.manager.get_surface(surface, queue_handle, ())
+    }
+}
+
+/// Convert the opacity to the multiplier of the protocol, where `u32::MAX` is fully opaque.
+pub fn opacity_to_multiplier(opacity: f32) -> u32 {
+    (opacity.clamp(0., 1.) as f64 * u32::MAX as f64).round() as u32
+}
+
+impl Dispatch<WpAlphaModifierV1, GlobalData, WinitState> for AlphaModifierManager {
+    fn event(
+        _: &mut WinitState,
+        _: &WpAlphaModifierV1,
+        _: <WpAlphaModifierV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for wp_alpha_modifier_v1");
+    }
+}
+
+impl Dispatch<WpAlphaModifierSurfaceV1, (), WinitState> for AlphaModifierManager {
+    fn event(
+        _: &mut WinitState,
+        _: &WpAlphaModifierSurfaceV1,
+        _: <WpAlphaModifierSurfaceV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for wp_alpha_modifier_surface_v1");
+    }
+}
+
+delegate_dispatch!(WinitState: [WpAlphaModifierV1: GlobalData] => AlphaModifierManager);
+delegate_dispatch!(WinitState: [WpAlphaModifierSurfaceV1: ()] => AlphaModifierManager);
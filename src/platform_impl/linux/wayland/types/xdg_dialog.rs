@@ -0,0 +1,87 @@
+//! Handling of the xdg-dialog, which is used to hint the modal dialogs.
+
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::Dispatch;
+use sctk::reexports::client::{delegate_dispatch, Connection, Proxy, QueueHandle};
+use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::XdgToplevel;
+
+use sctk::globals::GlobalData;
+
+use crate::platform_impl::wayland::state::WinitState;
+
+use self::protocol::xdg_dialog_v1::XdgDialogV1;
+use self::protocol::xdg_wm_dialog_v1::XdgWmDialogV1;
+
+/// The bindings of the protocol, which isn't part of `wayland-protocols` yet.
+#[allow(missing_docs, clippy::all)]
+pub mod protocol {
+    use sctk::reexports::client as wayland_client;
+    use sctk::reexports::protocols::xdg::shell::client::*;
+
+    pub mod __interfaces {
+        use sctk::reexports::protocols::xdg::shell::client::__interfaces::*;
+        wayland_scanner::generate_interfaces!(
+            "src/platform_impl/linux/wayland/protocols/xdg-dialog-v1.xml"
+        );
+    }
+
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!(
+        "src/platform_impl/linux/wayland/protocols/xdg-dialog-v1.xml"
+    );
+}
+
+/// Xdg dialog manager.
+#[derive(Debug, Clone)]
+pub struct XdgDialogManager {
+    manager: XdgWmDialogV1,
+}
+
+impl XdgDialogManager {
+    pub fn new(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let manager = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { manager })
+    }
+
+    /// Get the dialog object for the given toplevel.
+    pub fn get_dialog(
+        &self,
+        toplevel: &XdgToplevel,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> XdgDialogV1 {
+        self.manager.get_xdg_dialog(toplevel, queue_handle, ())
+    }
+}
+
+impl Dispatch<XdgWmDialogV1, GlobalData, WinitState> for XdgDialogManager {
+    fn event(
+        _: &mut WinitState,
+        _: &XdgWmDialogV1,
+        _: <XdgWmDialogV1 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for xdg_wm_dialog_v1");
+    }
+}
+
+impl Dispatch<XdgDialogV1, (), WinitState> for XdgDialogManager {
+    fn event(
+        _: &mut WinitState,
+        _: &XdgDialogV1,
+        _: <XdgDialogV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        unreachable!("no events defined for xdg_dialog_v1");
+    }
+}
+
+delegate_dispatch!(WinitState: [XdgWmDialogV1: GlobalData] => XdgDialogManager);
+delegate_dispatch!(WinitState: [XdgDialogV1: ()] => XdgDialogManager);
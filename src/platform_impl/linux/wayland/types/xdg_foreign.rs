@@ -0,0 +1,129 @@
+//! Handling of xdg foreign, which is used to parent surfaces across processes.
+
+use sctk::reexports::client::globals::{BindError, GlobalList};
+use sctk::reexports::client::protocol::wl_surface::WlSurface;
+use sctk::reexports::client::Dispatch;
+use sctk::reexports::client::{delegate_dispatch, Connection, Proxy, QueueHandle};
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_exported_v2::{
+    Event as ExportedEvent, ZxdgExportedV2,
+};
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_exporter_v2::ZxdgExporterV2;
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_imported_v2::ZxdgImportedV2;
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_importer_v2::ZxdgImporterV2;
+
+use sctk::globals::GlobalData;
+
+use crate::event::WindowEvent;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform_impl::wayland::state::WinitState;
+use crate::platform_impl::WindowId;
+use crate::window::ForeignWindowHandle;
+
+/// The xdg foreign exporter and importer.
+#[derive(Debug, Clone)]
+pub struct XdgForeignState {
+    exporter: ZxdgExporterV2,
+    importer: ZxdgImporterV2,
+}
+
+impl XdgForeignState {
+    pub fn bind(
+        globals: &GlobalList,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> Result<Self, BindError> {
+        let exporter = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        let importer = globals.bind(queue_handle, 1..=1, GlobalData)?;
+        Ok(Self { exporter, importer })
+    }
+
+    /// Export the toplevel surface, the handle being delivered to the window with the given id.
+    pub fn export_toplevel(
+        &self,
+        surface: &WlSurface,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> ZxdgExportedV2 {
+        self.exporter
+            .export_toplevel(surface, queue_handle, (window_id, serial))
+    }
+
+    /// Make the toplevel surface a child of the surface exported by another client.
+    pub fn set_parent_of(
+        &self,
+        handle: &ForeignWindowHandle,
+        surface: &WlSurface,
+        queue_handle: &QueueHandle<WinitState>,
+    ) -> ZxdgImportedV2 {
+        let imported =
+            self.importer
+                .import_toplevel(handle.as_raw().to_owned(), queue_handle, GlobalData);
+        imported.set_parent_of(surface);
+        imported
+    }
+}
+
+impl Dispatch<ZxdgExporterV2, GlobalData, WinitState> for XdgForeignState {
+    fn event(
+        _: &mut WinitState,
+        _: &ZxdgExporterV2,
+        _: <ZxdgExporterV2 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        // No events.
+    }
+}
+
+impl Dispatch<ZxdgImporterV2, GlobalData, WinitState> for XdgForeignState {
+    fn event(
+        _: &mut WinitState,
+        _: &ZxdgImporterV2,
+        _: <ZxdgImporterV2 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        // No events.
+    }
+}
+
+impl Dispatch<ZxdgExportedV2, (WindowId, AsyncRequestSerial), WinitState> for XdgForeignState {
+    fn event(
+        state: &mut WinitState,
+        _: &ZxdgExportedV2,
+        event: <ZxdgExportedV2 as Proxy>::Event,
+        (window_id, serial): &(WindowId, AsyncRequestSerial),
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        if let ExportedEvent::Handle { handle } = event {
+            state.events_sink.push_window_event(
+                WindowEvent::HandleExported {
+                    serial: *serial,
+                    handle: ForeignWindowHandle::from_raw(handle),
+                },
+                *window_id,
+            );
+        }
+    }
+}
+
+impl Dispatch<ZxdgImportedV2, GlobalData, WinitState> for XdgForeignState {
+    fn event(
+        _: &mut WinitState,
+        _: &ZxdgImportedV2,
+        _: <ZxdgImportedV2 as Proxy>::Event,
+        _: &GlobalData,
+        _: &Connection,
+        _: &QueueHandle<WinitState>,
+    ) {
+        // The parent is gone, which unsets the parent of our surface.
+    }
+}
+
+delegate_dispatch!(WinitState: [ZxdgExporterV2: GlobalData] => XdgForeignState);
+delegate_dispatch!(WinitState: [ZxdgImporterV2: GlobalData] => XdgForeignState);
+delegate_dispatch!(WinitState: [ZxdgExportedV2: (WindowId, AsyncRequestSerial)] => XdgForeignState);
+delegate_dispatch!(WinitState: [ZxdgImportedV2: GlobalData] => XdgForeignState);
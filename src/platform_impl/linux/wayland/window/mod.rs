@@ -8,7 +8,7 @@ use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::Proxy;
 use sctk::reexports::client::QueueHandle;
 
-use sctk::compositor::{CompositorState, Region, SurfaceData};
+use sctk::compositor::SurfaceData;
 use sctk::reexports::protocols::xdg::activation::v1::client::xdg_activation_v1::XdgActivationV1;
 use sctk::shell::xdg::window::Window as SctkWindow;
 use sctk::shell::xdg::window::WindowDecorations;
@@ -20,12 +20,14 @@ use crate::dpi::{LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError, OsError as RootOsError};
 use crate::event::{Ime, WindowEvent};
 use crate::event_loop::AsyncRequestSerial;
+use crate::platform::backend::BackendCapabilities;
+use crate::platform::seat::SeatId;
 use crate::platform_impl::{
     Fullscreen, MonitorHandle as PlatformMonitorHandle, OsError, PlatformIcon,
 };
 use crate::window::{
-    Cursor, CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowLevel,
+    Cursor, CursorGrabMode, CursorIcon, ImePurpose, OpaqueRegion, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowButtons, WindowLevel, WindowRegion,
 };
 
 use super::event_loop::sink::EventSink;
@@ -49,9 +51,6 @@ pub struct Window {
     /// The state of the window.
     window_state: Arc<Mutex<WindowState>>,
 
-    /// Compositor to handle WlRegion stuff.
-    compositor: Arc<CompositorState>,
-
     /// The wayland display used solely for raw window handle.
     #[allow(dead_code)]
     display: WlDisplay,
@@ -89,7 +88,6 @@ impl Window {
         let monitors = state.monitors.clone();
 
         let surface = state.compositor_state.create_surface(&queue_handle);
-        let compositor = state.compositor_state.clone();
         let xdg_activation = state
             .xdg_activation
             .as_ref()
@@ -127,6 +125,10 @@ impl Window {
 
         window_state.set_blur(attributes.blur);
 
+        if attributes.opacity < 1. {
+            window_state.set_opacity(attributes.opacity);
+        }
+
         // Set the decorations hint.
         window_state.set_decorate(attributes.decorations);
 
@@ -148,6 +150,20 @@ impl Window {
         // Non-resizable implies that the min and max sizes are set to the same value.
         window_state.set_resizable(attributes.resizable);
 
+        let resize_increments = attributes
+            .resize_increments
+            .map(|increments| increments.to_logical(1.));
+        window_state.set_resize_increments(resize_increments);
+        window_state.set_aspect_ratio(attributes.aspect_ratio);
+        window_state.set_enabled_buttons(attributes.enabled_buttons);
+
+        if attributes.window_level != WindowLevel::Normal {
+            warn!(
+                "`{:?}` is not supported on Wayland",
+                attributes.window_level
+            );
+        }
+
         // Set startup mode.
         match attributes.fullscreen.map(Into::into) {
             Some(Fullscreen::Exclusive(_)) => {
@@ -171,6 +187,19 @@ impl Window {
             Cursor::Custom(cursor) => window_state.set_custom_cursor(cursor),
         }
 
+        // Parent the window to its owner.
+        let owner = attributes
+            .owner
+            .and_then(|owner| state.windows.get_mut().get(&owner.0).cloned());
+        if let Some(owner) = owner {
+            window.set_parent(Some(&owner.lock().unwrap().window));
+            window_state.set_modal(attributes.modal);
+        }
+
+        if let Some(handle) = attributes.platform_specific.foreign_parent.as_ref() {
+            window_state.set_foreign_parent(handle);
+        }
+
         // Activate the window when the token is passed.
         if let (Some(xdg_activation), Some(token)) = (
             xdg_activation.as_ref(),
@@ -192,6 +221,7 @@ impl Window {
 
         let window_requests = WindowRequests {
             redraw_requested: AtomicBool::new(true),
+            resize_requested: AtomicBool::new(false),
             closed: AtomicBool::new(false),
         };
         let window_requests = Arc::new(window_requests);
@@ -231,7 +261,6 @@ impl Window {
             display,
             monitors,
             window_id,
-            compositor,
             window_state,
             queue_handle,
             xdg_activation,
@@ -351,12 +380,34 @@ impl Window {
 
     #[inline]
     pub fn resize_increments(&self) -> Option<PhysicalSize<u32>> {
-        None
+        let window_state = self.window_state.lock().unwrap();
+        let scale_factor = window_state.scale_factor();
+        window_state
+            .resize_increments()
+            .map(|increments| super::logical_to_physical_rounded(increments, scale_factor))
     }
 
     #[inline]
-    pub fn set_resize_increments(&self, _increments: Option<Size>) {
-        warn!("`set_resize_increments` is not implemented for Wayland");
+    pub fn set_resize_increments(&self, increments: Option<Size>) {
+        let mut window_state = self.window_state.lock().unwrap();
+        let scale_factor = window_state.scale_factor();
+        let increments = increments.map(|increments| increments.to_logical(scale_factor));
+        window_state.set_resize_increments(increments);
+    }
+
+    #[inline]
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        let resized = self
+            .window_state
+            .lock()
+            .unwrap()
+            .set_aspect_ratio(aspect_ratio);
+        if resized {
+            self.window_requests
+                .resize_requested
+                .store(true, Ordering::Relaxed);
+        }
+        self.request_redraw();
     }
 
     #[inline]
@@ -385,6 +436,11 @@ impl Window {
         self.window_state.lock().unwrap().show_window_menu(position);
     }
 
+    #[inline]
+    pub fn capabilities(&self) -> BackendCapabilities {
+        self.window_state.lock().unwrap().capabilities()
+    }
+
     #[inline]
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
         self.window_state
@@ -407,14 +463,21 @@ impl Window {
     }
 
     #[inline]
-    pub fn set_enabled_buttons(&self, _buttons: WindowButtons) {
-        // TODO(kchibisov) v5 of the xdg_shell allows that.
+    pub fn set_enabled_buttons(&self, buttons: WindowButtons) {
+        if self
+            .window_state
+            .lock()
+            .unwrap()
+            .set_enabled_buttons(buttons)
+        {
+            // NOTE: Requires commit to be applied.
+            self.request_redraw();
+        }
     }
 
     #[inline]
     pub fn enabled_buttons(&self) -> WindowButtons {
-        // TODO(kchibisov) v5 of the xdg_shell allows that.
-        WindowButtons::all()
+        self.window_state.lock().unwrap().enabled_buttons()
     }
 
     #[inline]
@@ -427,6 +490,14 @@ impl Window {
         self.window_state.lock().unwrap().set_blur(blur);
     }
 
+    #[inline]
+    pub fn set_opacity(&self, opacity: f32) {
+        if self.window_state.lock().unwrap().set_opacity(opacity) {
+            // NOTE: Requires commit to be applied.
+            self.request_redraw();
+        }
+    }
+
     #[inline]
     pub fn set_decorations(&self, decorate: bool) {
         self.window_state.lock().unwrap().set_decorate(decorate)
@@ -437,8 +508,14 @@ impl Window {
         self.window_state.lock().unwrap().is_decorated()
     }
 
+    /// No protocol available to regular clients lets them keep their windows above or below the
+    /// others.
     #[inline]
-    pub fn set_window_level(&self, _level: WindowLevel) {}
+    pub fn set_window_level(&self, level: WindowLevel) {
+        if level != WindowLevel::Normal {
+            warn!("`{level:?}` is not supported on Wayland");
+        }
+    }
 
     #[inline]
     pub(crate) fn set_window_icon(&self, _window_icon: Option<PlatformIcon>) {}
@@ -518,7 +595,11 @@ impl Window {
 
         match cursor {
             Cursor::Icon(icon) => window_state.set_cursor(icon),
-            Cursor::Custom(cursor) => window_state.set_custom_cursor(cursor),
+            Cursor::Custom(cursor) => {
+                window_state.set_custom_cursor(cursor);
+                // Wake up the event loop to schedule the frames of animated cursors.
+                self.event_loop_awakener.ping();
+            }
         }
     }
 
@@ -528,6 +609,18 @@ impl Window {
             .lock()
             .unwrap()
             .set_cursor_visible(visible);
+
+        if visible {
+            self.event_loop_awakener.ping();
+        }
+    }
+
+    #[inline]
+    pub fn set_seat_cursor(&self, seat: SeatId, cursor: CursorIcon) {
+        self.window_state
+            .lock()
+            .unwrap()
+            .set_seat_cursor(seat, cursor);
     }
 
     pub fn request_user_attention(&self, request_type: Option<UserAttentionType>) {
@@ -572,11 +665,32 @@ impl Window {
         Ok(serial)
     }
 
+    pub fn export_handle(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
+        let serial = AsyncRequestSerial::get();
+        self.window_state
+            .lock()
+            .unwrap()
+            .export_handle(self.window_id, serial)?;
+        Ok(serial)
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
         self.window_state.lock().unwrap().set_cursor_grab(mode)
     }
 
+    #[inline]
+    pub fn set_seat_cursor_grab(
+        &self,
+        seat: SeatId,
+        mode: CursorGrabMode,
+    ) -> Result<(), ExternalError> {
+        self.window_state
+            .lock()
+            .unwrap()
+            .set_seat_cursor_grab(seat, mode)
+    }
+
     #[inline]
     pub fn set_cursor_position(&self, position: Position) -> Result<(), ExternalError> {
         let scale_factor = self.scale_factor();
@@ -596,19 +710,38 @@ impl Window {
 
     #[inline]
     pub fn set_cursor_hittest(&self, hittest: bool) -> Result<(), ExternalError> {
-        let surface = self.window.wl_surface();
-
-        if hittest {
-            surface.set_input_region(None);
-            Ok(())
+        let region = if hittest {
+            None
         } else {
-            let region = Region::new(&*self.compositor).map_err(|_| {
-                ExternalError::Os(os_error!(OsError::Misc("failed to set input region.")))
-            })?;
-            region.add(0, 0, 0, 0);
-            surface.set_input_region(Some(region.wl_region()));
-            Ok(())
-        }
+            Some(WindowRegion::new())
+        };
+        self.set_input_region(region)
+    }
+
+    #[inline]
+    pub fn set_input_region(&self, region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.window_state.lock().unwrap().set_input_region(region)?;
+        self.request_redraw();
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    #[inline]
+    pub fn set_opaque_region(&self, region: Option<OpaqueRegion>) {
+        self.window_state.lock().unwrap().set_opaque_region(region);
+        self.request_redraw();
+    }
+
+    #[inline]
+    pub fn set_idle_inhibited(&self, inhibited: bool) {
+        self.window_state
+            .lock()
+            .unwrap()
+            .set_idle_inhibited(inhibited);
     }
 
     #[inline]
@@ -636,6 +769,45 @@ impl Window {
         }
     }
 
+    pub fn set_seat_ime_cursor_area(&self, seat: SeatId, position: Position, size: Size) {
+        let mut window_state = self.window_state.lock().unwrap();
+        let scale_factor = window_state.scale_factor();
+        let position = position.to_logical(scale_factor);
+        let size = size.to_logical(scale_factor);
+        window_state.set_seat_ime_cursor_area(seat, position, size);
+    }
+
+    #[inline]
+    pub fn set_seat_ime_allowed(&self, seat: SeatId, allowed: bool) {
+        let mut window_state = self.window_state.lock().unwrap();
+
+        if window_state.seat_ime_allowed(seat) != allowed
+            && window_state.set_seat_ime_allowed(seat, allowed)
+        {
+            let event = WindowEvent::Ime(if allowed { Ime::Enabled } else { Ime::Disabled });
+            self.window_events_sink
+                .lock()
+                .unwrap()
+                .push_window_event(event, self.window_id);
+            self.event_loop_awakener.ping();
+        }
+    }
+
+    #[inline]
+    pub fn reset_seat(&self, seat: SeatId) {
+        let mut window_state = self.window_state.lock().unwrap();
+
+        if window_state.reset_seat(seat) {
+            let allowed = window_state.seat_ime_allowed(seat);
+            let event = WindowEvent::Ime(if allowed { Ime::Enabled } else { Ime::Disabled });
+            self.window_events_sink
+                .lock()
+                .unwrap()
+                .push_window_event(event, self.window_id);
+            self.event_loop_awakener.ping();
+        }
+    }
+
     #[inline]
     pub fn set_ime_purpose(&self, purpose: ImePurpose) {
         self.window_state.lock().unwrap().set_ime_purpose(purpose);
@@ -746,6 +918,9 @@ pub struct WindowRequests {
 
     /// Redraw Requested.
     pub redraw_requested: AtomicBool,
+
+    /// The window was resized by the client.
+    pub resize_requested: AtomicBool,
 }
 
 impl WindowRequests {
@@ -756,6 +931,10 @@ impl WindowRequests {
     pub fn take_redraw_requested(&self) -> bool {
         self.redraw_requested.swap(false, Ordering::Relaxed)
     }
+
+    pub fn take_resize_requested(&self) -> bool {
+        self.resize_requested.swap(false, Ordering::Relaxed)
+    }
 }
 
 impl TryFrom<&str> for Theme {
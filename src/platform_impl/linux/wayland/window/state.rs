@@ -4,20 +4,25 @@ use std::num::NonZeroU32;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Duration;
 
-use ahash::HashSet;
+use ahash::{HashMap, HashSet};
 use log::{info, warn};
 
+use sctk::reexports::calloop::channel::Sender;
 use sctk::reexports::client::backend::ObjectId;
 use sctk::reexports::client::protocol::wl_seat::WlSeat;
 use sctk::reexports::client::protocol::wl_shm::WlShm;
 use sctk::reexports::client::protocol::wl_surface::WlSurface;
 use sctk::reexports::client::{Connection, Proxy, QueueHandle};
 use sctk::reexports::csd_frame::{
-    DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowState as XdgWindowState,
+    DecorationsFrame, FrameAction, FrameClick, ResizeEdge, WindowManagerCapabilities,
+    WindowState as XdgWindowState,
 };
 use sctk::reexports::protocols::wp::fractional_scale::v1::client::wp_fractional_scale_v1::WpFractionalScaleV1;
+use sctk::reexports::protocols::wp::idle_inhibit::zv1::client::zwp_idle_inhibitor_v1::ZwpIdleInhibitorV1;
 use sctk::reexports::protocols::wp::text_input::zv3::client::zwp_text_input_v3::ZwpTextInputV3;
 use sctk::reexports::protocols::wp::viewporter::client::wp_viewport::WpViewport;
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_exported_v2::ZxdgExportedV2;
+use sctk::reexports::protocols::xdg::foreign::zv2::client::zxdg_imported_v2::ZxdgImportedV2;
 use sctk::reexports::protocols::xdg::shell::client::xdg_toplevel::ResizeEdge as XdgResizeEdge;
 
 use sctk::compositor::{CompositorState, Region, SurfaceData, SurfaceDataExt};
@@ -33,11 +38,25 @@ use wayland_protocols_plasma::blur::client::org_kde_kwin_blur::OrgKdeKwinBlur;
 use crate::cursor::CustomCursor as RootCustomCursor;
 use crate::dpi::{LogicalPosition, LogicalSize, PhysicalSize, Size};
 use crate::error::{ExternalError, NotSupportedError};
-use crate::platform_impl::wayland::logical_to_physical_rounded;
+use crate::event_loop::AsyncRequestSerial;
+use crate::platform::backend::BackendCapabilities;
+use crate::platform::seat::SeatId;
 use crate::platform_impl::wayland::types::cursor::{CustomCursor, SelectedCursor};
 use crate::platform_impl::wayland::types::kwin_blur::KWinBlurManager;
+use crate::platform_impl::wayland::types::wp_alpha_modifier::protocol::wp_alpha_modifier_surface_v1::WpAlphaModifierSurfaceV1;
+use crate::platform_impl::wayland::types::wp_alpha_modifier::{
+    opacity_to_multiplier, AlphaModifierManager,
+};
+use crate::platform_impl::wayland::types::wp_idle_inhibit::IdleInhibitManager;
+use crate::platform_impl::wayland::types::xdg_dialog::protocol::xdg_dialog_v1::XdgDialogV1;
+use crate::platform_impl::wayland::types::xdg_dialog::XdgDialogManager;
+use crate::platform_impl::wayland::types::xdg_foreign::XdgForeignState;
+use crate::platform_impl::wayland::{logical_to_physical_rounded, make_wid, seat_id};
 use crate::platform_impl::{PlatformCustomCursor, WindowId};
-use crate::window::{CursorGrabMode, CursorIcon, ImePurpose, ResizeDirection, Theme};
+use crate::window::{
+    CursorGrabMode, CursorIcon, ForeignWindowHandle, ImePurpose, OpaqueRegion, ResizeDirection,
+    Theme, WindowButtons, WindowRegion, WindowState as RootWindowState,
+};
 
 use crate::platform_impl::wayland::seat::{
     PointerConstraintsState, WinitPointerData, WinitPointerDataExt, ZwpTextInputV3Ext,
@@ -74,12 +93,21 @@ pub struct WindowState {
 
     selected_cursor: SelectedCursor,
 
+    /// Whether the frames of the animated custom cursor are switched.
+    cursor_animated: bool,
+
+    /// Sender to start the timer switching the frames of the animated custom cursor.
+    cursor_animation_sender: Sender<WindowId>,
+
     /// Whether the cursor is visible.
     pub cursor_visible: bool,
 
     /// Pointer constraints to lock/confine pointer.
     pub pointer_constraints: Option<Arc<PointerConstraintsState>>,
 
+    /// The capabilities provided by the compositor.
+    capabilities: Arc<Mutex<BackendCapabilities>>,
+
     /// Queue handle.
     pub queue_handle: QueueHandle<WinitState>,
 
@@ -118,6 +146,9 @@ pub struct WindowState {
     /// The text inputs observed on the window.
     text_inputs: Vec<ZwpTextInputV3>,
 
+    /// The state set for single seats, overriding the one of the window.
+    seat_overrides: HashMap<SeatId, SeatOverride>,
+
     /// The inner size of the window, as in without client side decorations.
     size: LogicalSize<u32>,
 
@@ -131,6 +162,21 @@ pub struct WindowState {
     min_inner_size: LogicalSize<u32>,
     max_inner_size: Option<LogicalSize<u32>>,
 
+    /// The increments the size of the window is snapped to.
+    resize_increments: Option<LogicalSize<u32>>,
+
+    /// The aspect ratio the size of the window is shrunk to.
+    aspect_ratio: Option<(u32, u32)>,
+
+    /// The buttons enabled on the frame.
+    enabled_buttons: WindowButtons,
+
+    /// The region of the surface accepting input, the whole surface when `None`.
+    input_region: Option<WindowRegion>,
+
+    /// The opaque region of a transparent window.
+    opaque_region: Option<OpaqueRegion>,
+
     /// The size of the window when no states were applied to it. The primary use for it
     /// is to fallback to original window size, before it was maximized, if the compositor
     /// sends `None` for the new size in the configure.
@@ -147,6 +193,19 @@ pub struct WindowState {
     fractional_scale: Option<WpFractionalScaleV1>,
     blur: Option<OrgKdeKwinBlur>,
     blur_manager: Option<KWinBlurManager>,
+    alpha_modifier: Option<WpAlphaModifierSurfaceV1>,
+    alpha_modifier_manager: Option<AlphaModifierManager>,
+    xdg_foreign: Option<XdgForeignState>,
+    dialog: Option<XdgDialogV1>,
+    dialog_manager: Option<XdgDialogManager>,
+    idle_inhibitor: Option<ZwpIdleInhibitorV1>,
+    idle_inhibit_manager: Option<IdleInhibitManager>,
+
+    /// The last export of the window, which must be kept alive for its handle to stay valid.
+    exported: Option<ZxdgExportedV2>,
+
+    /// The window of another process this window is parented to.
+    foreign_parent: Option<ZxdgImportedV2>,
 
     /// Whether the client side decorations have pending move operations.
     ///
@@ -181,13 +240,26 @@ impl WindowState {
         Self {
             blur: None,
             blur_manager: winit_state.kwin_blur_manager.clone(),
+            alpha_modifier: None,
+            alpha_modifier_manager: winit_state.alpha_modifier.clone(),
+            xdg_foreign: winit_state.xdg_foreign.clone(),
+            dialog: None,
+            dialog_manager: winit_state.xdg_dialog.clone(),
+            idle_inhibitor: None,
+            idle_inhibit_manager: winit_state.idle_inhibit_manager.clone(),
+            exported: None,
+            foreign_parent: None,
+            capabilities: winit_state.capabilities.clone(),
             compositor,
             connection,
             csd_fails: false,
             cursor_grab_mode: GrabState::new(),
             selected_cursor: Default::default(),
+            cursor_animated: false,
+            cursor_animation_sender: winit_state.cursor_animation_sender.clone(),
             cursor_visible: true,
             decorate: true,
+            enabled_buttons: WindowButtons::all(),
             fractional_scale,
             frame: None,
             frame_callback_state: FrameCallbackState::None,
@@ -195,14 +267,19 @@ impl WindowState {
             has_pending_move: None,
             ime_allowed: false,
             ime_purpose: ImePurpose::Normal,
+            input_region: None,
             last_configure: None,
             max_inner_size: None,
+            opaque_region: None,
             min_inner_size: MIN_WINDOW_SIZE,
             pointer_constraints,
             pointers: Default::default(),
             queue_handle: queue_handle.clone(),
             resizable: true,
+            resize_increments: None,
+            aspect_ratio: None,
             scale_factor: 1.,
+            seat_overrides: Default::default(),
             shm: winit_state.shm.wl_shm().clone(),
             custom_cursor_pool: winit_state.custom_cursor_pool.clone(),
             size: initial_size.to_logical(1.),
@@ -306,9 +383,11 @@ impl WindowState {
 
         let stateless = Self::is_stateless(&configure);
 
+        let wm_capabilities = self.frame_wm_capabilities(configure.capabilities);
         let (mut new_size, constrain) = if let Some(frame) = self.frame.as_mut() {
             // Configure the window states.
             frame.update_state(configure.state);
+            frame.update_wm_capabilities(wm_capabilities);
 
             match configure.new_size {
                 (Some(width), Some(height)) => {
@@ -339,6 +418,11 @@ impl WindowState {
                 .1
                 .map(|bound_h| new_size.height.min(bound_h.get()))
                 .unwrap_or(new_size.height);
+        } else if stateless {
+            // Snap the size picked by the compositor, unless the window must fill an area.
+            new_size =
+                snap_to_resize_increments(new_size, self.resize_increments, self.min_inner_size);
+            new_size = fit_to_aspect_ratio(new_size, self.aspect_ratio, self.min_inner_size);
         }
 
         let new_state = configure.state;
@@ -397,6 +481,81 @@ impl WindowState {
         !(configure.is_maximized() || configure.is_fullscreen() || configure.is_tiled())
     }
 
+    /// Set the aspect ratio of the window, fitting the current size to it.
+    ///
+    /// Returns `true` when the window was resized.
+    pub fn set_aspect_ratio(&mut self, aspect_ratio: Option<(u32, u32)>) -> bool {
+        self.aspect_ratio = aspect_ratio.filter(|&(width, height)| width != 0 && height != 0);
+
+        // NOTE: The initial size is picked on the first configure.
+        if self.last_configure.is_none() {
+            return false;
+        }
+
+        let old_size = self.size;
+        let size = fit_to_aspect_ratio(self.size, self.aspect_ratio, self.min_inner_size);
+        if size != self.size {
+            self.request_inner_size(size.into());
+        }
+
+        self.size != old_size
+    }
+
+    /// The resize increments of the window.
+    #[inline]
+    pub fn resize_increments(&self) -> Option<LogicalSize<u32>> {
+        self.resize_increments
+    }
+
+    /// Set the resize increments of the window.
+    pub fn set_resize_increments(&mut self, increments: Option<LogicalSize<u32>>) {
+        self.resize_increments = increments;
+    }
+
+    /// The capabilities shown on the frame, without the buttons which were disabled.
+    fn frame_wm_capabilities(
+        &self,
+        wm_capabilities: WindowManagerCapabilities,
+    ) -> WindowManagerCapabilities {
+        let mut wm_capabilities = wm_capabilities;
+        if !self.enabled_buttons.contains(WindowButtons::MAXIMIZE) {
+            wm_capabilities.remove(WindowManagerCapabilities::MAXIMIZE);
+        }
+        if !self.enabled_buttons.contains(WindowButtons::MINIMIZE) {
+            wm_capabilities.remove(WindowManagerCapabilities::MINIMIZE);
+        }
+        wm_capabilities
+    }
+
+    /// The buttons enabled on the frame.
+    #[inline]
+    pub fn enabled_buttons(&self) -> WindowButtons {
+        self.enabled_buttons
+    }
+
+    /// Set the buttons enabled on the frame, returning whether the frame should be redrawn.
+    pub fn set_enabled_buttons(&mut self, buttons: WindowButtons) -> bool {
+        if self.enabled_buttons == buttons {
+            return false;
+        }
+
+        self.enabled_buttons = buttons;
+
+        let wm_capabilities = self
+            .last_configure
+            .as_ref()
+            .map(|configure| configure.capabilities)
+            .unwrap_or(WindowManagerCapabilities::all());
+        let wm_capabilities = self.frame_wm_capabilities(wm_capabilities);
+        match self.frame.as_mut() {
+            Some(frame) => {
+                frame.update_wm_capabilities(wm_capabilities);
+                frame.is_dirty()
+            }
+            None => false,
+        }
+    }
+
     /// Start interacting drag resize.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
         let xdg_toplevel = self.window.xdg_toplevel();
@@ -440,7 +599,10 @@ impl WindowState {
             FrameAction::Minimize => self.window.set_minimized(),
             FrameAction::Maximize => self.window.set_maximized(),
             FrameAction::UnMaximize => self.window.unset_maximized(),
-            FrameAction::Close => WinitState::queue_close(updates, window_id),
+            // The close button can't be hidden, thus ignore it when disabled.
+            FrameAction::Close if self.enabled_buttons.contains(WindowButtons::CLOSE) => {
+                WinitState::queue_close(updates, window_id)
+            }
             FrameAction::Move => self.has_pending_move = Some(serial),
             FrameAction::Resize(edge) => {
                 let edge = match edge {
@@ -553,6 +715,35 @@ impl WindowState {
         self.last_configure.is_some()
     }
 
+    /// The state of the window from the last configure.
+    pub fn window_state(&self) -> RootWindowState {
+        let configure_state = match self.last_configure.as_ref() {
+            Some(configure) => configure.state,
+            None => return RootWindowState::empty(),
+        };
+
+        let mut state = RootWindowState::empty();
+        for (xdg_state, root_state) in [
+            (XdgWindowState::MAXIMIZED, RootWindowState::MAXIMIZED),
+            (XdgWindowState::FULLSCREEN, RootWindowState::FULLSCREEN),
+            (XdgWindowState::TILED_LEFT, RootWindowState::TILED_LEFT),
+            (XdgWindowState::TILED_RIGHT, RootWindowState::TILED_RIGHT),
+            (XdgWindowState::TILED_TOP, RootWindowState::TILED_TOP),
+            (XdgWindowState::TILED_BOTTOM, RootWindowState::TILED_BOTTOM),
+            (XdgWindowState::SUSPENDED, RootWindowState::SUSPENDED),
+        ] {
+            state.set(root_state, configure_state.contains(xdg_state));
+        }
+
+        state
+    }
+
+    /// The capabilities provided by the compositor.
+    #[inline]
+    pub fn capabilities(&self) -> BackendCapabilities {
+        *self.capabilities.lock().unwrap()
+    }
+
     #[inline]
     pub fn is_decorated(&mut self) -> bool {
         let csd = self
@@ -584,6 +775,12 @@ impl WindowState {
 
         let mode = self.cursor_grab_mode.user_grab_mode;
         let _ = self.set_cursor_grab_inner(mode);
+
+        for (seat, seat_override) in &self.seat_overrides {
+            if let Some(mode) = seat_override.grab_mode {
+                self.apply_cursor_grab(Some(*seat), mode, mode);
+            }
+        }
     }
 
     /// Pointer has left the top-level.
@@ -616,7 +813,10 @@ impl WindowState {
         if self.cursor_visible {
             match &self.selected_cursor {
                 SelectedCursor::Named(icon) => self.set_cursor(*icon),
-                SelectedCursor::Custom(cursor) => self.apply_custom_cursor(cursor),
+                SelectedCursor::Custom(cursor) => {
+                    self.apply_custom_cursor(cursor);
+                    self.animate_cursor();
+                }
             }
         } else {
             self.set_cursor_visible(self.cursor_visible);
@@ -627,16 +827,70 @@ impl WindowState {
     pub fn reload_transparency_hint(&self) {
         let surface = self.window.wl_surface();
 
-        if self.transparent {
-            surface.set_opaque_region(None);
-        } else if let Ok(region) = Region::new(&*self.compositor) {
-            region.add(0, 0, i32::MAX, i32::MAX);
+        let opaque_region = match (&self.opaque_region, self.transparent) {
+            (_, false) => None,
+            (Some(opaque_region), true) => Some(opaque_region.to_region(self.size.cast())),
+            (None, true) => {
+                surface.set_opaque_region(None);
+                return;
+            }
+        };
+
+        if let Ok(region) = Region::new(&*self.compositor) {
+            match opaque_region {
+                Some(opaque_region) => {
+                    for (position, size) in opaque_region.to_logical(self.scale_factor) {
+                        region.add(position.x, position.y, size.width, size.height);
+                    }
+                }
+                None => region.add(0, 0, i32::MAX, i32::MAX),
+            }
             surface.set_opaque_region(Some(region.wl_region()));
         } else {
             warn!("Failed to mark window opaque.");
         }
     }
 
+    /// Set the opaque region of the window when it's transparent.
+    ///
+    /// NOTE: Requires commit to be applied.
+    pub fn set_opaque_region(&mut self, region: Option<OpaqueRegion>) {
+        self.opaque_region = region;
+        self.reload_transparency_hint();
+    }
+
+    /// Set the region of the surface accepting input.
+    ///
+    /// NOTE: Requires commit to be applied.
+    pub fn set_input_region(&mut self, region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.input_region = region;
+        self.reload_input_region()
+    }
+
+    /// Reissue the input region to the compositor.
+    fn reload_input_region(&self) -> Result<(), ExternalError> {
+        let surface = self.window.wl_surface();
+
+        let input_region = match &self.input_region {
+            Some(input_region) => input_region,
+            None => {
+                surface.set_input_region(None);
+                return Ok(());
+            }
+        };
+
+        let region = Region::new(&*self.compositor).map_err(|_| {
+            ExternalError::Os(os_error!(crate::platform_impl::OsError::Misc(
+                "failed to set input region."
+            )))
+        })?;
+        for (position, size) in input_region.to_logical(self.scale_factor) {
+            region.add(position.x, position.y, size.width, size.height);
+        }
+        surface.set_input_region(Some(region.wl_region()));
+        Ok(())
+    }
+
     /// Try to resize the window when the user can do so.
     pub fn request_inner_size(&mut self, inner_size: Size) -> PhysicalSize<u32> {
         if self
@@ -704,25 +958,44 @@ impl WindowState {
 
     /// Set the cursor icon.
     pub fn set_cursor(&mut self, cursor_icon: CursorIcon) {
+        self.stop_cursor_animation();
         self.selected_cursor = SelectedCursor::Named(cursor_icon);
 
         if !self.cursor_visible {
             return;
         }
 
-        self.apply_on_pointer(|pointer, _| {
+        self.apply_on_pointer(|pointer, data| {
+            let cursor_icon = self.seat_override(data).cursor.unwrap_or(cursor_icon);
             if pointer.set_cursor(&self.connection, cursor_icon).is_err() {
                 warn!("Failed to set cursor to {:?}", cursor_icon);
             }
         })
     }
 
+    /// Set the cursor icon for the given seat.
+    pub fn set_seat_cursor(&mut self, seat: SeatId, cursor_icon: CursorIcon) {
+        self.seat_overrides.entry(seat).or_default().cursor = Some(cursor_icon);
+
+        if !self.cursor_visible {
+            return;
+        }
+
+        self.apply_on_pointer(|pointer, data| {
+            if seat_id(data.seat()) == seat
+                && pointer.set_cursor(&self.connection, cursor_icon).is_err()
+            {
+                warn!("Failed to set cursor to {:?}", cursor_icon);
+            }
+        })
+    }
+
     /// Set the custom cursor icon.
     pub(crate) fn set_custom_cursor(&mut self, cursor: RootCustomCursor) {
         let cursor = match cursor {
             RootCustomCursor {
                 inner: PlatformCustomCursor::Wayland(cursor),
-            } => cursor.0,
+            } => cursor,
             #[cfg(x11_platform)]
             RootCustomCursor {
                 inner: PlatformCustomCursor::X(_),
@@ -734,18 +1007,90 @@ impl WindowState {
 
         let cursor = {
             let mut pool = self.custom_cursor_pool.lock().unwrap();
-            CustomCursor::new(&mut pool, &cursor)
+            CustomCursor::new(&mut pool, cursor, self.scale_factor)
         };
 
+        self.stop_cursor_animation();
+
         if self.cursor_visible {
             self.apply_custom_cursor(&cursor);
         }
 
         self.selected_cursor = SelectedCursor::Custom(cursor);
+
+        if self.cursor_visible {
+            self.animate_cursor();
+        }
+    }
+
+    /// Start switching the frames of the animated custom cursor.
+    fn animate_cursor(&mut self) {
+        if self.cursor_animated {
+            return;
+        }
+
+        if !matches!(&self.selected_cursor, SelectedCursor::Custom(cursor) if cursor.is_animated())
+        {
+            return;
+        }
+
+        // The timer is registered by the event loop.
+        self.cursor_animated = true;
+        let window_id = make_wid(self.window.wl_surface());
+        let _ = self.cursor_animation_sender.send(window_id);
+    }
+
+    /// Stop switching the frames of the animated custom cursor.
+    ///
+    /// The timer is dropped the next time it fires.
+    fn stop_cursor_animation(&mut self) {
+        self.cursor_animated = false;
+    }
+
+    /// How long the current frame of the animated custom cursor should be shown.
+    pub fn cursor_frame_duration(&self) -> Option<Duration> {
+        match &self.selected_cursor {
+            SelectedCursor::Custom(cursor) if self.cursor_animated => {
+                Some(frame_duration(cursor.frame().duration))
+            }
+            _ => None,
+        }
+    }
+
+    /// Show the next frame of the animated custom cursor.
+    ///
+    /// Returns how long the frame should be shown, `None` once the animation has stopped.
+    pub fn advance_cursor_frame(&mut self) -> Option<Duration> {
+        if !self.cursor_animated {
+            return None;
+        }
+
+        let mut selected_cursor = std::mem::take(&mut self.selected_cursor);
+        let duration = match &mut selected_cursor {
+            SelectedCursor::Custom(cursor) => {
+                let duration = frame_duration(cursor.advance().duration);
+                self.apply_custom_cursor(cursor);
+                Some(duration)
+            }
+            SelectedCursor::Named(_) => None,
+        };
+        self.selected_cursor = selected_cursor;
+
+        duration
     }
 
     fn apply_custom_cursor(&self, cursor: &CustomCursor) {
-        self.apply_on_pointer(|pointer, _| {
+        let cursor = cursor.frame();
+
+        self.apply_on_pointer(|pointer, data| {
+            // The seats with their own cursor keep it.
+            if let Some(cursor_icon) = self.seat_override(data).cursor {
+                if pointer.set_cursor(&self.connection, cursor_icon).is_err() {
+                    warn!("Failed to set cursor to {:?}", cursor_icon);
+                }
+                return;
+            }
+
             let surface = pointer.surface();
 
             let scale = surface
@@ -837,43 +1182,82 @@ impl WindowState {
         self.set_max_inner_size(self.max_inner_size);
     }
 
+    /// Set the cursor grabbing state for the given seat.
+    pub fn set_seat_cursor_grab(
+        &mut self,
+        seat: SeatId,
+        mode: CursorGrabMode,
+    ) -> Result<(), ExternalError> {
+        if self.pointer_constraints.is_none() && mode != CursorGrabMode::None {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+
+        let seat_override = self.seat_overrides.entry(seat).or_default();
+        let old_mode = seat_override
+            .grab_mode
+            .replace(mode)
+            .unwrap_or(self.cursor_grab_mode.current_grab_mode);
+        self.apply_cursor_grab(Some(seat), old_mode, mode);
+
+        Ok(())
+    }
+
     /// Set the grabbing state on the surface.
     fn set_cursor_grab_inner(&mut self, mode: CursorGrabMode) -> Result<(), ExternalError> {
-        let pointer_constraints = match self.pointer_constraints.as_ref() {
-            Some(pointer_constraints) => pointer_constraints,
+        match self.pointer_constraints.as_ref() {
+            Some(_) => (),
             None if mode == CursorGrabMode::None => return Ok(()),
             None => return Err(ExternalError::NotSupported(NotSupportedError::new())),
         };
 
         // Replace the current mode.
         let old_mode = std::mem::replace(&mut self.cursor_grab_mode.current_grab_mode, mode);
+        self.apply_cursor_grab(None, old_mode, mode);
 
-        match old_mode {
-            CursorGrabMode::None => (),
-            CursorGrabMode::Confined => self.apply_on_pointer(|_, data| {
-                data.unconfine_pointer();
-            }),
-            CursorGrabMode::Locked => {
-                self.apply_on_pointer(|_, data| data.unlock_pointer());
-            }
-        }
+        Ok(())
+    }
+
+    /// Replace the grab of the pointers of the given seat, or of the seats without their own grab.
+    fn apply_cursor_grab(
+        &self,
+        seat: Option<SeatId>,
+        old_mode: CursorGrabMode,
+        mode: CursorGrabMode,
+    ) {
+        let pointer_constraints = match self.pointer_constraints.as_ref() {
+            Some(pointer_constraints) => pointer_constraints,
+            None => return,
+        };
 
         let surface = self.window.wl_surface();
-        match mode {
-            CursorGrabMode::Locked => self.apply_on_pointer(|pointer, data| {
-                let pointer = pointer.pointer();
-                data.lock_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
-            }),
-            CursorGrabMode::Confined => self.apply_on_pointer(|pointer, data| {
-                let pointer = pointer.pointer();
-                data.confine_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
-            }),
-            CursorGrabMode::None => {
-                // Current lock/confine was already removed.
+        self.apply_on_pointer(|pointer, data| {
+            let applies = match seat {
+                Some(seat) => seat_id(data.seat()) == seat,
+                None => self.seat_override(data).grab_mode.is_none(),
+            };
+            if !applies {
+                return;
             }
-        }
 
-        Ok(())
+            match old_mode {
+                CursorGrabMode::None => (),
+                CursorGrabMode::Confined => data.unconfine_pointer(),
+                CursorGrabMode::Locked => data.unlock_pointer(),
+            }
+
+            let pointer = pointer.pointer();
+            match mode {
+                CursorGrabMode::Locked => {
+                    data.lock_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
+                }
+                CursorGrabMode::Confined => {
+                    data.confine_pointer(pointer_constraints, surface, pointer, &self.queue_handle)
+                }
+                CursorGrabMode::None => {
+                    // Current lock/confine was already removed.
+                }
+            }
+        });
     }
 
     pub fn show_window_menu(&self, position: LogicalPosition<u32>) {
@@ -914,9 +1298,14 @@ impl WindowState {
         if self.cursor_visible {
             match &self.selected_cursor {
                 SelectedCursor::Named(icon) => self.set_cursor(*icon),
-                SelectedCursor::Custom(cursor) => self.apply_custom_cursor(cursor),
+                SelectedCursor::Custom(cursor) => {
+                    self.apply_custom_cursor(cursor);
+                    self.animate_cursor();
+                }
             }
         } else {
+            self.stop_cursor_animation();
+
             for pointer in self.pointers.iter().filter_map(|pointer| pointer.upgrade()) {
                 let latest_enter_serial = pointer.pointer().winit_data().latest_enter_serial();
 
@@ -977,6 +1366,39 @@ impl WindowState {
 
         let mut applied = false;
         for text_input in &self.text_inputs {
+            let seat = text_input.seat_id();
+            if self
+                .seat_overrides
+                .get(&seat)
+                .and_then(|o| o.ime_allowed)
+                .is_some()
+            {
+                continue;
+            }
+
+            applied = true;
+            if allowed {
+                text_input.enable();
+                text_input.set_content_type_by_purpose(self.ime_purpose);
+            } else {
+                text_input.disable();
+            }
+            text_input.commit();
+        }
+
+        applied
+    }
+
+    /// Returns `true` if the requested state was applied.
+    pub fn set_seat_ime_allowed(&mut self, seat: SeatId, allowed: bool) -> bool {
+        self.seat_overrides.entry(seat).or_default().ime_allowed = Some(allowed);
+
+        let mut applied = false;
+        for text_input in &self.text_inputs {
+            if text_input.seat_id() != seat {
+                continue;
+            }
+
             applied = true;
             if allowed {
                 text_input.enable();
@@ -991,18 +1413,109 @@ impl WindowState {
     }
 
     /// Set the IME position.
+    ///
+    /// The seats with their own IME position set are left untouched.
     pub fn set_ime_cursor_area(&self, position: LogicalPosition<u32>, size: LogicalSize<u32>) {
-        // FIXME: This won't fly unless user will have a way to request IME window per seat, since
-        // the ime windows will be overlapping, but winit doesn't expose API to specify for
-        // which seat we're setting IME position.
         let (x, y) = (position.x as i32, position.y as i32);
         let (width, height) = (size.width as i32, size.height as i32);
         for text_input in self.text_inputs.iter() {
+            let seat = text_input.seat_id();
+            if self.seat_ime_cursor_area(seat).is_some() {
+                continue;
+            }
+
             text_input.set_cursor_rectangle(x, y, width, height);
             text_input.commit();
         }
     }
 
+    /// Set the IME position for the given seat.
+    pub fn set_seat_ime_cursor_area(
+        &mut self,
+        seat: SeatId,
+        position: LogicalPosition<u32>,
+        size: LogicalSize<u32>,
+    ) {
+        self.seat_overrides.entry(seat).or_default().ime_cursor_area = Some((position, size));
+
+        let (x, y) = (position.x as i32, position.y as i32);
+        let (width, height) = (size.width as i32, size.height as i32);
+        for text_input in self.text_inputs.iter() {
+            if text_input.seat_id() == seat {
+                text_input.set_cursor_rectangle(x, y, width, height);
+                text_input.commit();
+            }
+        }
+    }
+
+    /// Whether the IME input is allowed for the given seat.
+    pub fn seat_ime_allowed(&self, seat: SeatId) -> bool {
+        self.seat_overrides
+            .get(&seat)
+            .and_then(|seat_override| seat_override.ime_allowed)
+            .unwrap_or(self.ime_allowed)
+    }
+
+    /// The IME position set for the given seat.
+    pub fn seat_ime_cursor_area(
+        &self,
+        seat: SeatId,
+    ) -> Option<(LogicalPosition<u32>, LogicalSize<u32>)> {
+        self.seat_overrides
+            .get(&seat)
+            .and_then(|seat_override| seat_override.ime_cursor_area)
+    }
+
+    /// Reset the state of the given seat to the one of the window.
+    ///
+    /// Returns `true` if the IME input of the seat was enabled or disabled.
+    pub fn reset_seat(&mut self, seat: SeatId) -> bool {
+        let seat_override = match self.seat_overrides.remove(&seat) {
+            Some(seat_override) => seat_override,
+            None => return false,
+        };
+
+        if seat_override.cursor.is_some() {
+            self.reload_cursor_style();
+        }
+
+        if let Some(old_mode) = seat_override.grab_mode {
+            let mode = self.cursor_grab_mode.current_grab_mode;
+            self.apply_cursor_grab(Some(seat), old_mode, mode);
+        }
+
+        let mut ime_changed = false;
+        if seat_override
+            .ime_allowed
+            .is_some_and(|allowed| allowed != self.ime_allowed)
+        {
+            for text_input in &self.text_inputs {
+                if text_input.seat_id() != seat {
+                    continue;
+                }
+
+                ime_changed = true;
+                if self.ime_allowed {
+                    text_input.enable();
+                    text_input.set_content_type_by_purpose(self.ime_purpose);
+                } else {
+                    text_input.disable();
+                }
+                text_input.commit();
+            }
+        }
+
+        ime_changed
+    }
+
+    /// The state set for the seat of the pointer.
+    fn seat_override(&self, data: &WinitPointerData) -> SeatOverride {
+        self.seat_overrides
+            .get(&seat_id(data.seat()))
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Set the IME purpose.
     pub fn set_ime_purpose(&mut self, purpose: ImePurpose) {
         self.ime_purpose = purpose;
@@ -1031,6 +1544,31 @@ impl WindowState {
         if let Some(frame) = self.frame.as_mut() {
             frame.set_scaling_factor(scale_factor);
         }
+
+        if self.input_region.is_some() {
+            let _ = self.reload_input_region();
+        }
+
+        if self.opaque_region.is_some() {
+            self.reload_transparency_hint();
+        }
+
+        // Switch to the size of the custom cursor fitting the new scale.
+        let source = match &self.selected_cursor {
+            SelectedCursor::Custom(cursor)
+                if cursor.source.0.variant_index(scale_factor) != cursor.variant =>
+            {
+                cursor.source.clone()
+            }
+            _ => return,
+        };
+        let cursor = {
+            let mut pool = self.custom_cursor_pool.lock().unwrap();
+            CustomCursor::new(&mut pool, source, scale_factor)
+        };
+        self.stop_cursor_animation();
+        self.selected_cursor = SelectedCursor::Custom(cursor);
+        self.reload_cursor_style();
     }
 
     /// Make window background blurred
@@ -1053,6 +1591,113 @@ impl WindowState {
         }
     }
 
+    /// Set the opacity of the whole window, returning whether it's supported.
+    pub fn set_opacity(&mut self, opacity: f32) -> bool {
+        let manager = match self.alpha_modifier_manager.as_ref() {
+            Some(manager) => manager,
+            None => {
+                info!("Alpha modifier unavailable, unable to change opacity");
+                return false;
+            }
+        };
+
+        if opacity >= 1. {
+            // Destroying the modifier makes the window opaque again.
+            if let Some(alpha_modifier) = self.alpha_modifier.take() {
+                alpha_modifier.destroy();
+            }
+        } else {
+            let surface = self.window.wl_surface();
+            self.alpha_modifier
+                .get_or_insert_with(|| manager.get_surface(surface, &self.queue_handle))
+                .set_multiplier(opacity_to_multiplier(opacity));
+        }
+
+        true
+    }
+
+    /// Inhibit the idle behavior of the system while the window is visible.
+    pub fn set_idle_inhibited(&mut self, inhibited: bool) {
+        let manager = match self.idle_inhibit_manager.as_ref() {
+            Some(manager) => manager,
+            None => {
+                info!("Idle inhibit manager unavailable, unable to inhibit the idle behavior");
+                return;
+            }
+        };
+
+        if !inhibited {
+            if let Some(idle_inhibitor) = self.idle_inhibitor.take() {
+                idle_inhibitor.destroy();
+            }
+        } else if self.idle_inhibitor.is_none() {
+            let surface = self.window.wl_surface();
+            self.idle_inhibitor = Some(manager.create_inhibitor(surface, &self.queue_handle));
+        }
+    }
+
+    /// Hint that the window is a dialog of its parent, which could be modal.
+    pub fn set_modal(&mut self, modal: bool) {
+        let manager = match self.dialog_manager.as_ref() {
+            Some(manager) => manager,
+            None => {
+                info!("Xdg dialog unavailable, unable to hint the dialog");
+                return;
+            }
+        };
+
+        let toplevel = self.window.xdg_toplevel();
+        let dialog = self
+            .dialog
+            .get_or_insert_with(|| manager.get_dialog(toplevel, &self.queue_handle));
+        if modal {
+            dialog.set_modal();
+        } else {
+            dialog.unset_modal();
+        }
+    }
+
+    /// Export the window, revoking the handle of the previous export.
+    pub fn export_handle(
+        &mut self,
+        window_id: WindowId,
+        serial: AsyncRequestSerial,
+    ) -> Result<(), NotSupportedError> {
+        let xdg_foreign = self
+            .xdg_foreign
+            .as_ref()
+            .ok_or_else(NotSupportedError::new)?;
+        let exported = xdg_foreign.export_toplevel(
+            self.window.wl_surface(),
+            window_id,
+            serial,
+            &self.queue_handle,
+        );
+
+        if let Some(exported) = self.exported.replace(exported) {
+            exported.destroy();
+        }
+
+        Ok(())
+    }
+
+    /// Parent the window to the window of another process.
+    pub fn set_foreign_parent(&mut self, handle: &ForeignWindowHandle) {
+        let xdg_foreign = match self.xdg_foreign.as_ref() {
+            Some(xdg_foreign) => xdg_foreign,
+            None => {
+                info!("Xdg foreign unavailable, unable to set the parent");
+                return;
+            }
+        };
+
+        let imported =
+            xdg_foreign.set_parent_of(handle, self.window.wl_surface(), &self.queue_handle);
+        if let Some(foreign_parent) = self.foreign_parent.replace(imported) {
+            foreign_parent.destroy();
+        }
+    }
+
     /// Set the window title to a new value.
     ///
     /// This will automatically truncate the title to something meaningful.
@@ -1112,6 +1757,26 @@ impl Drop for WindowState {
             blur.release();
         }
 
+        if let Some(alpha_modifier) = self.alpha_modifier.take() {
+            alpha_modifier.destroy();
+        }
+
+        if let Some(dialog) = self.dialog.take() {
+            dialog.destroy();
+        }
+
+        if let Some(idle_inhibitor) = self.idle_inhibitor.take() {
+            idle_inhibitor.destroy();
+        }
+
+        if let Some(exported) = self.exported.take() {
+            exported.destroy();
+        }
+
+        if let Some(foreign_parent) = self.foreign_parent.take() {
+            foreign_parent.destroy();
+        }
+
         if let Some(fs) = self.fractional_scale.take() {
             fs.destroy();
         }
@@ -1144,6 +1809,22 @@ impl GrabState {
     }
 }
 
+/// The state set for a single seat, `None` when following the window.
+#[derive(Default, Debug, Clone, Copy)]
+struct SeatOverride {
+    /// The cursor icon of the seat.
+    cursor: Option<CursorIcon>,
+
+    /// The grab mode of the seat.
+    grab_mode: Option<CursorGrabMode>,
+
+    /// Whether the IME input is allowed for the seat.
+    ime_allowed: Option<bool>,
+
+    /// The IME position of the seat.
+    ime_cursor_area: Option<(LogicalPosition<u32>, LogicalSize<u32>)>,
+}
+
 /// The state of the frame callback.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FrameCallbackState {
@@ -1180,3 +1861,130 @@ fn into_sctk_adwaita_config(theme: Option<Theme>) -> sctk_adwaita::FrameConfig {
         None => sctk_adwaita::FrameConfig::auto(),
     }
 }
+
+/// The duration of a cursor frame, not letting zero durations spin the event loop.
+#[inline]
+fn frame_duration(duration: Duration) -> Duration {
+    duration.max(Duration::from_millis(1))
+}
+
+/// Round the size down to the resize increments, keeping it above the minimum size.
+fn snap_to_resize_increments(
+    size: LogicalSize<u32>,
+    increments: Option<LogicalSize<u32>>,
+    min_size: LogicalSize<u32>,
+) -> LogicalSize<u32> {
+    let increments = match increments {
+        Some(increments) => increments,
+        None => return size,
+    };
+
+    let snap = |size: u32, increment: u32, min: u32| {
+        if increment == 0 {
+            size
+        } else {
+            (size - size % increment).max(min)
+        }
+    };
+
+    LogicalSize::new(
+        snap(size.width, increments.width, min_size.width),
+        snap(size.height, increments.height, min_size.height),
+    )
+}
+
+/// Shrink the size to the aspect ratio, keeping it above the minimum size.
+fn fit_to_aspect_ratio(
+    size: LogicalSize<u32>,
+    aspect_ratio: Option<(u32, u32)>,
+    min_size: LogicalSize<u32>,
+) -> LogicalSize<u32> {
+    let (ratio_width, ratio_height) = match aspect_ratio {
+        Some(aspect_ratio) => aspect_ratio,
+        None => return size,
+    };
+
+    let (width, height) = (size.width as u64, size.height as u64);
+    let (ratio_width, ratio_height) = (ratio_width as u64, ratio_height as u64);
+    let size = if width * ratio_height > height * ratio_width {
+        LogicalSize::new((height * ratio_width / ratio_height) as u32, size.height)
+    } else {
+        LogicalSize::new(size.width, (width * ratio_height / ratio_width) as u32)
+    };
+
+    LogicalSize::new(
+        size.width.max(min_size.width),
+        size.height.max(min_size.height),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_increments() {
+        let min = LogicalSize::new(2, 2);
+        let size = LogicalSize::new(107, 53);
+
+        assert_eq!(snap_to_resize_increments(size, None, min), size);
+        assert_eq!(
+            snap_to_resize_increments(size, Some(LogicalSize::new(10, 25)), min),
+            LogicalSize::new(100, 50)
+        );
+        // Zero increments leave the dimension as is.
+        assert_eq!(
+            snap_to_resize_increments(size, Some(LogicalSize::new(0, 10)), min),
+            LogicalSize::new(107, 50)
+        );
+        // The minimum size wins over the increments.
+        assert_eq!(
+            snap_to_resize_increments(LogicalSize::new(7, 7), Some(LogicalSize::new(10, 10)), min),
+            min
+        );
+    }
+
+    #[test]
+    fn aspect_ratio() {
+        let min = LogicalSize::new(1, 1);
+
+        assert_eq!(
+            fit_to_aspect_ratio(LogicalSize::new(300, 100), None, min),
+            LogicalSize::new(300, 100)
+        );
+        // Too wide.
+        assert_eq!(
+            fit_to_aspect_ratio(LogicalSize::new(300, 100), Some((16, 9)), min),
+            LogicalSize::new(177, 100)
+        );
+        // Too tall.
+        assert_eq!(
+            fit_to_aspect_ratio(LogicalSize::new(160, 300), Some((16, 9)), min),
+            LogicalSize::new(160, 90)
+        );
+        // The minimum size wins over the aspect ratio.
+        assert_eq!(
+            fit_to_aspect_ratio(
+                LogicalSize::new(100, 100),
+                Some((4, 1)),
+                LogicalSize::new(50, 50)
+            ),
+            LogicalSize::new(100, 50)
+        );
+    }
+
+    #[test]
+    fn aspect_ratio_after_resize_increments() {
+        let min = LogicalSize::new(1, 1);
+        let size = snap_to_resize_increments(
+            LogicalSize::new(205, 205),
+            Some(LogicalSize::new(10, 10)),
+            min,
+        );
+
+        assert_eq!(
+            fit_to_aspect_ratio(size, Some((2, 1)), min),
+            LogicalSize::new(200, 100)
+        );
+    }
+}
@@ -43,18 +43,27 @@ atom_manager! {
     XIM_SERVERS,
 
     // Assorted ICCCM Atoms
+    _NET_WM_DESKTOP,
     _NET_WM_ICON,
     _NET_WM_MOVERESIZE,
     _NET_WM_NAME,
+    _NET_WM_OPAQUE_REGION,
     _NET_WM_PID,
     _NET_WM_PING,
     _NET_WM_STATE,
     _NET_WM_STATE_ABOVE,
     _NET_WM_STATE_BELOW,
+    _NET_WM_STATE_DEMANDS_ATTENTION,
     _NET_WM_STATE_FULLSCREEN,
     _NET_WM_STATE_HIDDEN,
     _NET_WM_STATE_MAXIMIZED_HORZ,
     _NET_WM_STATE_MAXIMIZED_VERT,
+    _NET_WM_STATE_MODAL,
+    _NET_WM_STATE_SHADED,
+    _NET_WM_STATE_SKIP_PAGER,
+    _NET_WM_STATE_SKIP_TASKBAR,
+    _NET_WM_STATE_STICKY,
+    _NET_WM_WINDOW_OPACITY,
     _NET_WM_WINDOW_TYPE,
 
     // Activation atoms.
@@ -92,12 +101,18 @@ atom_manager! {
     TextUriList: b"text/uri-list",
     None: b"None",
 
+    // XInput Atoms
+    DeviceProductId: b"Device Product ID",
+
     // Miscellaneous Atoms
     _GTK_THEME_VARIANT,
     _MOTIF_WM_HINTS,
     _NET_ACTIVE_WINDOW,
     _NET_CLIENT_LIST,
+    _NET_CURRENT_DESKTOP,
+    _NET_DESKTOP_NAMES,
     _NET_FRAME_EXTENTS,
+    _NET_NUMBER_OF_DESKTOPS,
     _NET_SUPPORTED,
     _NET_SUPPORTING_WM_CHECK,
     _XEMBED,
@@ -22,8 +22,8 @@ use xkbcommon_dl::xkb_mod_mask_t;
 
 use crate::dpi::{PhysicalPosition, PhysicalSize};
 use crate::event::{
-    DeviceEvent, ElementState, Event, Ime, MouseScrollDelta, RawKeyEvent, Touch, TouchPhase,
-    WindowEvent,
+    DeviceEvent, ElementState, Event, Ime, MouseScrollDelta, MouseScrollSource, RawKeyEvent, Touch,
+    TouchPhase, WindowEvent,
 };
 use crate::event::{InnerSizeWriter, MouseButton};
 use crate::event_loop::ActiveEventLoop as RootAEL;
@@ -65,6 +65,9 @@ pub struct EventProcessor {
     pub first_touch: Option<u64>,
     // Currently focused window belonging to this process
     pub active_window: Option<xproto::Window>,
+    /// The master keyboard delivering the core key events, the one of the latest raw key event or
+    /// the one focusing the active window.
+    pub active_keyboard: xinput::DeviceId,
     /// Latest modifiers we've sent for the user to trigger change in event.
     pub modifiers: Cell<ModifiersState>,
     pub xfiltered_modifiers: VecDeque<c_ulong>,
@@ -485,10 +488,12 @@ impl EventProcessor {
             };
 
             if !accepted {
-                unsafe {
+                let result = unsafe {
                     self.dnd
                         .send_status(window, source_window, DndState::Rejected)
-                        .expect("Failed to send `XdndStatus` message.");
+                };
+                if let Err(err) = result {
+                    log::error!("Failed to send `XdndStatus` message: {err}");
                 }
                 self.dnd.reset();
                 return;
@@ -512,10 +517,12 @@ impl EventProcessor {
                 }
             }
 
-            unsafe {
+            let result = unsafe {
                 self.dnd
                     .send_status(window, source_window, DndState::Accepted)
-                    .expect("Failed to send `XdndStatus` message.");
+            };
+            if let Err(err) = result {
+                log::error!("Failed to send `XdndStatus` message: {err}");
             }
             return;
         }
@@ -539,10 +546,9 @@ impl EventProcessor {
                 (source_window, DndState::Rejected)
             };
 
-            unsafe {
-                self.dnd
-                    .send_finished(window, source_window, state)
-                    .expect("Failed to send `XdndFinished` message.");
+            let result = unsafe { self.dnd.send_finished(window, source_window, state) };
+            if let Err(err) = result {
+                log::error!("Failed to send `XdndFinished` message: {err}");
             }
 
             self.dnd.reset();
@@ -690,17 +696,17 @@ impl EventProcessor {
             let last_scale_factor = shared_state_lock.last_monitor.scale_factor;
             let new_scale_factor = {
                 let window_rect = util::AaRect::new(new_outer_position, new_inner_size);
-                let monitor = wt
-                    .xconn
-                    .get_monitor_for_window(Some(window_rect))
-                    .expect("Failed to find monitor for window");
-
-                if monitor.is_dummy() {
+                match wt.xconn.get_monitor_for_window(Some(window_rect)) {
                     // Avoid updating monitor using a dummy monitor handle
-                    last_scale_factor
-                } else {
-                    shared_state_lock.last_monitor = monitor.clone();
-                    monitor.scale_factor
+                    Ok(monitor) if monitor.is_dummy() => last_scale_factor,
+                    Ok(monitor) => {
+                        shared_state_lock.last_monitor = monitor.clone();
+                        monitor.scale_factor
+                    }
+                    Err(err) => {
+                        log::error!("Failed to find monitor for window: {err}");
+                        last_scale_factor
+                    }
                 }
             };
             if last_scale_factor != new_scale_factor {
@@ -733,6 +739,10 @@ impl EventProcessor {
                 let new_inner_size = *inner_size.lock().unwrap();
                 drop(inner_size);
 
+                window.reload_custom_cursor();
+                window.reload_shape_regions();
+                window.reload_opaque_region();
+
                 if new_inner_size != old_inner_size {
                     window.request_inner_size_physical(new_inner_size.width, new_inner_size.height);
                     window.shared_state_lock().dpi_adjusted = Some(new_inner_size.into());
@@ -775,6 +785,8 @@ impl EventProcessor {
         }
 
         if resized {
+            window.reload_opaque_region();
+
             callback(
                 &self.target,
                 Event::WindowEvent {
@@ -839,9 +851,9 @@ impl EventProcessor {
         // Since all XIM stuff needs to happen from the same thread, we destroy the input
         // context here instead of when dropping the window.
         if let Some(ime) = wt.ime.as_ref() {
-            ime.borrow_mut()
-                .remove_context(window as XWindow)
-                .expect("Failed to destroy input context");
+            if let Err(err) = ime.borrow_mut().remove_context(window as XWindow) {
+                log::error!("Failed to destroy input context: {err:?}");
+            }
         }
 
         callback(
@@ -865,6 +877,36 @@ impl EventProcessor {
             || atom == atoms[_XSETTINGS_SETTINGS]
         {
             self.process_dpi_change(&mut callback);
+        } else if atom == atoms[_NET_WM_STATE] {
+            let xwindow = xev.window as xproto::Window;
+            if let Some(Some(state)) =
+                self.with_window(xwindow, |window| window.net_wm_state_changed())
+            {
+                let event = Event::WindowEvent {
+                    window_id: mkwid(xwindow),
+                    event: WindowEvent::StateChanged(state),
+                };
+                callback(&self.target, event);
+            }
+        } else if atom == atoms[_NET_WM_DESKTOP] {
+            let xwindow = xev.window as xproto::Window;
+            if self.window_exists(xwindow) {
+                let event = Event::WindowEvent {
+                    window_id: mkwid(xwindow),
+                    event: WindowEvent::DesktopChanged,
+                };
+                callback(&self.target, event);
+            }
+        } else if atom == atoms[_NET_CURRENT_DESKTOP] && xev.window as xproto::Window == wt.root {
+            // Every window moved in or out of sight.
+            let window_ids: Vec<_> = wt.windows.borrow().keys().copied().collect();
+            for window_id in window_ids {
+                let event = Event::WindowEvent {
+                    window_id: crate::window::WindowId(window_id),
+                    event: WindowEvent::DesktopChanged,
+                };
+                callback(&self.target, event);
+            }
         }
     }
 
@@ -916,6 +958,7 @@ impl EventProcessor {
 
         // Set the timestamp.
         wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        let timestamp = Some(wt.event_instant(xev.time));
 
         let window = match self.active_window {
             Some(window) => window,
@@ -923,7 +966,7 @@ impl EventProcessor {
         };
 
         let window_id = mkwid(window);
-        let device_id = mkdid(util::VIRTUAL_CORE_KEYBOARD);
+        let device_id = mkdid(self.active_keyboard);
 
         let keycode = xev.keycode as _;
 
@@ -991,7 +1034,7 @@ impl EventProcessor {
             }
 
             if let Some(mut key_processor) = self.xkb_context.key_context() {
-                let event = key_processor.process_key_event(keycode, state, repeat);
+                let event = key_processor.process_key_event(keycode, state, repeat, timestamp);
                 let event = Event::WindowEvent {
                     window_id,
                     event: WindowEvent::KeyboardInput {
@@ -1086,6 +1129,7 @@ impl EventProcessor {
 
         // Set the timestamp.
         wt.xconn.set_timestamp(event.time as xproto::Timestamp);
+        let timestamp = Some(wt.event_instant(event.time));
 
         // Deliver multi-touch events instead of emulated mouse events.
         if (event.flags & xinput2::XIPointerEmulated) != 0 {
@@ -1097,22 +1141,35 @@ impl EventProcessor {
                 device_id,
                 state,
                 button: MouseButton::Left,
+                timestamp,
             },
             xlib::Button2 => WindowEvent::MouseInput {
                 device_id,
                 state,
                 button: MouseButton::Middle,
+                timestamp,
             },
 
             xlib::Button3 => WindowEvent::MouseInput {
                 device_id,
                 state,
                 button: MouseButton::Right,
+                timestamp,
             },
 
-            // Suppress emulated scroll wheel clicks, since we handle the real motion events for those.
-            // In practice, even clicky scroll wheels appear to be reported by evdev (and XInput2 in
-            // turn) as axis motion, so we don't otherwise special-case these button presses.
+            // The scroll buttons of devices with scroll axes are reported by the motion of the axes,
+            // emulated by the server when the device sends the buttons. A click of the wheel is a
+            // press and a release, thus only the press scrolls.
+            4..=7
+                if state == ElementState::Released
+                    || self
+                        .devices
+                        .borrow()
+                        .get(&DeviceId(event.sourceid as xinput::DeviceId))
+                        .is_some_and(|device| !device.scroll_axes.is_empty()) =>
+            {
+                return;
+            }
             4..=7 => WindowEvent::MouseWheel {
                 device_id,
                 delta: match event.detail {
@@ -1123,22 +1180,36 @@ impl EventProcessor {
                     _ => unreachable!(),
                 },
                 phase: TouchPhase::Moved,
+                // The horizontal buttons are usually sent by tilting the wheel.
+                source: match event.detail {
+                    4 | 5 => MouseScrollSource::Wheel,
+                    _ => MouseScrollSource::WheelTilt,
+                },
+                value120: Some(match event.detail {
+                    4 => (0, 120),
+                    5 => (0, -120),
+                    6 => (120, 0),
+                    _ => (-120, 0),
+                }),
             },
             8 => WindowEvent::MouseInput {
                 device_id,
                 state,
                 button: MouseButton::Back,
+                timestamp,
             },
 
             9 => WindowEvent::MouseInput {
                 device_id,
                 state,
                 button: MouseButton::Forward,
+                timestamp,
             },
             x => WindowEvent::MouseInput {
                 device_id,
                 state,
                 button: MouseButton::Other(x as u16),
+                timestamp,
             },
         };
 
@@ -1162,6 +1233,18 @@ impl EventProcessor {
 
         let cursor_moved = self.with_window(window, |window| {
             let mut shared_state_lock = window.shared_state_lock();
+
+            // The locked cursor doesn't move, the motion is only reported by the raw events.
+            if let Some(lock_pos) = shared_state_lock.cursor_lock_pos {
+                drop(shared_state_lock);
+                if new_cursor_pos != lock_pos {
+                    let (x, y) = lock_pos;
+                    let _ = window.set_cursor_position_physical(x as i32, y as i32);
+                }
+
+                return false;
+            }
+
             util::maybe_change(&mut shared_state_lock.cursor_pos, new_cursor_pos)
         });
 
@@ -1173,6 +1256,7 @@ impl EventProcessor {
                 event: WindowEvent::CursorMoved {
                     device_id,
                     position,
+                    timestamp: Some(wt.event_instant(event.time)),
                 },
             };
             callback(&self.target, event);
@@ -1207,17 +1291,31 @@ impl EventProcessor {
                 let delta = (x - info.position) / info.increment;
                 info.position = x;
                 // X11 vertical scroll coordinates are opposite to winit's
-                let delta = match info.orientation {
-                    ScrollOrientation::Horizontal => {
-                        MouseScrollDelta::LineDelta(-delta as f32, 0.0)
-                    }
-                    ScrollOrientation::Vertical => MouseScrollDelta::LineDelta(0.0, -delta as f32),
+                let (delta_x, delta_y) = match info.orientation {
+                    ScrollOrientation::Horizontal => (-delta, 0.0),
+                    ScrollOrientation::Vertical => (0.0, -delta),
                 };
 
+                // The motion emulated from the legacy scroll buttons is a detent of the wheel, like
+                // the increment of the smooth scroll of wheels. XInput2 has no event once the
+                // fingers are lifted, thus the smooth scroll of touchpads never ends.
+                let emulated = (event.flags & xinput2::XIPointerEmulated) != 0;
+                let source = match info.orientation {
+                    _ if physical_device.finger_scroll && !emulated => MouseScrollSource::Finger,
+                    ScrollOrientation::Horizontal if emulated => MouseScrollSource::WheelTilt,
+                    _ => MouseScrollSource::Wheel,
+                };
+                let value120 = (source == MouseScrollSource::Wheel).then(|| {
+                    let value120 = |delta: f64| (delta * 120.0).round() as i32;
+                    (value120(delta_x), value120(delta_y))
+                });
+
                 WindowEvent::MouseWheel {
                     device_id,
-                    delta,
+                    delta: MouseScrollDelta::LineDelta(delta_x as f32, delta_y as f32),
                     phase: TouchPhase::Moved,
+                    source,
+                    value120,
                 }
             } else {
                 WindowEvent::AxisMotion {
@@ -1245,6 +1343,7 @@ impl EventProcessor {
 
         // Set the timestamp.
         wt.xconn.set_timestamp(event.time as xproto::Timestamp);
+        let timestamp = Some(wt.event_instant(event.time));
 
         let window = event.event as xproto::Window;
         let window_id = mkwid(window);
@@ -1282,6 +1381,7 @@ impl EventProcessor {
                 event: WindowEvent::CursorMoved {
                     device_id,
                     position,
+                    timestamp,
                 },
             };
             callback(&self.target, event);
@@ -1320,13 +1420,16 @@ impl EventProcessor {
 
         // Set the timestamp.
         wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        let timestamp = Some(wt.event_instant(xev.time));
 
         if let Some(ime) = wt.ime.as_ref() {
-            ime.borrow_mut()
-                .focus(xev.event)
-                .expect("Failed to focus input context");
+            if let Err(err) = ime.borrow_mut().focus(xev.event) {
+                log::error!("Failed to focus input context: {err:?}");
+            }
         }
 
+        self.active_keyboard = xev.deviceid as xinput::DeviceId;
+
         if self.active_window == Some(window) {
             return;
         }
@@ -1352,6 +1455,7 @@ impl EventProcessor {
         Self::handle_pressed_keys(
             &self.target,
             window_id,
+            mkdid(self.active_keyboard),
             ElementState::Pressed,
             &mut self.xkb_context,
             &mut callback,
@@ -1373,6 +1477,7 @@ impl EventProcessor {
             event: WindowEvent::CursorMoved {
                 device_id: mkdid(pointer_id as _),
                 position,
+                timestamp,
             },
         };
         callback(&self.target, event);
@@ -1393,9 +1498,9 @@ impl EventProcessor {
         }
 
         if let Some(ime) = wt.ime.as_ref() {
-            ime.borrow_mut()
-                .unfocus(xev.event)
-                .expect("Failed to unfocus input context");
+            if let Err(err) = ime.borrow_mut().unfocus(xev.event) {
+                log::error!("Failed to unfocus input context: {err:?}");
+            }
         }
 
         if self.active_window.take() == Some(window) {
@@ -1414,6 +1519,7 @@ impl EventProcessor {
             Self::handle_pressed_keys(
                 &self.target,
                 window_id,
+                mkdid(xev.deviceid as xinput::DeviceId),
                 ElementState::Released,
                 &mut self.xkb_context,
                 &mut callback,
@@ -1447,6 +1553,7 @@ impl EventProcessor {
 
         // Set the timestamp.
         wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
+        let timestamp = Some(wt.event_instant(xev.time));
 
         let window = xev.event as xproto::Window;
         if self.window_exists(window) {
@@ -1462,6 +1569,7 @@ impl EventProcessor {
                     event: WindowEvent::CursorMoved {
                         device_id: mkdid(util::VIRTUAL_CORE_POINTER),
                         position: location.cast(),
+                        timestamp,
                     },
                 };
                 callback(&self.target, event);
@@ -1475,6 +1583,7 @@ impl EventProcessor {
                     location,
                     force: None, // TODO
                     id,
+                    timestamp,
                 }),
             };
             callback(&self.target, event)
@@ -1553,7 +1662,10 @@ impl EventProcessor {
         if let Some(mouse_delta) = mouse_delta.consume() {
             let event = Event::DeviceEvent {
                 device_id: did,
-                event: DeviceEvent::MouseMotion { delta: mouse_delta },
+                event: DeviceEvent::MouseMotion {
+                    delta: mouse_delta,
+                    timestamp: Some(wt.event_instant(xev.time)),
+                },
             };
             callback(&self.target, event);
         }
@@ -1582,6 +1694,9 @@ impl EventProcessor {
         // Set the timestamp.
         wt.xconn.set_timestamp(xev.time as xproto::Timestamp);
 
+        // The core key event of the keystroke follows the raw one.
+        self.active_keyboard = xev.deviceid as xinput::DeviceId;
+
         let device_id = mkdid(xev.sourceid as xinput::DeviceId);
         let keycode = xev.detail as u32;
         if keycode < KEYCODE_OFFSET as u32 {
@@ -1887,14 +2002,13 @@ impl EventProcessor {
     fn handle_pressed_keys<T: 'static, F>(
         target: &RootAEL,
         window_id: crate::window::WindowId,
+        device_id: crate::event::DeviceId,
         state: ElementState,
         xkb_context: &mut Context,
         callback: &mut F,
     ) where
         F: FnMut(&RootAEL, Event<T>),
     {
-        let device_id = mkdid(util::VIRTUAL_CORE_KEYBOARD);
-
         // Update modifiers state and emit key events based on which keys are currently pressed.
         let window_target = Self::window_target(target);
         let xcb = window_target
@@ -1923,7 +2037,7 @@ impl EventProcessor {
             .into_iter()
             .filter(|k| *k >= KEYCODE_OFFSET)
         {
-            let event = key_processor.process_key_event(keycode as u32, state, false);
+            let event = key_processor.process_key_event(keycode as u32, state, false, None);
             let event = Event::WindowEvent {
                 window_id,
                 event: WindowEvent::KeyboardInput {
@@ -1941,9 +2055,9 @@ impl EventProcessor {
         F: FnMut(&RootAEL, Event<T>),
     {
         let wt = Self::window_target(&self.target);
-        wt.xconn
-            .reload_database()
-            .expect("failed to reload Xft database");
+        if let Err(err) = wt.xconn.reload_database() {
+            log::error!("Failed to reload Xft database: {err}");
+        }
 
         // In the future, it would be quite easy to emit monitor hotplug events.
         let prev_list = {
@@ -1954,10 +2068,13 @@ impl EventProcessor {
             }
         };
 
-        let new_list = wt
-            .xconn
-            .available_monitors()
-            .expect("Failed to get monitor list");
+        let new_list = match wt.xconn.available_monitors() {
+            Ok(new_list) => new_list,
+            Err(err) => {
+                log::error!("Failed to get monitor list: {err}");
+                return;
+            }
+        };
         for new_monitor in new_list {
             // Previous list may be empty, in case of disconnecting and
             // reconnecting the only one monitor. We still need to emit events in
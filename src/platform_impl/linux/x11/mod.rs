@@ -16,22 +16,34 @@ use std::{ptr, slice, str};
 
 use calloop::generic::Generic;
 use calloop::EventLoop as Loop;
-use calloop::{ping::Ping, Readiness};
+use calloop::{ping::Ping, LoopHandle, Readiness, RegistrationToken};
 use libc::{setlocale, LC_CTYPE};
 use log::warn;
 
 use x11rb::connection::RequestConnection;
 use x11rb::errors::{ConnectError, ConnectionError, IdsExhausted, ReplyError};
+use x11rb::protocol::screensaver::ConnectionExt as _;
 use x11rb::protocol::xinput::{self, ConnectionExt as _};
 use x11rb::protocol::xkb;
 use x11rb::protocol::xproto::{self, ConnectionExt as _};
 use x11rb::x11_utils::X11Error as LogicalError;
 use x11rb::xcb_ffi::ReplyOrIdError;
 
-use crate::error::{EventLoopError, OsError as RootOsError};
-use crate::event::{Event, StartCause, WindowEvent};
-use crate::event_loop::{ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents, EventLoopClosed};
+use crate::error::{EventLoopError, ExternalError, OsError as RootOsError};
+use crate::event::{DeviceEvent, DeviceId as RootDeviceId, Event, StartCause, WindowEvent};
+use crate::event_loop::{
+    ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents, EventLoopBuilder, EventLoopClosed,
+    FdReadiness, FdToken,
+};
+use crate::platform::backend::BackendCapabilities;
+use crate::platform::fd_source::FdInterest;
+use crate::platform::input_device::{InputDevice, InputDeviceCapabilities, InputDeviceKind};
 use crate::platform::pump_events::PumpStatus;
+use crate::platform::seat::{Seat, SeatId};
+#[cfg(target_os = "linux")]
+use crate::platform_impl::common::gamepad::{GamepadId, Gamepads};
+use crate::platform_impl::common::stream_waker::StreamWaker;
+use crate::platform_impl::common::timestamp::ServerClock;
 use crate::platform_impl::common::xkb::Context;
 use crate::platform_impl::platform::{min_timeout, WindowId};
 use crate::platform_impl::{
@@ -74,6 +86,7 @@ type X11Source = Generic<BorrowedFd<'static>>;
 struct WakeSender<T> {
     sender: Sender<T>,
     waker: Ping,
+    stream_waker: StreamWaker,
 }
 
 impl<T> Clone for WakeSender<T> {
@@ -81,6 +94,7 @@ impl<T> Clone for WakeSender<T> {
         Self {
             sender: self.sender.clone(),
             waker: self.waker.clone(),
+            stream_waker: self.stream_waker.clone(),
         }
     }
 }
@@ -90,6 +104,7 @@ impl<T> WakeSender<T> {
         let res = self.sender.send(t).map_err(|e| EventLoopClosed(e.0));
         if res.is_ok() {
             self.waker.ping();
+            self.stream_waker.wake();
         }
         res
     }
@@ -141,17 +156,25 @@ pub struct ActiveEventLoop {
     windows: RefCell<HashMap<WindowId, Weak<UnownedWindow>>>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<ActivationToken>,
+    export_sender: WakeSender<ExportRequest>,
     device_events: Cell<DeviceEvents>,
+    loop_handle: LoopHandle<'static, EventLoopState>,
+    fd_sources: RefCell<HashMap<FdToken, RegistrationToken>>,
+    server_clock: ServerClock,
+    #[cfg(target_os = "linux")]
+    gamepads: std::cell::OnceCell<std::rc::Rc<Gamepads>>,
 }
 
 pub struct EventLoop<T: 'static> {
     loop_running: bool,
     event_loop: Loop<'static, EventLoopState>,
     waker: calloop::ping::Ping,
+    stream_waker: StreamWaker,
     event_processor: EventProcessor,
     redraw_receiver: PeekableReceiver<WindowId>,
     user_receiver: PeekableReceiver<T>,
     activation_receiver: PeekableReceiver<ActivationToken>,
+    export_receiver: PeekableReceiver<ExportRequest>,
     user_sender: Sender<T>,
 
     /// The current state of the event loop.
@@ -160,9 +183,17 @@ pub struct EventLoop<T: 'static> {
 
 type ActivationToken = (WindowId, crate::event_loop::AsyncRequestSerial);
 
+type ExportRequest = (WindowId, crate::event_loop::AsyncRequestSerial);
+
 struct EventLoopState {
     /// The latest readiness state for the x11 file descriptor
     x11_readiness: Readiness,
+
+    /// The readiness of the user's file descriptors since the last iteration.
+    fd_events: Vec<(FdToken, FdReadiness)>,
+
+    /// The events of the devices not handled by the X server, like the gamepads.
+    device_events: Vec<(RootDeviceId, DeviceEvent)>,
 }
 
 pub struct EventLoopProxy<T: 'static> {
@@ -248,6 +279,17 @@ impl<T: 'static> EventLoop<T> {
 
         xconn.update_cached_wm_info(root);
 
+        // Get PropertyNotify events from the root window, to follow the desktops.
+        xconn
+            .xcb_connection()
+            .change_window_attributes(
+                root,
+                &xproto::ChangeWindowAttributesAux::new()
+                    .event_mask(xproto::EventMask::PROPERTY_CHANGE),
+            )
+            .expect("Failed to select root window events")
+            .ignore_error();
+
         // Create an event loop.
         let event_loop =
             Loop::<EventLoopState>::try_new().expect("Failed to initialize the event loop");
@@ -282,6 +324,9 @@ impl<T: 'static> EventLoop<T> {
         // Create a channel for sending activation tokens.
         let (activation_token_sender, activation_token_channel) = mpsc::channel();
 
+        // Create a channel for exporting the windows.
+        let (export_sender, export_channel) = mpsc::channel();
+
         // Create a channel for sending user events.
         let (user_sender, user_channel) = mpsc::channel();
 
@@ -291,6 +336,9 @@ impl<T: 'static> EventLoop<T> {
         let mut xmodmap = util::ModifierKeymap::new();
         xmodmap.reload_from_x_connection(&xconn);
 
+        // Wakes up the task awaiting the event stream on the events sent from other threads.
+        let stream_waker = StreamWaker::new();
+
         let window_target = ActiveEventLoop {
             ime,
             root,
@@ -304,12 +352,24 @@ impl<T: 'static> EventLoop<T> {
             redraw_sender: WakeSender {
                 sender: redraw_sender, // not used again so no clone
                 waker: waker.clone(),
+                stream_waker: stream_waker.clone(),
             },
             activation_sender: WakeSender {
                 sender: activation_token_sender, // not used again so no clone
                 waker: waker.clone(),
+                stream_waker: stream_waker.clone(),
+            },
+            export_sender: WakeSender {
+                sender: export_sender, // not used again so no clone
+                waker: waker.clone(),
+                stream_waker: stream_waker.clone(),
             },
             device_events: Default::default(),
+            loop_handle: event_loop.handle(),
+            fd_sources: Default::default(),
+            server_clock: ServerClock::new(),
+            #[cfg(target_os = "linux")]
+            gamepads: Default::default(),
         };
 
         // Set initial device event filter.
@@ -336,6 +396,7 @@ impl<T: 'static> EventLoop<T> {
             held_key_press: None,
             first_touch: None,
             active_window: None,
+            active_keyboard: util::VIRTUAL_CORE_KEYBOARD,
             modifiers: Default::default(),
             is_composing: false,
         };
@@ -367,13 +428,17 @@ impl<T: 'static> EventLoop<T> {
             loop_running: false,
             event_loop,
             waker,
+            stream_waker,
             event_processor,
             redraw_receiver: PeekableReceiver::from_recv(redraw_channel),
             activation_receiver: PeekableReceiver::from_recv(activation_token_channel),
+            export_receiver: PeekableReceiver::from_recv(export_channel),
             user_receiver: PeekableReceiver::from_recv(user_channel),
             user_sender,
             state: EventLoopState {
                 x11_readiness: Readiness::EMPTY,
+                fd_events: Vec::new(),
+                device_events: Vec::new(),
             },
         }
     }
@@ -383,20 +448,65 @@ impl<T: 'static> EventLoop<T> {
             user_sender: WakeSender {
                 sender: self.user_sender.clone(),
                 waker: self.waker.clone(),
+                stream_waker: self.stream_waker.clone(),
             },
         }
     }
 
+    pub(crate) fn stream_waker(&self) -> &StreamWaker {
+        &self.stream_waker
+    }
+
     pub(crate) fn window_target(&self) -> &RootAEL {
         &self.event_processor.target
     }
 
+    #[cfg(target_os = "linux")]
+    pub(crate) fn enable_gamepads(&mut self) {
+        let gamepads = match Gamepads::new() {
+            Ok(gamepads) => std::rc::Rc::new(gamepads),
+            Err(error) => {
+                warn!("Failed to read the gamepads: {error}");
+                return;
+            }
+        };
+
+        // SAFETY: the gamepads are kept alive by the callback of the source.
+        let fd = unsafe { BorrowedFd::borrow_raw(gamepads.as_fd().as_raw_fd()) };
+        let source = Generic::new(fd, calloop::Interest::READ, calloop::Mode::Level);
+        let source_gamepads = gamepads.clone();
+        let result = self.event_loop.handle().insert_source(
+            source,
+            move |_, _, state: &mut EventLoopState| {
+                source_gamepads.dispatch(|device_id, event| {
+                    state.device_events.push((device_id, event));
+                });
+                Ok(calloop::PostAction::Continue)
+            },
+        );
+        if let Err(error) = result {
+            warn!("Failed to poll the gamepads: {}", error.error);
+            return;
+        }
+
+        for device_id in gamepads.device_ids() {
+            self.state
+                .device_events
+                .push((device_id, DeviceEvent::Added));
+        }
+        let window_target = EventProcessor::window_target(&self.event_processor.target);
+        let _ = window_target.gamepads.set(gamepads);
+    }
+
     pub fn run_on_demand<F>(&mut self, mut event_handler: F) -> Result<(), EventLoopError>
     where
         F: FnMut(Event<T>, &RootAEL),
     {
         let exit = loop {
             match self.pump_events(None, &mut event_handler) {
+                PumpStatus::Exit(_) if self.connection_lost() => {
+                    return Err(EventLoopError::ConnectionLost);
+                }
                 PumpStatus::Exit(0) => {
                     break Ok(());
                 }
@@ -440,6 +550,10 @@ impl<T: 'static> EventLoop<T> {
         if let Some(code) = self.exit_code() {
             self.loop_running = false;
 
+            if self.connection_lost() {
+                callback(Event::ConnectionLost, self.window_target());
+            }
+
             callback(Event::LoopExiting, self.window_target());
 
             PumpStatus::Exit(code)
@@ -452,6 +566,8 @@ impl<T: 'static> EventLoop<T> {
         self.event_processor.poll()
             || self.user_receiver.has_incoming()
             || self.redraw_receiver.has_incoming()
+            || !self.state.fd_events.is_empty()
+            || !self.state.device_events.is_empty()
     }
 
     pub fn poll_events_with_timeout<F>(&mut self, mut timeout: Option<Duration>, mut callback: F)
@@ -460,6 +576,12 @@ impl<T: 'static> EventLoop<T> {
     {
         let start = Instant::now();
 
+        // The requests made while handling the previous events could have hit the connection loss.
+        if self.connection_lost() {
+            self.exit_on_connection_lost();
+            return;
+        }
+
         let has_pending = self.has_pending();
 
         timeout = if has_pending {
@@ -489,6 +611,11 @@ impl<T: 'static> EventLoop<T> {
             return;
         }
 
+        if self.connection_lost() {
+            self.exit_on_connection_lost();
+            return;
+        }
+
         // NB: `StartCause::Init` is handled as a special case and doesn't need
         // to be considered here
         let cause = match self.control_flow() {
@@ -547,6 +674,37 @@ impl<T: 'static> EventLoop<T> {
         // Process all pending events
         self.drain_events(callback);
 
+        // Report the readiness of the user's file descriptors.
+        for (token, readiness) in self.state.fd_events.drain(..) {
+            let window_target = EventProcessor::window_target(&self.event_processor.target);
+            if !window_target.fd_sources.borrow().contains_key(&token) {
+                // Unregistered while the event was pending.
+                continue;
+            }
+
+            callback(
+                Event::FdReady { token, readiness },
+                &self.event_processor.target,
+            );
+        }
+
+        // Report the events of the devices not handled by the X server.
+        let window_target = EventProcessor::window_target(&self.event_processor.target);
+        let allowed = match window_target.device_events.get() {
+            DeviceEvents::Always => true,
+            DeviceEvents::WhenFocused => self.event_processor.active_window.is_some(),
+            DeviceEvents::Never => false,
+        };
+        for (device_id, event) in self.state.device_events.drain(..) {
+            // The connection changes are delivered regardless, like for the other devices.
+            if allowed || matches!(event, DeviceEvent::Added | DeviceEvent::Removed) {
+                callback(
+                    Event::DeviceEvent { device_id, event },
+                    &self.event_processor.target,
+                );
+            }
+        }
+
         // Empty activation tokens.
         while let Ok((window_id, serial)) = self.activation_receiver.try_recv() {
             let token = self
@@ -573,6 +731,26 @@ impl<T: 'static> EventLoop<T> {
             }
         }
 
+        // Empty the export requests, the handle being the XID of the window.
+        while let Ok((window_id, serial)) = self.export_receiver.try_recv() {
+            let exists = self
+                .event_processor
+                .with_window(window_id.0 as xproto::Window, |_| ())
+                .is_some();
+
+            if exists {
+                let handle = window_id.0.to_string();
+                let event = Event::WindowEvent {
+                    window_id: crate::window::WindowId(window_id),
+                    event: WindowEvent::HandleExported {
+                        serial,
+                        handle: crate::window::ForeignWindowHandle::from_raw(handle),
+                    },
+                };
+                callback(event, &self.event_processor.target)
+            }
+        }
+
         // Empty the user event buffer
         {
             while let Ok(event) = self.user_receiver.try_recv() {
@@ -612,7 +790,11 @@ impl<T: 'static> EventLoop<T> {
     {
         let mut xev = MaybeUninit::uninit();
 
-        while unsafe { self.event_processor.poll_one_event(xev.as_mut_ptr()) } {
+        // Stop processing once the connection is gone, the requests made by the handlers would
+        // fail anyway.
+        while !self.connection_lost()
+            && unsafe { self.event_processor.poll_one_event(xev.as_mut_ptr()) }
+        {
             let mut xev = unsafe { xev.assume_init() };
             self.event_processor
                 .process_event(&mut xev, |window_target, event| {
@@ -630,6 +812,17 @@ impl<T: 'static> EventLoop<T> {
         }
     }
 
+    fn connection_lost(&self) -> bool {
+        let window_target = EventProcessor::window_target(&self.event_processor.target);
+        window_target.xconn.is_connection_lost()
+    }
+
+    fn exit_on_connection_lost(&self) {
+        // The windows are gone with the connection, allow to reconnect with a new event loop.
+        EventLoopBuilder::<()>::allow_event_loop_recreation();
+        self.set_exit_code(1);
+    }
+
     fn control_flow(&self) -> ControlFlow {
         let window_target = EventProcessor::window_target(&self.event_processor.target);
         window_target.control_flow()
@@ -678,6 +871,21 @@ impl ActiveEventLoop {
         self.xconn.primary_monitor().ok()
     }
 
+    #[inline]
+    pub fn desktop_count(&self) -> Option<u32> {
+        self.xconn.desktop_count(self.root)
+    }
+
+    #[inline]
+    pub fn desktop_names(&self) -> Vec<String> {
+        self.xconn.desktop_names(self.root)
+    }
+
+    #[inline]
+    pub fn current_desktop(&self) -> Option<u32> {
+        self.xconn.current_desktop(self.root)
+    }
+
     pub(crate) fn create_custom_cursor(&self, cursor: CustomCursorSource) -> RootCustomCursor {
         RootCustomCursor {
             inner: PlatformCustomCursor::X(CustomCursor::new(self, cursor.inner)),
@@ -688,6 +896,153 @@ impl ActiveEventLoop {
         self.device_events.set(allowed);
     }
 
+    pub(crate) unsafe fn register_fd(
+        &self,
+        fd: BorrowedFd<'_>,
+        interest: FdInterest,
+    ) -> Result<FdToken, ExternalError> {
+        let token = FdToken::get();
+
+        // SAFETY: the caller guarantees that the fd stays open while registered.
+        let fd = unsafe { BorrowedFd::borrow_raw(fd.as_raw_fd()) };
+        let source = Generic::new(fd, interest.into(), calloop::Mode::Level);
+        let registration = self
+            .loop_handle
+            .insert_source(source, move |readiness, _, state| {
+                state.fd_events.push((token, readiness.into()));
+                Ok(calloop::PostAction::Continue)
+            })
+            .map_err(|error| {
+                log::error!("Failed to register the file descriptor: {}", error.error);
+                ExternalError::Os(os_error!(OsError::Misc(
+                    "failed to register the file descriptor"
+                )))
+            })?;
+
+        self.fd_sources.borrow_mut().insert(token, registration);
+        Ok(token)
+    }
+
+    pub(crate) fn unregister_fd(&self, token: FdToken) {
+        if let Some(registration) = self.fd_sources.borrow_mut().remove(&token) {
+            self.loop_handle.remove(registration);
+        }
+    }
+
+    pub(crate) fn capabilities(&self) -> BackendCapabilities {
+        let mut capabilities = self.xconn.wm_capabilities();
+        capabilities.set(BackendCapabilities::IME, self.ime.is_some());
+        capabilities
+    }
+
+    pub(crate) fn input_devices(&self) -> Vec<InputDevice> {
+        #[allow(unused_mut)]
+        let mut devices: Vec<_> = match DeviceInfo::get(&self.xconn, ffi::XIAllDevices) {
+            Some(info) => info
+                .iter()
+                .map(|info| {
+                    let name = unsafe { CStr::from_ptr(info.name).to_string_lossy() }.into_owned();
+                    let (kind, capabilities) = Device::kind_and_capabilities(info, &name);
+                    let (vendor_id, product_id) = self.device_product_id(info.deviceid).unzip();
+                    InputDevice {
+                        id: mkdid(info.deviceid as xinput::DeviceId),
+                        name,
+                        kind,
+                        vendor_id,
+                        product_id,
+                        capabilities,
+                    }
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        #[cfg(target_os = "linux")]
+        if let Some(gamepads) = self.gamepads.get() {
+            devices.extend(gamepads.input_devices());
+        }
+
+        devices
+    }
+
+    pub(crate) fn seats(&self) -> Vec<Seat> {
+        match DeviceInfo::get(&self.xconn, ffi::XIAllMasterDevices) {
+            Some(info) => info
+                .iter()
+                .filter(|info| info._use == ffi::XIMasterPointer)
+                .map(|info| Seat {
+                    id: SeatId(info.deviceid as u32),
+                    name: unsafe { CStr::from_ptr(info.name).to_string_lossy() }.into_owned(),
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub(crate) fn device_seat(&self, device_id: DeviceId) -> Option<SeatId> {
+        let info = DeviceInfo::get(&self.xconn, ffi::XIAllDevices)?;
+        let find = |id: c_int| info.iter().find(|info| info.deviceid == id);
+
+        // Walk from the slave devices to their master, and from the master keyboards to the master
+        // pointer they're paired with.
+        let mut device = find(device_id.0 as c_int)?;
+        loop {
+            match device._use {
+                ffi::XIMasterPointer => return Some(SeatId(device.deviceid as u32)),
+                ffi::XIMasterKeyboard | ffi::XISlavePointer | ffi::XISlaveKeyboard => {
+                    device = find(device.attachment)?;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) fn set_rumble(
+        &self,
+        id: GamepadId,
+        strong: f32,
+        weak: f32,
+        duration: Duration,
+    ) -> Result<(), ExternalError> {
+        match self.gamepads.get() {
+            Some(gamepads) => gamepads.set_rumble(id, strong, weak, duration),
+            None => Err(ExternalError::NotSupported(
+                crate::error::NotSupportedError::new(),
+            )),
+        }
+    }
+
+    /// Read the vendor and product ids of the device set by the input drivers.
+    fn device_product_id(&self, device: c_int) -> Option<(u16, u16)> {
+        let reply = self
+            .xconn
+            .xcb_connection()
+            .xinput_xi_get_property(
+                device as xinput::DeviceId,
+                false,
+                self.xconn.atoms()[DeviceProductId],
+                xproto::Atom::from(xproto::AtomEnum::INTEGER),
+                0,
+                2,
+            )
+            .ok()?
+            .reply()
+            .ok()?;
+
+        match reply.items {
+            xinput::XIGetPropertyItems::Data32(ids) if ids.len() == 2 => {
+                Some((ids[0] as u16, ids[1] as u16))
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert the server time of an input event to the local time.
+    fn event_instant(&self, time: c_ulong) -> Instant {
+        self.server_clock.instant(time as u32)
+    }
+
     /// Update the device event based on window focus.
     pub fn update_listen_device_events(&self, focus: bool) {
         let device_events = self.device_events.get() == DeviceEvents::Always
@@ -846,6 +1201,13 @@ impl Drop for Window {
         let window = self.deref();
         let xconn = &window.xconn;
 
+        // The screensaver suspension outlives the window otherwise.
+        if window.shared_state_lock().idle_inhibited {
+            if let Ok(c) = xconn.xcb_connection().screensaver_suspend(false.into()) {
+                c.ignore_error();
+            }
+        }
+
         if let Ok(c) = xconn
             .xcb_connection()
             .destroy_window(window.id().0 as xproto::Window)
@@ -993,13 +1355,41 @@ type VoidCookie<'a> = x11rb::cookie::VoidCookie<'a, X11rbConnection>;
 
 /// Extension trait for `Result<VoidCookie, E>`.
 trait CookieResultExt {
-    /// Unwrap the send error and ignore the result.
+    /// Ignore the result of the request.
+    ///
+    /// Sending the request only fails once the connection to the X server is lost, which was
+    /// already reported with `Event::ConnectionLost`, thus the send error is only logged.
     fn expect_then_ignore_error(self, msg: &str);
 }
 
 impl<'a, E: fmt::Debug> CookieResultExt for Result<VoidCookie<'a>, E> {
     fn expect_then_ignore_error(self, msg: &str) {
-        self.expect(msg).ignore_error()
+        match self {
+            Ok(cookie) => cookie.ignore_error(),
+            Err(err) => warn!("{msg}: {err:?}"),
+        }
+    }
+}
+
+/// Extension trait for the results of the requests to the X server.
+trait XResultExt<T> {
+    /// Unwrap the result, unless the connection to the X server was lost.
+    ///
+    /// The connection loss was already reported with `Event::ConnectionLost`, thus the error is
+    /// only logged and `None` returned.
+    fn expect_or_lost(self, xconn: &XConnection, msg: &str) -> Option<T>;
+}
+
+impl<T, E: fmt::Debug> XResultExt<T> for Result<T, E> {
+    fn expect_or_lost(self, xconn: &XConnection, msg: &str) -> Option<T> {
+        match self {
+            Ok(value) => Some(value),
+            Err(err) if xconn.is_connection_lost() => {
+                warn!("{msg}: {err:?}");
+                None
+            }
+            Err(err) => panic!("{msg}: {err:?}"),
+        }
     }
 }
 
@@ -1014,6 +1404,9 @@ fn mkdid(w: xinput::DeviceId) -> crate::event::DeviceId {
 pub struct Device {
     _name: String,
     scroll_axes: Vec<(i32, ScrollAxis)>,
+    /// Whether the smooth scroll of the scroll axes is driven by fingers, on touchpads and
+    /// touchscreens.
+    finger_scroll: bool,
     // For master devices, this is the paired device (pointer <-> keyboard).
     // For slave devices, this is the master.
     attachment: c_int,
@@ -1059,9 +1452,16 @@ impl Device {
             }
         }
 
+        let finger_scroll = !scroll_axes.is_empty()
+            && matches!(
+                Device::kind_and_capabilities(info, &name).0,
+                InputDeviceKind::Touchpad | InputDeviceKind::Touchscreen
+            );
+
         let mut device = Device {
             _name: name.into_owned(),
             scroll_axes,
+            finger_scroll,
             attachment: info.attachment,
         };
         device.reset_scroll_position(info);
@@ -1086,6 +1486,58 @@ impl Device {
         }
     }
 
+    fn kind_and_capabilities(
+        info: &ffi::XIDeviceInfo,
+        name: &str,
+    ) -> (InputDeviceKind, InputDeviceCapabilities) {
+        let mut capabilities = InputDeviceCapabilities::empty();
+        let mut touch_mode = None;
+        for &class_ptr in Device::classes(info) {
+            match unsafe { (*class_ptr)._type } {
+                ffi::XIKeyClass => capabilities |= InputDeviceCapabilities::KEYS,
+                ffi::XIButtonClass => capabilities |= InputDeviceCapabilities::BUTTONS,
+                ffi::XIScrollClass => capabilities |= InputDeviceCapabilities::SCROLL,
+                ffi::XIValuatorClass => {
+                    let info = unsafe { &*(class_ptr as *const ffi::XIValuatorClassInfo) };
+                    capabilities |= if info.mode == ffi::XIModeAbsolute {
+                        InputDeviceCapabilities::ABSOLUTE_MOTION
+                    } else {
+                        InputDeviceCapabilities::RELATIVE_MOTION
+                    };
+                }
+                ffi::XITouchClass => {
+                    let info = unsafe { &*(class_ptr as *const ffi::XITouchClassInfo) };
+                    capabilities |= InputDeviceCapabilities::TOUCH;
+                    touch_mode = Some(info.mode);
+                }
+                _ => (),
+            }
+        }
+
+        let keyboard = info._use == ffi::XIMasterKeyboard
+            || info._use == ffi::XISlaveKeyboard
+            || (info._use == ffi::XIFloatingSlave
+                && capabilities.contains(InputDeviceCapabilities::KEYS)
+                && !capabilities.contains(InputDeviceCapabilities::BUTTONS));
+
+        // The drivers don't always expose the touch class of touchpads, fall back to the name.
+        let kind = if keyboard {
+            InputDeviceKind::Keyboard
+        } else if touch_mode == Some(ffi::XIDirectTouch) {
+            InputDeviceKind::Touchscreen
+        } else if touch_mode == Some(ffi::XIDependentTouch)
+            || name.to_lowercase().contains("touchpad")
+        {
+            InputDeviceKind::Touchpad
+        } else if capabilities.contains(InputDeviceCapabilities::ABSOLUTE_MOTION) {
+            InputDeviceKind::Tablet
+        } else {
+            InputDeviceKind::Pointer
+        };
+
+        (kind, capabilities)
+    }
+
     #[inline]
     fn physical_device(info: &ffi::XIDeviceInfo) -> bool {
         info._use == ffi::XISlaveKeyboard
@@ -6,14 +6,20 @@ use std::{
 };
 
 use x11rb::connection::Connection;
+use x11rb::protocol::xinput::{self, ConnectionExt as _};
 
+use crate::cursor::{best_variant, CustomCursorFrame};
 use crate::{platform_impl::PlatformCustomCursorSource, window::CursorIcon};
 
 use super::super::ActiveEventLoop;
 use super::*;
 
 impl XConnection {
-    pub fn set_cursor_icon(&self, window: xproto::Window, cursor: Option<CursorIcon>) {
+    pub fn set_cursor_icon(
+        &self,
+        window: xproto::Window,
+        cursor: Option<CursorIcon>,
+    ) -> Result<(), X11Error> {
         let cursor = *self
             .cursor_cache
             .lock()
@@ -22,12 +28,49 @@ impl XConnection {
             .or_insert_with(|| self.get_cursor(cursor));
 
         self.update_cursor(window, cursor)
-            .expect("Failed to set cursor");
     }
 
-    pub(crate) fn set_custom_cursor(&self, window: xproto::Window, cursor: &CustomCursor) {
-        self.update_cursor(window, cursor.inner.cursor)
-            .expect("Failed to set cursor");
+    pub(crate) fn set_custom_cursor(
+        &self,
+        window: xproto::Window,
+        cursor: &CustomCursor,
+        scale_factor: f64,
+    ) -> Result<(), X11Error> {
+        self.update_cursor(window, cursor.cursor(scale_factor))
+    }
+
+    /// Set the cursor of the master pointer over the window, `None` using the one of the window.
+    pub fn set_device_cursor_icon(
+        &self,
+        window: xproto::Window,
+        device_id: xinput::DeviceId,
+        cursor: Option<CursorIcon>,
+    ) -> Result<(), X11Error> {
+        let cursor = cursor.map_or(0, |cursor| {
+            *self
+                .cursor_cache
+                .lock()
+                .unwrap()
+                .entry(Some(cursor))
+                .or_insert_with(|| self.get_cursor(Some(cursor)))
+        });
+
+        self.xcb_connection()
+            .xinput_xi_change_cursor(window, cursor as xproto::Cursor, device_id)?
+            .ignore_error();
+        self.flush_requests()?;
+
+        Ok(())
+    }
+
+    /// The invisible cursor.
+    pub fn empty_cursor(&self) -> ffi::Cursor {
+        *self
+            .cursor_cache
+            .lock()
+            .unwrap()
+            .entry(None)
+            .or_insert_with(|| self.get_cursor(None))
     }
 
     fn create_empty_cursor(&self) -> ffi::Cursor {
@@ -128,49 +171,97 @@ impl CustomCursor {
         event_loop: &ActiveEventLoop,
         cursor: PlatformCustomCursorSource,
     ) -> CustomCursor {
-        unsafe {
-            let ximage = (event_loop.xconn.xcursor.XcursorImageCreate)(
-                cursor.0.width as i32,
-                cursor.0.height as i32,
-            );
+        let cursors = cursor
+            .variants
+            .iter()
+            .map(|variant| load_cursor(&event_loop.xconn, &variant.frames))
+            .collect();
+
+        Self {
+            inner: Arc::new(CustomCursorInner {
+                xconn: event_loop.xconn.clone(),
+                size: cursor.size,
+                nominal_sizes: cursor
+                    .variants
+                    .iter()
+                    .map(|variant| variant.nominal_size)
+                    .collect(),
+                cursors,
+            }),
+        }
+    }
+
+    /// The cursor of the size fitting the scale factor.
+    fn cursor(&self, scale_factor: f64) -> ffi::Cursor {
+        let index = best_variant(
+            self.inner.size,
+            self.inner.nominal_sizes.iter().copied(),
+            scale_factor,
+        );
+        self.inner.cursors[index]
+    }
+}
+
+/// Load the frames of the cursor at one size.
+fn load_cursor(xconn: &XConnection, frames: &[CustomCursorFrame]) -> ffi::Cursor {
+    let xcursor = &xconn.xcursor;
+    unsafe {
+        let ximages = (xcursor.XcursorImagesCreate)(frames.len() as i32);
+        if ximages.is_null() {
+            panic!("failed to allocate cursor images");
+        }
+
+        for (i, frame) in frames.iter().enumerate() {
+            let image = &frame.image;
+            let ximage = (xcursor.XcursorImageCreate)(image.width as i32, image.height as i32);
             if ximage.is_null() {
+                (xcursor.XcursorImagesDestroy)(ximages);
                 panic!("failed to allocate cursor image");
             }
-            (*ximage).xhot = cursor.0.hotspot_x as u32;
-            (*ximage).yhot = cursor.0.hotspot_y as u32;
-            (*ximage).delay = 0;
+            (*ximage).xhot = image.hotspot_x as u32;
+            (*ximage).yhot = image.hotspot_y as u32;
+            // A delay of zero means a static cursor, thus don't let a frame end up with it.
+            (*ximage).delay = if frames.len() > 1 {
+                (frame.duration.as_millis() as u32).max(1)
+            } else {
+                0
+            };
 
-            let dst = slice::from_raw_parts_mut((*ximage).pixels, cursor.0.rgba.len() / 4);
-            for (dst, chunk) in dst.iter_mut().zip(cursor.0.rgba.chunks_exact(4)) {
+            let dst = slice::from_raw_parts_mut((*ximage).pixels, image.rgba.len() / 4);
+            for (dst, chunk) in dst.iter_mut().zip(image.rgba.chunks_exact(4)) {
                 *dst = (chunk[0] as u32) << 16
                     | (chunk[1] as u32) << 8
                     | (chunk[2] as u32)
                     | (chunk[3] as u32) << 24;
             }
 
-            let cursor =
-                (event_loop.xconn.xcursor.XcursorImageLoadCursor)(event_loop.xconn.display, ximage);
-            (event_loop.xconn.xcursor.XcursorImageDestroy)(ximage);
-            Self {
-                inner: Arc::new(CustomCursorInner {
-                    xconn: event_loop.xconn.clone(),
-                    cursor,
-                }),
-            }
+            // The images are owned by `ximages` and freed along with it.
+            *(*ximages).images.add(i) = ximage;
+            (*ximages).nimage += 1;
         }
+
+        let cursor = (xcursor.XcursorImagesLoadCursor)(xconn.display, ximages);
+        (xcursor.XcursorImagesDestroy)(ximages);
+        cursor
     }
 }
 
 #[derive(Debug)]
 struct CustomCursorInner {
     xconn: Arc<XConnection>,
-    cursor: ffi::Cursor,
+    /// The size the cursor was requested at, before scaling.
+    size: u32,
+    nominal_sizes: Vec<u32>,
+    /// The cursor at each of the nominal sizes.
+    cursors: Vec<ffi::Cursor>,
 }
 
 impl Drop for CustomCursorInner {
     fn drop(&mut self) {
-        unsafe {
-            (self.xconn.xlib.XFreeCursor)(self.xconn.display, self.cursor);
+        for &cursor in &self.cursors {
+            unsafe {
+                (self.xconn.xlib.XFreeCursor)(self.xconn.display, cursor);
+            }
         }
     }
 }
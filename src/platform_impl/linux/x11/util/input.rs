@@ -61,6 +61,29 @@ impl XConnection {
             .map_err(Into::into)
     }
 
+    /// The master pointer the core requests of the connection apply to.
+    pub fn client_pointer(&self) -> Result<xinput::DeviceId, X11Error> {
+        let reply = self
+            .xcb_connection()
+            .xinput_xi_get_client_pointer(x11rb::NONE)?
+            .reply()?;
+
+        // The server picks the first master pointer until one is set.
+        Ok(if reply.set {
+            reply.deviceid
+        } else {
+            VIRTUAL_CORE_POINTER
+        })
+    }
+
+    /// Set the master pointer the core requests of the connection apply to.
+    pub fn set_client_pointer(&self, device_id: xinput::DeviceId) -> Result<(), X11Error> {
+        self.xcb_connection()
+            .xinput_xi_set_client_pointer(x11rb::NONE, device_id)?
+            .check()?;
+        Ok(())
+    }
+
     fn lookup_utf8_inner(
         &self,
         ic: ffi::XIC,
@@ -58,6 +58,11 @@ impl XConnection {
     //    When in doubt, check the X11 source; if a function calls `_XReply`, it flushes and waits.
     // All util functions that abstract an async function will return a `Flusher`.
     pub fn flush_requests(&self) -> Result<(), XError> {
+        // Nothing reaches the server once the connection is lost, which was already reported.
+        if self.is_connection_lost() {
+            return Ok(());
+        }
+
         unsafe { (self.xlib.XFlush)(self.display) };
         //println!("XFlush");
         // This isn't necessarily a useful time to check for errors (since our request hasn't
@@ -1,6 +1,7 @@
 use std::sync::Mutex;
 
 use super::*;
+use crate::platform::backend::BackendCapabilities;
 
 // https://specifications.freedesktop.org/wm-spec/latest/ar01s04.html#idm46075117309248
 pub const MOVERESIZE_TOPLEFT: isize = 0;
@@ -16,6 +17,7 @@ pub const MOVERESIZE_MOVE: isize = 8;
 // This info is global to the window manager.
 static SUPPORTED_HINTS: Mutex<Vec<xproto::Atom>> = Mutex::new(Vec::new());
 static WM_NAME: Mutex<Option<String>> = Mutex::new(None);
+static COMPOSITING_MANAGER: Mutex<bool> = Mutex::new(false);
 
 pub fn hint_is_supported(hint: xproto::Atom) -> bool {
     (*SUPPORTED_HINTS.lock().unwrap()).contains(&hint)
@@ -30,9 +32,100 @@ pub fn wm_name_is_one_of(names: &[&str]) -> bool {
 }
 
 impl XConnection {
+    /// The capabilities depending on the running window manager.
+    pub fn wm_capabilities(&self) -> BackendCapabilities {
+        let atoms = self.atoms();
+        let mut capabilities = BackendCapabilities::WINDOW_POSITION
+            | BackendCapabilities::FRACTIONAL_SCALE
+            | BackendCapabilities::CURSOR_LOCK
+            | BackendCapabilities::CURSOR_CONFINE
+            | BackendCapabilities::RAW_POINTER_MOTION
+            | BackendCapabilities::RESIZE_INCREMENTS
+            | BackendCapabilities::FOREIGN_PARENT;
+        capabilities.set(
+            BackendCapabilities::SERVER_SIDE_DECORATIONS,
+            hint_is_supported(atoms[_NET_FRAME_EXTENTS]),
+        );
+        capabilities.set(
+            BackendCapabilities::ACTIVATION,
+            hint_is_supported(atoms[_NET_ACTIVE_WINDOW]),
+        );
+        capabilities.set(
+            BackendCapabilities::WINDOW_ICON,
+            hint_is_supported(atoms[_NET_WM_ICON]),
+        );
+        capabilities.set(
+            BackendCapabilities::WINDOW_LEVEL,
+            hint_is_supported(atoms[_NET_WM_STATE_ABOVE])
+                && hint_is_supported(atoms[_NET_WM_STATE_BELOW]),
+        );
+        capabilities.set(
+            BackendCapabilities::DRAG_RESIZE,
+            hint_is_supported(atoms[_NET_WM_MOVERESIZE]),
+        );
+        capabilities.set(
+            BackendCapabilities::OPACITY,
+            *COMPOSITING_MANAGER.lock().unwrap(),
+        );
+        capabilities
+    }
+
+    /// The number of desktops, from `_NET_NUMBER_OF_DESKTOPS`.
+    pub fn desktop_count(&self, root: xproto::Window) -> Option<u32> {
+        self.get_cardinal(root, self.atoms()[_NET_NUMBER_OF_DESKTOPS])
+    }
+
+    /// The index of the current desktop, from `_NET_CURRENT_DESKTOP`.
+    pub fn current_desktop(&self, root: xproto::Window) -> Option<u32> {
+        self.get_cardinal(root, self.atoms()[_NET_CURRENT_DESKTOP])
+    }
+
+    /// The names of the desktops, from the null-separated `_NET_DESKTOP_NAMES`.
+    pub fn desktop_names(&self, root: xproto::Window) -> Vec<String> {
+        let atoms = self.atoms();
+        let names = self
+            .get_property::<u8>(root, atoms[_NET_DESKTOP_NAMES], atoms[UTF8_STRING])
+            .unwrap_or_default();
+        let mut names: Vec<String> = names
+            .split(|&byte| byte == 0)
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect();
+        // The last name is terminated as well.
+        if names.last().is_some_and(|name| name.is_empty()) {
+            names.pop();
+        }
+        names
+    }
+
+    /// Get a property holding a single `CARDINAL`.
+    pub fn get_cardinal(&self, window: xproto::Window, property: xproto::Atom) -> Option<u32> {
+        self.get_property::<Cardinal>(
+            window,
+            property,
+            xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+        )
+        .ok()?
+        .first()
+        .copied()
+    }
+
     pub fn update_cached_wm_info(&self, root: xproto::Window) {
         *SUPPORTED_HINTS.lock().unwrap() = self.get_supported_hints(root);
         *WM_NAME.lock().unwrap() = self.get_wm_name(root);
+        *COMPOSITING_MANAGER.lock().unwrap() = self.compositing_manager_is_running();
+    }
+
+    /// Whether a compositing manager owns the `_NET_WM_CM_Sn` selection of the default screen.
+    fn compositing_manager_is_running(&self) -> bool {
+        let selection = match self.cm_selection() {
+            Some(selection) => selection,
+            None => return false,
+        };
+        self.xcb_connection()
+            .get_selection_owner(selection)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .is_some_and(|reply| reply.owner != x11rb::NONE)
     }
 
     fn get_supported_hints(&self, root: xproto::Window) -> Vec<xproto::Atom> {
@@ -1,5 +1,7 @@
 use std::{
-    cmp, env,
+    cmp,
+    collections::HashMap,
+    env,
     ffi::CString,
     mem::replace,
     os::raw::*,
@@ -10,10 +12,11 @@ use std::{
 use log::{debug, info, warn};
 use x11rb::{
     connection::Connection,
-    properties::{WmHints, WmSizeHints, WmSizeHintsSpecification},
+    properties::{AspectRatio, WmHints, WmSizeHints, WmSizeHintsSpecification},
     protocol::{
         randr,
-        shape::SK,
+        screensaver::ConnectionExt as _,
+        shape::{ConnectionExt as _, SK, SO},
         xfixes::{ConnectionExt, RegionWrapper},
         xinput,
         xproto::{self, ConnectionExt as _, Rectangle},
@@ -26,7 +29,11 @@ use crate::{
     error::{ExternalError, NotSupportedError, OsError as RootOsError},
     event::{Event, InnerSizeWriter, WindowEvent},
     event_loop::AsyncRequestSerial,
-    platform::x11::WindowType,
+    platform::{
+        backend::BackendCapabilities,
+        seat::SeatId,
+        x11::{Desktop, NetWmState, WindowType},
+    },
     platform_impl::{
         x11::{
             atoms::*, xinput_fp1616_to_float, MonitorHandle as X11MonitorHandle, WakeSender,
@@ -36,8 +43,8 @@ use crate::{
         PlatformIcon, VideoModeHandle as PlatformVideoModeHandle,
     },
     window::{
-        CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-        WindowButtons, WindowLevel,
+        CursorGrabMode, CursorIcon, ImePurpose, OpaqueRegion, ResizeDirection, Theme,
+        UserAttentionType, WindowAttributes, WindowButtons, WindowLevel, WindowRegion, WindowState,
     },
 };
 
@@ -45,11 +52,14 @@ use super::{
     ffi,
     util::{self, SelectedCursor},
     ActiveEventLoop, CookieResultExt, ImeRequest, ImeSender, VoidCookie, WindowId, XConnection,
+    XResultExt,
 };
 
 #[derive(Debug)]
 pub struct SharedState {
     pub cursor_pos: Option<(f64, f64)>,
+    /// The position the cursor is warped back to while locked.
+    pub cursor_lock_pos: Option<(f64, f64)>,
     pub size: Option<(u32, u32)>,
     pub position: Option<(i32, i32)>,
     pub inner_position: Option<(i32, i32)>,
@@ -74,6 +84,12 @@ pub struct SharedState {
     pub has_focus: bool,
     // Use `Option` to not apply hittest logic when it was never requested.
     pub cursor_hittest: Option<bool>,
+    pub input_region: Option<WindowRegion>,
+    pub shape: Option<WindowRegion>,
+    pub opaque_region: Option<OpaqueRegion>,
+    pub idle_inhibited: bool,
+    // The last state reported with `WindowEvent::StateChanged`.
+    pub window_state: WindowState,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -99,6 +115,7 @@ impl SharedState {
             is_resizable: window_attributes.resizable,
             is_decorated: window_attributes.decorations,
             cursor_pos: None,
+            cursor_lock_pos: None,
             size: None,
             position: None,
             inner_position: None,
@@ -115,6 +132,11 @@ impl SharedState {
             base_size: None,
             has_focus: false,
             cursor_hittest: None,
+            input_region: None,
+            shape: None,
+            opaque_region: None,
+            idle_inhibited: false,
+            window_state: WindowState::empty(),
         })
     }
 }
@@ -134,10 +156,16 @@ pub struct UnownedWindow {
     cursor_grabbed_mode: Mutex<CursorGrabMode>,
     #[allow(clippy::mutex_atomic)]
     cursor_visible: Mutex<bool>,
+    /// The cursors set for the master pointers of the seats.
+    seat_cursors: Mutex<HashMap<SeatId, CursorIcon>>,
+    /// The grabs set for the master pointers of the seats.
+    seat_grabs: Mutex<HashMap<SeatId, CursorGrabMode>>,
     ime_sender: Mutex<ImeSender>,
+    has_ime: bool, // never changes
     pub shared_state: Mutex<SharedState>,
     redraw_sender: WakeSender<WindowId>,
     activation_sender: WakeSender<super::ActivationToken>,
+    export_sender: WakeSender<super::ExportRequest>,
 }
 
 macro_rules! leap {
@@ -369,10 +397,14 @@ impl UnownedWindow {
             selected_cursor: Default::default(),
             cursor_grabbed_mode: Mutex::new(CursorGrabMode::None),
             cursor_visible: Mutex::new(true),
+            seat_cursors: Default::default(),
+            seat_grabs: Default::default(),
             ime_sender: Mutex::new(event_loop.ime_sender.clone()),
+            has_ime: event_loop.ime.is_some(),
             shared_state: SharedState::new(guessed_monitor, &window_attrs),
             redraw_sender: event_loop.redraw_sender.clone(),
             activation_sender: event_loop.activation_sender.clone(),
+            export_sender: event_loop.export_sender.clone(),
         };
 
         // Title must be set before mapping. Some tiling window managers (i.e. i3) use the window
@@ -440,8 +472,29 @@ impl UnownedWindow {
                 flusher.ignore_error()
             }
 
-            leap!(window.set_window_types(window_attrs.platform_specific.x11.x11_window_types))
-                .ignore_error();
+            // A dialog is expected to be owned, so make the owned windows dialogs by default.
+            let mut window_types = window_attrs.platform_specific.x11.x11_window_types;
+            if window_attrs.owner.is_some() && window_types == [WindowType::Normal] {
+                window_types = vec![WindowType::Dialog];
+            }
+            leap!(window.set_window_types(window_types)).ignore_error();
+
+            if let Some(owner) = window_attrs.owner {
+                leap!(window.set_transient_for_inner(owner.0 .0 as xproto::Window)).ignore_error();
+            }
+
+            // The handle of a foreign window is its XID.
+            if let Some(handle) = &window_attrs.platform_specific.foreign_parent {
+                match handle.as_raw().parse() {
+                    Ok(parent) => leap!(window.set_transient_for_inner(parent)).ignore_error(),
+                    Err(_) => warn!("Ignoring the invalid foreign window handle {:?}", handle),
+                }
+            }
+
+            // The window isn't mapped yet, so the property is read by the window manager.
+            if let Some(desktop) = window_attrs.platform_specific.x11.desktop {
+                leap!(window.set_desktop_property(desktop)).ignore_error();
+            }
 
             // Set size hints.
             let mut min_inner_size = window_attrs
@@ -485,7 +538,7 @@ impl UnownedWindow {
                     .x11
                     .base_size
                     .map(|size| cast_size_to_hint(size, scale_factor)),
-                aspect: None,
+                aspect: window_attrs.aspect_ratio.and_then(aspect_ratio_to_hint),
                 win_gravity: None,
             };
             leap!(leap!(normal_hints.set(
@@ -582,6 +635,18 @@ impl UnownedWindow {
             }
 
             leap!(window.set_window_level_inner(window_attrs.window_level)).ignore_error();
+
+            let mut net_wm_state = window_attrs.platform_specific.x11.net_wm_state;
+            if window_attrs.owner.is_some() && window_attrs.modal {
+                net_wm_state |= NetWmState::MODAL;
+            }
+            if !net_wm_state.is_empty() {
+                leap!(window.set_net_wm_state_inner(net_wm_state, true));
+            }
+
+            if window_attrs.opacity < 1.0 {
+                leap!(window.set_opacity_inner(window_attrs.opacity)).ignore_error();
+            }
         }
 
         window.set_cursor(window_attrs.cursor);
@@ -644,6 +709,16 @@ impl UnownedWindow {
         flusher.map(Some)
     }
 
+    fn set_transient_for_inner(&self, parent: xproto::Window) -> Result<VoidCookie<'_>, X11Error> {
+        self.xconn.change_property(
+            self.xwindow,
+            xproto::Atom::from(xproto::AtomEnum::WM_TRANSIENT_FOR),
+            xproto::Atom::from(xproto::AtomEnum::WINDOW),
+            xproto::PropMode::REPLACE,
+            &[parent],
+        )
+    }
+
     fn set_window_types(&self, window_types: Vec<WindowType>) -> Result<VoidCookie<'_>, X11Error> {
         let atoms = self.xconn.atoms();
         let hint_atom = atoms[_NET_WM_WINDOW_TYPE];
@@ -683,8 +758,7 @@ impl UnownedWindow {
     #[inline]
     pub fn set_theme(&self, theme: Option<Theme>) {
         self.set_theme_inner(theme)
-            .expect("Failed to change window theme")
-            .ignore_error();
+            .expect_then_ignore_error("Failed to change window theme");
 
         self.xconn
             .flush_requests()
@@ -766,20 +840,14 @@ impl UnownedWindow {
                 &Some(Fullscreen::Exclusive(PlatformVideoModeHandle::X(ref video_mode))),
             ) => {
                 let monitor = video_mode.monitor.as_ref().unwrap();
-                shared_state_lock.desktop_video_mode = Some((
-                    monitor.id,
-                    self.xconn
-                        .get_crtc_mode(monitor.id)
-                        .expect("Failed to get desktop video mode"),
-                ));
+                shared_state_lock.desktop_video_mode =
+                    Some((monitor.id, self.xconn.get_crtc_mode(monitor.id)?));
             }
             // Restore desktop video mode upon exiting exclusive fullscreen
             (&Some(Fullscreen::Exclusive(_)), &None)
             | (&Some(Fullscreen::Exclusive(_)), &Some(Fullscreen::Borderless(_))) => {
                 let (monitor_id, mode_id) = shared_state_lock.desktop_video_mode.take().unwrap();
-                self.xconn
-                    .set_crtc_config(monitor_id, mode_id)
-                    .expect("failed to restore desktop video mode");
+                self.xconn.set_crtc_config(monitor_id, mode_id)?;
             }
             _ => (),
         }
@@ -844,8 +912,7 @@ impl UnownedWindow {
                     // this will make someone unhappy, but it's very unusual for
                     // games to want to do this anyway).
                     self.xconn
-                        .set_crtc_config(monitor.id, video_mode.native_mode)
-                        .expect("failed to set video mode");
+                        .set_crtc_config(monitor.id, video_mode.native_mode)?;
                 }
 
                 let window_position = self.outer_position_physical();
@@ -872,11 +939,12 @@ impl UnownedWindow {
     pub(crate) fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
         if let Some(flusher) = self
             .set_fullscreen_inner(fullscreen)
-            .expect("Failed to change window fullscreen state")
+            .expect_or_lost(&self.xconn, "Failed to change window fullscreen state")
+            .flatten()
         {
             flusher
                 .check()
-                .expect("Failed to change window fullscreen state");
+                .expect_or_lost(&self.xconn, "Failed to change window fullscreen state");
             self.invalidate_cached_frame_extents();
         }
     }
@@ -910,15 +978,14 @@ impl UnownedWindow {
     pub fn available_monitors(&self) -> Vec<X11MonitorHandle> {
         self.xconn
             .available_monitors()
-            .expect("Failed to get available monitors")
+            .expect_or_lost(&self.xconn, "Failed to get available monitors")
+            .unwrap_or_default()
     }
 
     pub fn primary_monitor(&self) -> Option<X11MonitorHandle> {
-        Some(
-            self.xconn
-                .primary_monitor()
-                .expect("Failed to get primary monitor"),
-        )
+        self.xconn
+            .primary_monitor()
+            .expect_or_lost(&self.xconn, "Failed to get primary monitor")
     }
 
     #[inline]
@@ -940,6 +1007,37 @@ impl UnownedWindow {
         })
     }
 
+    // Called by EventProcessor when the _NET_WM_STATE property changed, returns the new state
+    // when it differs from the last one reported.
+    pub(crate) fn net_wm_state_changed(&self) -> Option<WindowState> {
+        let atoms = self.xconn.atoms();
+        let net_wm_state = self
+            .xconn
+            .get_property::<xproto::Atom>(
+                self.xwindow,
+                atoms[_NET_WM_STATE],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default();
+        let has_atom = |atom| net_wm_state.contains(&atoms[atom]);
+
+        let mut state = WindowState::empty();
+        state.set(
+            WindowState::MAXIMIZED,
+            has_atom(_NET_WM_STATE_MAXIMIZED_HORZ) && has_atom(_NET_WM_STATE_MAXIMIZED_VERT),
+        );
+        state.set(WindowState::MINIMIZED, has_atom(_NET_WM_STATE_HIDDEN));
+        state.set(WindowState::FULLSCREEN, has_atom(_NET_WM_STATE_FULLSCREEN));
+
+        let mut shared_state = self.shared_state_lock();
+        if shared_state.window_state == state {
+            return None;
+        }
+
+        shared_state.window_state = state;
+        Some(state)
+    }
+
     /// Refresh the API for the given monitor.
     #[inline]
     pub(super) fn refresh_dpi_for_monitor<T: 'static>(
@@ -982,6 +1080,10 @@ impl UnownedWindow {
                 let (new_width, new_height) = new_inner_size.into();
                 self.request_inner_size_physical(new_width, new_height);
             }
+
+            self.reload_custom_cursor();
+            self.reload_shape_regions();
+            self.reload_opaque_region();
         }
     }
 
@@ -1155,6 +1257,237 @@ impl UnownedWindow {
             .expect("Failed to set window-level state");
     }
 
+    #[inline]
+    pub fn desktop(&self) -> Option<Desktop> {
+        let atoms = self.xconn.atoms();
+        match self
+            .xconn
+            .get_cardinal(self.xwindow, atoms[_NET_WM_DESKTOP])?
+        {
+            ALL_DESKTOPS => Some(Desktop::All),
+            index => Some(Desktop::Index(index)),
+        }
+    }
+
+    fn set_desktop_property(&self, desktop: Desktop) -> Result<VoidCookie<'_>, X11Error> {
+        let atoms = self.xconn.atoms();
+        self.xconn.change_property(
+            self.xwindow,
+            atoms[_NET_WM_DESKTOP],
+            xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+            xproto::PropMode::REPLACE,
+            &[desktop_to_cardinal(desktop)],
+        )
+    }
+
+    #[inline]
+    pub fn set_desktop(&self, desktop: Desktop) {
+        // Only a withdrawn window can set the property itself, the window manager moves the others.
+        if self.shared_state_lock().visibility == Visibility::No {
+            self.set_desktop_property(desktop)
+        } else {
+            let atoms = self.xconn.atoms();
+            self.xconn.send_client_msg(
+                self.xwindow,
+                self.root,
+                atoms[_NET_WM_DESKTOP],
+                Some(
+                    xproto::EventMask::SUBSTRUCTURE_REDIRECT
+                        | xproto::EventMask::SUBSTRUCTURE_NOTIFY,
+                ),
+                [desktop_to_cardinal(desktop), 1, 0, 0, 0],
+            )
+        }
+        .expect_then_ignore_error("Failed to set window desktop");
+        self.xconn
+            .flush_requests()
+            .expect("Failed to set window desktop");
+    }
+
+    #[inline]
+    pub fn net_wm_state(&self) -> NetWmState {
+        let atoms = self.xconn.atoms();
+        let net_wm_state = self
+            .xconn
+            .get_property::<xproto::Atom>(
+                self.xwindow,
+                atoms[_NET_WM_STATE],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default();
+
+        NET_WM_STATES
+            .iter()
+            .filter(|(_, atom)| net_wm_state.contains(&atoms[*atom]))
+            .fold(NetWmState::empty(), |state, (flag, _)| state | *flag)
+    }
+
+    fn set_net_wm_state_inner(&self, state: NetWmState, enabled: bool) -> Result<(), X11Error> {
+        let atoms = self.xconn.atoms();
+        let state_atoms: Vec<xproto::Atom> = NET_WM_STATES
+            .iter()
+            .filter(|(flag, _)| state.contains(*flag))
+            .map(|(_, atom)| atoms[*atom])
+            .collect();
+
+        // Only a withdrawn window can set the property itself, the window manager changes it for
+        // the others.
+        if self.shared_state_lock().visibility != Visibility::No {
+            for atoms in state_atoms.chunks(2) {
+                let second = atoms.get(1).copied().unwrap_or(0);
+                self.set_netwm(enabled.into(), (atoms[0], second, 0, 0))?
+                    .ignore_error();
+            }
+            return Ok(());
+        }
+
+        let mut net_wm_state = self
+            .xconn
+            .get_property::<xproto::Atom>(
+                self.xwindow,
+                atoms[_NET_WM_STATE],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+            )
+            .unwrap_or_default();
+        net_wm_state.retain(|atom| !state_atoms.contains(atom));
+        if enabled {
+            net_wm_state.extend(state_atoms);
+        }
+        self.xconn
+            .change_property(
+                self.xwindow,
+                atoms[_NET_WM_STATE],
+                xproto::Atom::from(xproto::AtomEnum::ATOM),
+                xproto::PropMode::REPLACE,
+                &net_wm_state,
+            )?
+            .ignore_error();
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_net_wm_state(&self, state: NetWmState, enabled: bool) {
+        self.set_net_wm_state_inner(state, enabled)
+            .expect_or_lost(&self.xconn, "Failed to set _NET_WM_STATE hints");
+        self.xconn
+            .flush_requests()
+            .expect("Failed to set _NET_WM_STATE hints");
+    }
+
+    fn set_opacity_inner(&self, opacity: f32) -> Result<VoidCookie<'_>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let opacity_atom = atoms[_NET_WM_WINDOW_OPACITY];
+        if opacity >= 1.0 {
+            return self
+                .xconn
+                .xcb_connection()
+                .delete_property(self.xwindow, opacity_atom)
+                .map_err(Into::into);
+        }
+
+        let opacity = (opacity.clamp(0.0, 1.0) as f64 * u32::MAX as f64).round() as u32;
+        self.xconn.change_property(
+            self.xwindow,
+            opacity_atom,
+            xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+            xproto::PropMode::REPLACE,
+            &[opacity],
+        )
+    }
+
+    #[inline]
+    pub fn set_opacity(&self, opacity: f32) {
+        self.set_opacity_inner(opacity)
+            .expect_then_ignore_error("Failed to set window opacity");
+        self.xconn
+            .flush_requests()
+            .expect("Failed to set window opacity");
+    }
+
+    fn set_opaque_region_inner(
+        &self,
+        region: Option<&OpaqueRegion>,
+    ) -> Result<VoidCookie<'_>, X11Error> {
+        let atoms = self.xconn.atoms();
+        let opaque_region_atom = atoms[_NET_WM_OPAQUE_REGION];
+        let region = match region {
+            Some(region) => region,
+            None => {
+                return self
+                    .xconn
+                    .xcb_connection()
+                    .delete_property(self.xwindow, opaque_region_atom)
+                    .map_err(Into::into)
+            }
+        };
+
+        let scale_factor = self.scale_factor();
+        let size = self.inner_size().to_logical(scale_factor);
+        let data: Vec<util::Cardinal> = region
+            .to_region(size)
+            .to_physical(scale_factor)
+            .flat_map(|(position, size)| {
+                [
+                    position.x.max(0) as util::Cardinal,
+                    position.y.max(0) as util::Cardinal,
+                    size.width,
+                    size.height,
+                ]
+            })
+            .collect();
+        self.xconn.change_property(
+            self.xwindow,
+            opaque_region_atom,
+            xproto::Atom::from(xproto::AtomEnum::CARDINAL),
+            xproto::PropMode::REPLACE,
+            &data,
+        )
+    }
+
+    #[inline]
+    pub fn set_opaque_region(&self, region: Option<OpaqueRegion>) {
+        self.set_opaque_region_inner(region.as_ref())
+            .expect_then_ignore_error("Failed to set opaque region");
+        self.xconn
+            .flush_requests()
+            .expect("Failed to set opaque region");
+        self.shared_state_lock().opaque_region = region;
+    }
+
+    #[inline]
+    pub fn set_idle_inhibited(&self, inhibited: bool) {
+        let mut shared_state = self.shared_state_lock();
+        // The suspension is counted for the whole client, so only the changes of this window are
+        // sent for the window to release its own suspension when destroyed.
+        if shared_state.idle_inhibited == inhibited {
+            return;
+        }
+
+        match self
+            .xconn
+            .xcb_connection()
+            .screensaver_suspend(inhibited.into())
+        {
+            Ok(cookie) => {
+                cookie.ignore_error();
+                shared_state.idle_inhibited = inhibited;
+            }
+            Err(err) => warn!("Failed to suspend the screensaver: {}", err),
+        }
+
+        self.xconn
+            .flush_requests()
+            .expect("Failed to suspend the screensaver");
+    }
+
+    /// Recompute the opaque region after the window was resized.
+    pub(crate) fn reload_opaque_region(&self) {
+        let region = self.shared_state_lock().opaque_region.clone();
+        if region.is_some() {
+            self.set_opaque_region(region);
+        }
+    }
+
     fn set_icon_inner(&self, icon: PlatformIcon) -> Result<VoidCookie<'_>, X11Error> {
         let atoms = self.xconn.atoms();
         let icon_atom = atoms[_NET_WM_ICON];
@@ -1355,9 +1688,9 @@ impl UnownedWindow {
                     .height(height),
             )
             .expect_then_ignore_error("Failed to call `xcb_configure_window`");
-        self.xconn
-            .flush_requests()
-            .expect("Failed to call XResizeWindow");
+        if let Err(err) = self.xconn.flush_requests() {
+            log::error!("Failed to call XResizeWindow: {err}");
+        }
         // cursor_hittest needs to be reapplied after each window resize.
         if self.shared_state_lock().cursor_hittest.unwrap_or(false) {
             let _ = self.set_cursor_hittest(true);
@@ -1373,7 +1706,7 @@ impl UnownedWindow {
                 normal_hints.min_size = Some(size);
                 normal_hints.max_size = Some(size);
             })
-            .expect("Failed to call `XSetWMNormalHints`");
+            .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
         }
         self.request_inner_size_physical(size.0 as u32, size.1 as u32);
 
@@ -1407,7 +1740,7 @@ impl UnownedWindow {
             normal_hints.min_size =
                 dimensions.map(|(w, h)| (cast_dimension_to_hint(w), cast_dimension_to_hint(h)))
         })
-        .expect("Failed to call `XSetWMNormalHints`");
+        .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
     }
 
     #[inline]
@@ -1423,7 +1756,7 @@ impl UnownedWindow {
             normal_hints.max_size =
                 dimensions.map(|(w, h)| (cast_dimension_to_hint(w), cast_dimension_to_hint(h)))
         })
-        .expect("Failed to call `XSetWMNormalHints`");
+        .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
     }
 
     #[inline]
@@ -1454,7 +1787,14 @@ impl UnownedWindow {
         let physical_increments =
             increments.map(|increments| cast_size_to_hint(increments, self.scale_factor()));
         self.update_normal_hints(|hints| hints.size_increment = physical_increments)
-            .expect("Failed to call `XSetWMNormalHints`");
+            .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
+    }
+
+    #[inline]
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        let aspect = aspect_ratio.and_then(aspect_ratio_to_hint);
+        self.update_normal_hints(|hints| hints.aspect = aspect)
+            .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
     }
 
     pub(crate) fn adjust_for_dpi(
@@ -1478,7 +1818,7 @@ impl UnownedWindow {
             normal_hints.size_increment = resize_increments;
             normal_hints.base_size = base_size;
         })
-        .expect("Failed to update normal hints");
+        .expect_or_lost(&self.xconn, "Failed to update normal hints");
 
         let new_width = (width as f64 * scale_factor).round() as u32;
         let new_height = (height as f64 * scale_factor).round() as u32;
@@ -1517,7 +1857,7 @@ impl UnownedWindow {
             normal_hints.min_size = min_inner_size;
             normal_hints.max_size = max_inner_size;
         })
-        .expect("Failed to call `XSetWMNormalHints`");
+        .expect_or_lost(&self.xconn, "Failed to call `XSetWMNormalHints`");
     }
 
     #[inline]
@@ -1557,7 +1897,9 @@ impl UnownedWindow {
                 #[allow(clippy::mutex_atomic)]
                 if SelectedCursor::Named(icon) != old_cursor && *self.cursor_visible.lock().unwrap()
                 {
-                    self.xconn.set_cursor_icon(self.xwindow, Some(icon));
+                    if let Err(err) = self.xconn.set_cursor_icon(self.xwindow, Some(icon)) {
+                        log::error!("Failed to set cursor: {err}");
+                    }
                 }
             }
             Cursor::Custom(RootCustomCursor {
@@ -1565,7 +1907,12 @@ impl UnownedWindow {
             }) => {
                 #[allow(clippy::mutex_atomic)]
                 if *self.cursor_visible.lock().unwrap() {
-                    self.xconn.set_custom_cursor(self.xwindow, &cursor);
+                    if let Err(err) =
+                        self.xconn
+                            .set_custom_cursor(self.xwindow, &cursor, self.scale_factor())
+                    {
+                        log::error!("Failed to set cursor: {err}");
+                    }
                 }
 
                 *self.selected_cursor.lock().unwrap() = SelectedCursor::Custom(cursor);
@@ -1577,6 +1924,21 @@ impl UnownedWindow {
         }
     }
 
+    #[inline]
+    pub fn set_seat_cursor(&self, seat: SeatId, cursor: CursorIcon) {
+        self.seat_cursors.lock().unwrap().insert(seat, cursor);
+
+        #[allow(clippy::mutex_atomic)]
+        if *self.cursor_visible.lock().unwrap() {
+            if let Err(err) =
+                self.xconn
+                    .set_device_cursor_icon(self.xwindow, seat.0 as _, Some(cursor))
+            {
+                log::error!("Failed to set cursor: {err}");
+            }
+        }
+    }
+
     #[inline]
     pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
         let mut grabbed_lock = self.cursor_grabbed_mode.lock().unwrap();
@@ -1584,75 +1946,163 @@ impl UnownedWindow {
             return Ok(());
         }
 
+        let result = self.grab_pointer(mode);
+        if result.is_ok() {
+            *grabbed_lock = mode;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn set_seat_cursor_grab(
+        &self,
+        seat: SeatId,
+        mode: CursorGrabMode,
+    ) -> Result<(), ExternalError> {
+        // The lock warps the cursor of the client pointer back, which isn't done per seat.
+        if mode == CursorGrabMode::Locked {
+            return Err(ExternalError::NotSupported(NotSupportedError::new()));
+        }
+
+        // The grab of the seat would replace the one locking the cursor of the window.
+        if *self.cursor_grabbed_mode.lock().unwrap() == CursorGrabMode::Locked {
+            return Err(ExternalError::Os(os_error!(OsError::Misc(
+                "Cursor could not be grabbed: the cursor of the window is locked"
+            ))));
+        }
+
+        // The core grabs apply to the client pointer, thus it's switched to the master pointer of
+        // the seat for the time of the grab.
+        let client_pointer = self
+            .xconn
+            .client_pointer()
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+        self.xconn
+            .set_client_pointer(seat.0 as _)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+
+        let result = self.grab_pointer(mode);
+
+        self.xconn
+            .set_client_pointer(client_pointer)
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+
+        if result.is_ok() {
+            self.seat_grabs.lock().unwrap().insert(seat, mode);
+        }
+
+        result
+    }
+
+    /// Reset the cursor and the cursor grab of the seat to the ones of the window.
+    pub fn reset_seat(&self, seat: SeatId) {
+        if self.seat_cursors.lock().unwrap().remove(&seat).is_some() {
+            if let Err(err) = self
+                .xconn
+                .set_device_cursor_icon(self.xwindow, seat.0 as _, None)
+            {
+                log::error!("Failed to set cursor: {err}");
+            }
+        }
+
+        let seat_grab = self.seat_grabs.lock().unwrap().remove(&seat);
+        if seat_grab.is_some_and(|mode| mode != CursorGrabMode::None) {
+            if let Err(err) = self.set_seat_cursor_grab(seat, CursorGrabMode::None) {
+                log::error!("Failed to release the cursor grab of the seat: {err}");
+            }
+            self.seat_grabs.lock().unwrap().remove(&seat);
+
+            // The grab of the window is released when the seat owns the client pointer.
+            let mode = *self.cursor_grabbed_mode.lock().unwrap();
+            if mode != CursorGrabMode::None {
+                if let Err(err) = self.grab_pointer(mode) {
+                    log::error!("Failed to restore the cursor grab: {err}");
+                }
+            }
+        }
+    }
+
+    /// Grab the client pointer.
+    fn grab_pointer(&self, mode: CursorGrabMode) -> Result<(), ExternalError> {
         // We ungrab before grabbing to prevent passive grabs from causing `AlreadyGrabbed`.
-        // Therefore, this is common to both codepaths.
+        // Therefore, this is common to all codepaths.
         self.xconn
             .xcb_connection()
             .ungrab_pointer(x11rb::CURRENT_TIME)
             .expect_then_ignore_error("Failed to call `xcb_ungrab_pointer`");
+        self.shared_state_lock().cursor_lock_pos = None;
 
-        let result = match mode {
-            CursorGrabMode::None => self.xconn.flush_requests().map_err(|err| {
+        if mode == CursorGrabMode::None {
+            return self.xconn.flush_requests().map_err(|err| {
                 ExternalError::Os(os_error!(OsError::XError(X11Error::Xlib(err).into())))
-            }),
-            CursorGrabMode::Confined => {
-                let result = {
-                    self.xconn
-                        .xcb_connection()
-                        .grab_pointer(
-                            true as _,
-                            self.xwindow,
-                            xproto::EventMask::BUTTON_PRESS
-                                | xproto::EventMask::BUTTON_RELEASE
-                                | xproto::EventMask::ENTER_WINDOW
-                                | xproto::EventMask::LEAVE_WINDOW
-                                | xproto::EventMask::POINTER_MOTION
-                                | xproto::EventMask::POINTER_MOTION_HINT
-                                | xproto::EventMask::BUTTON1_MOTION
-                                | xproto::EventMask::BUTTON2_MOTION
-                                | xproto::EventMask::BUTTON3_MOTION
-                                | xproto::EventMask::BUTTON4_MOTION
-                                | xproto::EventMask::BUTTON5_MOTION
-                                | xproto::EventMask::KEYMAP_STATE,
-                            xproto::GrabMode::ASYNC,
-                            xproto::GrabMode::ASYNC,
-                            self.xwindow,
-                            0u32,
-                            x11rb::CURRENT_TIME,
-                        )
-                        .expect("Failed to call `grab_pointer`")
-                        .reply()
-                        .expect("Failed to receive reply from `grab_pointer`")
-                };
+            });
+        }
 
-                match result.status {
-                    xproto::GrabStatus::SUCCESS => Ok(()),
-                    xproto::GrabStatus::ALREADY_GRABBED => {
-                        Err("Cursor could not be confined: already confined by another client")
-                    }
-                    xproto::GrabStatus::INVALID_TIME => {
-                        Err("Cursor could not be confined: invalid time")
-                    }
-                    xproto::GrabStatus::NOT_VIEWABLE => {
-                        Err("Cursor could not be confined: confine location not viewable")
-                    }
-                    xproto::GrabStatus::FROZEN => {
-                        Err("Cursor could not be confined: frozen by another client")
-                    }
-                    _ => unreachable!(),
-                }
-                .map_err(|err| ExternalError::Os(os_error!(OsError::Misc(err))))
+        // X11 can't lock the cursor, thus the locked cursor is confined and warped back to the
+        // lock position on each motion, hidden so the warps aren't seen.
+        let cursor = match mode {
+            CursorGrabMode::Locked => self.xconn.empty_cursor(),
+            _ => x11rb::NONE as ffi::Cursor,
+        };
+
+        let result = self
+            .xconn
+            .xcb_connection()
+            .grab_pointer(
+                true as _,
+                self.xwindow,
+                xproto::EventMask::BUTTON_PRESS
+                    | xproto::EventMask::BUTTON_RELEASE
+                    | xproto::EventMask::ENTER_WINDOW
+                    | xproto::EventMask::LEAVE_WINDOW
+                    | xproto::EventMask::POINTER_MOTION
+                    | xproto::EventMask::POINTER_MOTION_HINT
+                    | xproto::EventMask::BUTTON1_MOTION
+                    | xproto::EventMask::BUTTON2_MOTION
+                    | xproto::EventMask::BUTTON3_MOTION
+                    | xproto::EventMask::BUTTON4_MOTION
+                    | xproto::EventMask::BUTTON5_MOTION
+                    | xproto::EventMask::KEYMAP_STATE,
+                xproto::GrabMode::ASYNC,
+                xproto::GrabMode::ASYNC,
+                self.xwindow,
+                cursor as xproto::Cursor,
+                x11rb::CURRENT_TIME,
+            )
+            .map_err(X11Error::from)
+            .and_then(|cookie| cookie.reply().map_err(X11Error::from))
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+
+        match result.status {
+            xproto::GrabStatus::SUCCESS => Ok(()),
+            xproto::GrabStatus::ALREADY_GRABBED => {
+                Err("Cursor could not be grabbed: already grabbed by another client")
             }
-            CursorGrabMode::Locked => {
-                return Err(ExternalError::NotSupported(NotSupportedError::new()));
+            xproto::GrabStatus::INVALID_TIME => Err("Cursor could not be grabbed: invalid time"),
+            xproto::GrabStatus::NOT_VIEWABLE => {
+                Err("Cursor could not be grabbed: confine location not viewable")
             }
-        };
+            xproto::GrabStatus::FROZEN => {
+                Err("Cursor could not be grabbed: frozen by another client")
+            }
+            _ => unreachable!(),
+        }
+        .map_err(|err| ExternalError::Os(os_error!(OsError::Misc(err))))?;
 
-        if result.is_ok() {
-            *grabbed_lock = mode;
+        if mode == CursorGrabMode::Locked {
+            let pointer = self
+                .xconn
+                .xcb_connection()
+                .query_pointer(self.xwindow)
+                .map_err(X11Error::from)
+                .and_then(|cookie| cookie.reply().map_err(X11Error::from))
+                .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+            self.shared_state_lock().cursor_lock_pos =
+                Some((pointer.win_x as f64, pointer.win_y as f64));
         }
 
-        result
+        Ok(())
     }
 
     #[inline]
@@ -1669,15 +2119,46 @@ impl UnownedWindow {
         };
         *visible_lock = visible;
         drop(visible_lock);
-        match cursor {
+
+        // The cursors of the seats fall back to the one of the window when hidden.
+        for (seat, &cursor) in self.seat_cursors.lock().unwrap().iter() {
+            let cursor = visible.then_some(cursor);
+            if let Err(err) = self
+                .xconn
+                .set_device_cursor_icon(self.xwindow, seat.0 as _, cursor)
+            {
+                log::error!("Failed to set cursor: {err}");
+            }
+        }
+
+        let result = match cursor {
             Some(SelectedCursor::Custom(cursor)) => {
-                self.xconn.set_custom_cursor(self.xwindow, &cursor);
+                self.xconn
+                    .set_custom_cursor(self.xwindow, &cursor, self.scale_factor())
             }
             Some(SelectedCursor::Named(cursor)) => {
-                self.xconn.set_cursor_icon(self.xwindow, Some(cursor));
+                self.xconn.set_cursor_icon(self.xwindow, Some(cursor))
             }
-            None => {
-                self.xconn.set_cursor_icon(self.xwindow, None);
+            None => self.xconn.set_cursor_icon(self.xwindow, None),
+        };
+        if let Err(err) = result {
+            log::error!("Failed to set cursor: {err}");
+        }
+    }
+
+    /// Show the size of the custom cursor fitting the current scale factor.
+    pub(crate) fn reload_custom_cursor(&self) {
+        #[allow(clippy::mutex_atomic)]
+        if !*self.cursor_visible.lock().unwrap() {
+            return;
+        }
+
+        if let SelectedCursor::Custom(cursor) = &*self.selected_cursor.lock().unwrap() {
+            if let Err(err) =
+                self.xconn
+                    .set_custom_cursor(self.xwindow, cursor, self.scale_factor())
+            {
+                log::error!("Failed to set cursor: {err}");
             }
         }
     }
@@ -1704,6 +2185,14 @@ impl UnownedWindow {
     #[inline]
     pub fn set_cursor_position(&self, position: Position) -> Result<(), ExternalError> {
         let (x, y) = position.to_physical::<i32>(self.scale_factor()).into();
+
+        // Move the lock position along, otherwise the cursor would be warped back.
+        let mut shared_state_lock = self.shared_state_lock();
+        if shared_state_lock.cursor_lock_pos.is_some() {
+            shared_state_lock.cursor_lock_pos = Some((x as f64, y as f64));
+        }
+        drop(shared_state_lock);
+
         self.set_cursor_position_physical(x, y)
     }
 
@@ -1725,10 +2214,84 @@ impl UnownedWindow {
             .xcb_connection()
             .xfixes_set_window_shape_region(self.xwindow, SK::INPUT, 0, 0, region.region())
             .map_err(|_e| ExternalError::Ignored)?;
-        self.shared_state_lock().cursor_hittest = Some(hittest);
+        let mut shared_state = self.shared_state_lock();
+        shared_state.cursor_hittest = Some(hittest);
+        shared_state.input_region = None;
         Ok(())
     }
 
+    /// Set the shape of the given kind, `None` resetting it to the whole window.
+    fn set_shape_region(
+        &self,
+        kind: SK,
+        region: Option<&WindowRegion>,
+    ) -> Result<VoidCookie<'_>, X11Error> {
+        let xcb = self.xconn.xcb_connection();
+        let cookie = match region {
+            Some(region) => {
+                let rectangles: Vec<Rectangle> = region
+                    .to_physical(self.scale_factor())
+                    .map(|(position, size)| Rectangle {
+                        x: position.x as i16,
+                        y: position.y as i16,
+                        width: size.width as u16,
+                        height: size.height as u16,
+                    })
+                    .collect();
+                xcb.shape_rectangles(
+                    SO::SET,
+                    kind,
+                    xproto::ClipOrdering::UNSORTED,
+                    self.xwindow,
+                    0,
+                    0,
+                    &rectangles,
+                )?
+            }
+            None => xcb.shape_mask(SO::SET, kind, self.xwindow, 0, 0, x11rb::NONE)?,
+        };
+        Ok(cookie)
+    }
+
+    #[inline]
+    pub fn set_input_region(&self, region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.set_shape_region(SK::INPUT, region.as_ref())
+            .and_then(|cookie| Ok(cookie.check()?))
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+        let mut shared_state = self.shared_state_lock();
+        shared_state.cursor_hittest = None;
+        shared_state.input_region = region;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn set_shape(&self, shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.set_shape_region(SK::BOUNDING, shape.as_ref())
+            .and_then(|cookie| Ok(cookie.check()?))
+            .map_err(|err| ExternalError::Os(os_error!(OsError::XError(err.into()))))?;
+        self.shared_state_lock().shape = shape;
+        Ok(())
+    }
+
+    /// Reapply the regions given in logical units after a scale factor change.
+    pub(crate) fn reload_shape_regions(&self) {
+        let (input_region, shape) = {
+            let shared_state = self.shared_state_lock();
+            (
+                shared_state.input_region.clone(),
+                shared_state.shape.clone(),
+            )
+        };
+        if let Some(region) = input_region {
+            self.set_shape_region(SK::INPUT, Some(&region))
+                .expect_then_ignore_error("Failed to set input region");
+        }
+        if let Some(shape) = shape {
+            self.set_shape_region(SK::BOUNDING, Some(&shape))
+                .expect_then_ignore_error("Failed to set window shape");
+        }
+    }
+
     /// Moves the window while it is being dragged.
     pub fn drag_window(&self) -> Result<(), ExternalError> {
         self.drag_initiate(util::MOVERESIZE_MOVE)
@@ -1737,6 +2300,13 @@ impl UnownedWindow {
     #[inline]
     pub fn show_window_menu(&self, _position: Position) {}
 
+    #[inline]
+    pub fn capabilities(&self) -> BackendCapabilities {
+        let mut capabilities = self.xconn.wm_capabilities();
+        capabilities.set(BackendCapabilities::IME, self.has_ime);
+        capabilities
+    }
+
     /// Resizes the window while it is being dragged.
     pub fn drag_resize_window(&self, direction: ResizeDirection) -> Result<(), ExternalError> {
         self.drag_initiate(match direction {
@@ -1813,6 +2383,17 @@ impl UnownedWindow {
         ));
     }
 
+    #[inline]
+    pub fn set_seat_ime_cursor_area(&self, _seat: SeatId, spot: Position, size: Size) {
+        // The IME isn't tied to the seats.
+        self.set_ime_cursor_area(spot, size);
+    }
+
+    #[inline]
+    pub fn set_seat_ime_allowed(&self, _seat: SeatId, allowed: bool) {
+        self.set_ime_allowed(allowed);
+    }
+
     #[inline]
     pub fn set_ime_allowed(&self, allowed: bool) {
         let _ = self
@@ -1888,7 +2469,8 @@ impl UnownedWindow {
             let title_bytes = self
                 .xconn
                 .get_property(self.xwindow, atoms[_NET_WM_NAME], atoms[UTF8_STRING])
-                .expect("Failed to get title");
+                .expect_or_lost(&self.xconn, "Failed to get title")
+                .unwrap_or_default();
 
             String::from_utf8(title_bytes).expect("Bad title")
         };
@@ -1908,6 +2490,15 @@ impl UnownedWindow {
         Ok(serial)
     }
 
+    #[inline]
+    pub fn export_handle(&self) -> Result<AsyncRequestSerial, NotSupportedError> {
+        let serial = AsyncRequestSerial::get();
+        self.export_sender
+            .send((self.id(), serial))
+            .expect("export channel should never be closed");
+        Ok(serial)
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId(self.xwindow as _)
@@ -2007,6 +2598,19 @@ fn cast_physical_size_to_hint(size: PhysicalSize<u32>) -> (i32, i32) {
     )
 }
 
+/// Use the above strategy to cast an aspect ratio into the same minimum and maximum aspects.
+fn aspect_ratio_to_hint((width, height): (u32, u32)) -> Option<(AspectRatio, AspectRatio)> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let aspect = AspectRatio::new(
+        cast_dimension_to_hint(width),
+        cast_dimension_to_hint(height),
+    );
+    Some((aspect, aspect))
+}
+
 /// Use the above strategy to cast a size into a hinted size.
 fn cast_size_to_hint(size: Size, scale_factor: f64) -> (i32, i32) {
     match size {
@@ -2014,3 +2618,26 @@ fn cast_size_to_hint(size: Size, scale_factor: f64) -> (i32, i32) {
         Size::Logical(size) => size.to_physical::<i32>(scale_factor).into(),
     }
 }
+
+/// The atoms of the `_NET_WM_STATE` hints in `NetWmState`.
+const NET_WM_STATES: [(NetWmState, AtomName); 6] = [
+    (NetWmState::SKIP_TASKBAR, _NET_WM_STATE_SKIP_TASKBAR),
+    (NetWmState::SKIP_PAGER, _NET_WM_STATE_SKIP_PAGER),
+    (NetWmState::STICKY, _NET_WM_STATE_STICKY),
+    (NetWmState::SHADED, _NET_WM_STATE_SHADED),
+    (
+        NetWmState::DEMANDS_ATTENTION,
+        _NET_WM_STATE_DEMANDS_ATTENTION,
+    ),
+    (NetWmState::MODAL, _NET_WM_STATE_MODAL),
+];
+
+/// The `_NET_WM_DESKTOP` value of a window shown on all the desktops.
+const ALL_DESKTOPS: u32 = 0xFFFFFFFF;
+
+fn desktop_to_cardinal(desktop: Desktop) -> u32 {
+    match desktop {
+        Desktop::Index(index) => index,
+        Desktop::All => ALL_DESKTOPS,
+    }
+}
@@ -1,7 +1,10 @@
 use std::{
     collections::HashMap,
     error::Error,
-    fmt, ptr,
+    ffi::c_void,
+    fmt, mem,
+    os::raw::c_int,
+    ptr,
     sync::{
         atomic::{AtomicU32, Ordering},
         Arc, Mutex, RwLock, RwLockReadGuard,
@@ -61,6 +64,9 @@ pub struct XConnection {
     /// Atom for the XSettings screen.
     xsettings_screen: Option<xproto::Atom>,
 
+    /// Atom for the `_NET_WM_CM_Sn` selection of the compositing manager.
+    cm_selection: Option<xproto::Atom>,
+
     pub latest_error: Mutex<Option<XError>>,
     pub cursor_cache: Mutex<HashMap<Option<CursorIcon>, ffi::Cursor>>,
 }
@@ -71,6 +77,46 @@ unsafe impl Sync for XConnection {}
 pub type XErrorHandler =
     Option<unsafe extern "C" fn(*mut ffi::Display, *mut ffi::XErrorEvent) -> std::os::raw::c_int>;
 
+unsafe extern "C" fn io_error_handler(_display: *mut ffi::Display) -> c_int {
+    log::error!("Lost the connection to the X server");
+    0
+}
+
+unsafe extern "C" fn io_error_exit_handler(_display: *mut ffi::Display, _user_data: *mut c_void) {
+    // Unlike the default handler, don't exit the process, so the event loop can report the
+    // connection loss to the application.
+}
+
+type XSetIOErrorExitHandler = unsafe extern "C" fn(
+    *mut ffi::Display,
+    Option<unsafe extern "C" fn(*mut ffi::Display, *mut c_void)>,
+    *mut c_void,
+);
+
+/// Prevent Xlib from exiting the process once the connection to the X server is lost.
+///
+/// `XSetIOErrorExitHandler` is only available since libX11 1.7, thus is looked up at runtime.
+unsafe fn set_io_error_exit_handler(display: *mut ffi::Display) -> bool {
+    let library = unsafe {
+        libc::dlopen(
+            b"libX11.so.6\0".as_ptr().cast(),
+            libc::RTLD_LAZY | libc::RTLD_NOLOAD,
+        )
+    };
+    if library.is_null() {
+        return false;
+    }
+
+    let symbol = unsafe { libc::dlsym(library, b"XSetIOErrorExitHandler\0".as_ptr().cast()) };
+    if !symbol.is_null() {
+        let set_exit_handler: XSetIOErrorExitHandler = unsafe { mem::transmute(symbol) };
+        unsafe { set_exit_handler(display, Some(io_error_exit_handler), ptr::null_mut()) };
+    }
+
+    unsafe { libc::dlclose(library) };
+    !symbol.is_null()
+}
+
 impl XConnection {
     pub fn new(error_handler: XErrorHandler) -> Result<XConnection, XNotSupported> {
         // opening the libraries
@@ -81,6 +127,7 @@ impl XConnection {
 
         unsafe { (xlib.XInitThreads)() };
         unsafe { (xlib.XSetErrorHandler)(error_handler) };
+        unsafe { (xlib.XSetIOErrorHandler)(Some(io_error_handler)) };
 
         // calling XOpenDisplay
         let display = unsafe {
@@ -91,6 +138,10 @@ impl XConnection {
             display
         };
 
+        if !unsafe { set_io_error_exit_handler(display) } {
+            log::debug!("libX11 is older than 1.7, losing the connection will exit the process");
+        }
+
         // Open the x11rb XCB connection.
         let xcb = {
             // Get a pointer to the underlying XCB connection
@@ -124,6 +175,13 @@ impl XConnection {
             log::warn!("error setting XSETTINGS; Xft options won't reload automatically")
         }
 
+        // Fetch the _NET_WM_CM_S[screen number] atom.
+        let cm_selection = xcb
+            .intern_atom(false, format!("_NET_WM_CM_S{}", default_screen).as_bytes())
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.atom);
+
         // Fetch atoms.
         let atoms = Atoms::new(&xcb)
             .map_err(|e| XNotSupported::XcbConversionError(Arc::new(e)))?
@@ -145,6 +203,7 @@ impl XConnection {
             cursor_cache: Default::default(),
             randr_version: (randr_version.major_version, randr_version.minor_version),
             xsettings_screen,
+            cm_selection,
         })
     }
 
@@ -179,6 +238,12 @@ impl XConnection {
         Some(xsettings_screen)
     }
 
+    /// The `_NET_WM_CM_Sn` selection owned by the compositing manager of the default screen.
+    #[inline]
+    pub fn cm_selection(&self) -> Option<xproto::Atom> {
+        self.cm_selection
+    }
+
     /// Checks whether an error has been triggered by the previous function calls.
     #[inline]
     pub fn check_errors(&self) -> Result<(), XError> {
@@ -203,6 +268,12 @@ impl XConnection {
             .expect("xcb_connection somehow called after drop?")
     }
 
+    /// Whether the connection to the X server was lost.
+    #[inline]
+    pub fn is_connection_lost(&self) -> bool {
+        self.xcb_connection().has_error().is_some()
+    }
+
     /// Get the list of atoms.
     #[inline]
     pub fn atoms(&self) -> &Atoms {
@@ -79,6 +79,7 @@ fn maybe_dispatch_device_event(delegate: &ApplicationDelegate, event: &NSEvent)
             if delta_x != 0.0 || delta_y != 0.0 {
                 delegate.queue_device_event(DeviceEvent::MouseMotion {
                     delta: (delta_x, delta_y),
+                    timestamp: None,
                 });
             }
         }
@@ -192,6 +192,7 @@ pub(crate) fn create_key_event(
             text_with_all_modifiers,
             key_without_modifiers,
         },
+        timestamp: None,
     }
 }
 
@@ -30,8 +30,8 @@ use super::{util, DEVICE_ID};
 use crate::{
     dpi::{LogicalPosition, LogicalSize},
     event::{
-        DeviceEvent, ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta, TouchPhase,
-        WindowEvent,
+        DeviceEvent, ElementState, Ime, Modifiers, MouseButton, MouseScrollDelta,
+        MouseScrollSource, TouchPhase, WindowEvent,
     },
     keyboard::{Key, KeyCode, KeyLocation, ModifiersState, NamedKey},
     platform::macos::OptionAsAlt,
@@ -679,6 +679,8 @@ declare_class!(
                 device_id: DEVICE_ID,
                 delta,
                 phase,
+                source: MouseScrollSource::Unknown,
+                value120: None,
             });
         }
 
@@ -1024,6 +1026,7 @@ impl WinitView {
             device_id: DEVICE_ID,
             state: button_state,
             button,
+            timestamp: None,
         });
     }
 
@@ -1051,6 +1054,7 @@ impl WinitView {
         self.queue_event(WindowEvent::CursorMoved {
             device_id: DEVICE_ID,
             position: view_point.to_physical(self.scale_factor()),
+            timestamp: None,
         });
     }
 }
@@ -39,8 +39,8 @@ use crate::error::{ExternalError, NotSupportedError, OsError as RootOsError};
 use crate::event::WindowEvent;
 use crate::platform::macos::{OptionAsAlt, WindowExtMacOS};
 use crate::window::{
-    Cursor, CursorGrabMode, Icon, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowLevel,
+    Cursor, CursorGrabMode, Icon, ImePurpose, OpaqueRegion, ResizeDirection, Theme,
+    UserAttentionType, WindowAttributes, WindowButtons, WindowLevel, WindowRegion,
 };
 
 #[derive(Clone, Debug)]
@@ -828,6 +828,14 @@ impl WindowDelegate {
         }
     }
 
+    pub fn set_opacity(&self, _opacity: f32) {}
+
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+
+    pub fn set_opaque_region(&self, _region: Option<OpaqueRegion>) {}
+
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {}
+
     pub fn set_visible(&self, visible: bool) {
         match visible {
             true => self.window().makeKeyAndOrderFront(None),
@@ -1126,6 +1134,14 @@ impl WindowDelegate {
         Ok(())
     }
 
+    pub fn set_input_region(&self, _region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     pub(crate) fn is_zoomed(&self) -> bool {
         // because `isZoomed` doesn't work if the window's borderless,
         // we make it resizable temporarily.
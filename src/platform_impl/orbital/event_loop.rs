@@ -425,6 +425,7 @@ impl<T: 'static> EventLoop<T> {
                                 key_without_modifiers,
                                 text_with_all_modifiers,
                             },
+                            timestamp: None,
                         },
                         is_synthetic: false,
                     },
@@ -454,6 +455,7 @@ impl<T: 'static> EventLoop<T> {
                     event: event::WindowEvent::CursorMoved {
                         device_id: event::DeviceId(DeviceId),
                         position: (x, y).into(),
+                        timestamp: None,
                     },
                 });
             }
@@ -462,6 +464,7 @@ impl<T: 'static> EventLoop<T> {
                     device_id: event::DeviceId(DeviceId),
                     event: event::DeviceEvent::MouseMotion {
                         delta: (dx as f64, dy as f64),
+                        timestamp: None,
                     },
                 });
             }
@@ -477,6 +480,7 @@ impl<T: 'static> EventLoop<T> {
                             device_id: event::DeviceId(DeviceId),
                             state,
                             button,
+                            timestamp: None,
                         },
                     });
                 }
@@ -488,6 +492,8 @@ impl<T: 'static> EventLoop<T> {
                         device_id: event::DeviceId(DeviceId),
                         delta: event::MouseScrollDelta::LineDelta(x as f32, y as f32),
                         phase: event::TouchPhase::Moved,
+                        source: event::MouseScrollSource::Unknown,
+                        value120: None,
                     },
                 });
             }
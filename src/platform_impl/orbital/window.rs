@@ -288,6 +288,18 @@ impl Window {
     #[inline]
     pub fn set_blur(&self, _blur: bool) {}
 
+    #[inline]
+    pub fn set_opacity(&self, _opacity: f32) {}
+
+    #[inline]
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+
+    #[inline]
+    pub fn set_opaque_region(&self, _region: Option<window::OpaqueRegion>) {}
+
+    #[inline]
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {}
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let _ = self.set_flag(ORBITAL_FLAG_HIDDEN, !visible);
@@ -461,6 +473,24 @@ impl Window {
         ))
     }
 
+    pub fn set_input_region(
+        &self,
+        _region: Option<window::WindowRegion>,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
+    pub fn set_shape(
+        &self,
+        _shape: Option<window::WindowRegion>,
+    ) -> Result<(), error::ExternalError> {
+        Err(error::ExternalError::NotSupported(
+            error::NotSupportedError::new(),
+        ))
+    }
+
     #[cfg(feature = "rwh_04")]
     #[inline]
     pub fn raw_window_handle_rwh_04(&self) -> rwh_04::RawWindowHandle {
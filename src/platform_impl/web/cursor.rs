@@ -23,7 +23,9 @@ use super::backend::Style;
 use super::main_thread::{MainThreadMarker, MainThreadSafe};
 use super::r#async::{AbortHandle, Abortable, DropAbortHandle, Notified, Notifier};
 use super::ActiveEventLoop;
-use crate::cursor::{BadImage, Cursor, CursorImage, CustomCursor as RootCustomCursor};
+use crate::cursor::{
+    BadImage, Cursor, CursorImage, CustomCursor as RootCustomCursor, CustomCursorFrame,
+};
 use crate::platform::web::CustomCursorError;
 
 #[derive(Debug)]
@@ -52,6 +54,11 @@ impl CustomCursorSource {
             rgba, width, height, hotspot_x, hotspot_y,
         )?))
     }
+
+    /// Only the first frame is kept, the animations are created from [`RootCustomCursor`]s.
+    pub fn from_frames(frames: Vec<CustomCursorFrame>) -> CustomCursorSource {
+        CustomCursorSource::Image(frames.into_iter().next().unwrap().image)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -319,6 +319,7 @@ impl Shared {
                             device_id,
                             event: DeviceEvent::MouseMotion {
                                 delta: (delta.x, delta.y),
+                                timestamp: None,
                             },
                         }))
                 }));
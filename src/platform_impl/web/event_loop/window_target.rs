@@ -15,7 +15,8 @@ use super::{
     window::WindowId,
 };
 use crate::event::{
-    DeviceId as RootDeviceId, ElementState, Event, KeyEvent, Touch, TouchPhase, WindowEvent,
+    DeviceId as RootDeviceId, ElementState, Event, KeyEvent, MouseScrollSource, Touch, TouchPhase,
+    WindowEvent,
 };
 use crate::event_loop::{ControlFlow, DeviceEvents};
 use crate::keyboard::ModifiersState;
@@ -168,6 +169,7 @@ impl ActiveEventLoop {
                                 state: ElementState::Pressed,
                                 repeat,
                                 platform_specific: KeyEventExtra,
+                                timestamp: None,
                             },
                             is_synthetic: false,
                         },
@@ -204,6 +206,7 @@ impl ActiveEventLoop {
                                 state: ElementState::Released,
                                 repeat,
                                 platform_specific: KeyEventExtra,
+                                timestamp: None,
                             },
                             is_synthetic: false,
                         },
@@ -307,6 +310,7 @@ impl ActiveEventLoop {
                             event: WindowEvent::CursorMoved {
                                 device_id,
                                 position,
+                                timestamp: None,
                             },
                         })
                     })));
@@ -336,6 +340,7 @@ impl ActiveEventLoop {
                                 phase: TouchPhase::Moved,
                                 force: Some(force),
                                 location,
+                                timestamp: None,
                             }),
                         },
                     )));
@@ -377,6 +382,7 @@ impl ActiveEventLoop {
                             event: WindowEvent::CursorMoved {
                                 device_id,
                                 position,
+                                timestamp: None,
                             },
                         },
                         Event::WindowEvent {
@@ -385,6 +391,7 @@ impl ActiveEventLoop {
                                 device_id,
                                 state,
                                 button,
+                                timestamp: None,
                             },
                         },
                     ]));
@@ -431,6 +438,7 @@ impl ActiveEventLoop {
                             event: WindowEvent::CursorMoved {
                                 device_id,
                                 position,
+                                timestamp: None,
                             },
                         },
                         Event::WindowEvent {
@@ -439,6 +447,7 @@ impl ActiveEventLoop {
                                 device_id,
                                 state: ElementState::Pressed,
                                 button,
+                                timestamp: None,
                             },
                         },
                     ]));
@@ -466,6 +475,7 @@ impl ActiveEventLoop {
                                 phase: TouchPhase::Started,
                                 force: Some(force),
                                 location,
+                                timestamp: None,
                             }),
                         },
                     )))
@@ -515,6 +525,7 @@ impl ActiveEventLoop {
                             event: WindowEvent::CursorMoved {
                                 device_id,
                                 position,
+                                timestamp: None,
                             },
                         },
                         Event::WindowEvent {
@@ -523,6 +534,7 @@ impl ActiveEventLoop {
                                 device_id,
                                 state: ElementState::Released,
                                 button,
+                                timestamp: None,
                             },
                         },
                     ]));
@@ -552,6 +564,7 @@ impl ActiveEventLoop {
                                 phase: TouchPhase::Ended,
                                 force: Some(force),
                                 location,
+                                timestamp: None,
                             }),
                         },
                     )));
@@ -578,6 +591,8 @@ impl ActiveEventLoop {
                         device_id: RootDeviceId(DeviceId(pointer_id)),
                         delta,
                         phase: TouchPhase::Moved,
+                        source: MouseScrollSource::Unknown,
+                        value120: None,
                     },
                 },
             )));
@@ -593,6 +608,7 @@ impl ActiveEventLoop {
                     phase: TouchPhase::Cancelled,
                     force: Some(force),
                     location,
+                    timestamp: None,
                 }),
             });
         });
@@ -2,8 +2,8 @@ use crate::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
 use crate::error::{ExternalError, NotSupportedError, OsError as RootOE};
 use crate::icon::Icon;
 use crate::window::{
-    Cursor, CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType,
-    WindowAttributes, WindowButtons, WindowId as RootWI, WindowLevel,
+    Cursor, CursorGrabMode, ImePurpose, OpaqueRegion, ResizeDirection, Theme, UserAttentionType,
+    WindowAttributes, WindowButtons, WindowId as RootWI, WindowLevel, WindowRegion,
 };
 
 use super::main_thread::{MainThreadMarker, MainThreadSafe};
@@ -130,6 +130,14 @@ impl Inner {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_opacity(&self, _opacity: f32) {}
+
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+
+    pub fn set_opaque_region(&self, _region: Option<OpaqueRegion>) {}
+
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {}
+
     pub fn set_visible(&self, _visible: bool) {
         // Intentionally a no-op
     }
@@ -279,6 +287,14 @@ impl Inner {
         Err(ExternalError::NotSupported(NotSupportedError::new()))
     }
 
+    pub fn set_input_region(&self, _region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn set_minimized(&self, _minimized: bool) {
         // Intentionally a no-op, as canvases cannot be 'minimized'
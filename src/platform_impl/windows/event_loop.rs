@@ -71,8 +71,8 @@ use crate::{
     dpi::{PhysicalPosition, PhysicalSize},
     error::EventLoopError,
     event::{
-        DeviceEvent, Event, Force, Ime, InnerSizeWriter, RawKeyEvent, Touch, TouchPhase,
-        WindowEvent,
+        DeviceEvent, Event, Force, Ime, InnerSizeWriter, MouseScrollSource, RawKeyEvent, Touch,
+        TouchPhase, WindowEvent,
     },
     event_loop::{ActiveEventLoop as RootAEL, ControlFlow, DeviceEvents, EventLoopClosed},
     keyboard::ModifiersState,
@@ -1555,6 +1555,7 @@ unsafe fn public_window_callback_inner(
                     event: CursorMoved {
                         device_id: DEVICE_ID,
                         position,
+                        timestamp: None,
                     },
                 });
             }
@@ -1595,6 +1596,8 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     delta: LineDelta(0.0, value),
                     phase: TouchPhase::Moved,
+                    source: MouseScrollSource::Unknown,
+                    value120: None,
                 },
             });
 
@@ -1615,6 +1618,8 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     delta: LineDelta(value, 0.0),
                     phase: TouchPhase::Moved,
+                    source: MouseScrollSource::Unknown,
+                    value120: None,
                 },
             });
 
@@ -1648,6 +1653,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Pressed,
                     button: Left,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1668,6 +1674,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Released,
                     button: Left,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1688,6 +1695,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Pressed,
                     button: Right,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1708,6 +1716,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Released,
                     button: Right,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1728,6 +1737,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Pressed,
                     button: Middle,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1748,6 +1758,7 @@ unsafe fn public_window_callback_inner(
                     device_id: DEVICE_ID,
                     state: Released,
                     button: Middle,
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1774,6 +1785,7 @@ unsafe fn public_window_callback_inner(
                         2 => Forward,
                         _ => Other(xbutton),
                     },
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1800,6 +1812,7 @@ unsafe fn public_window_callback_inner(
                         2 => Forward,
                         _ => Other(xbutton),
                     },
+                    timestamp: None,
                 },
             });
             result = ProcResult::Value(0);
@@ -1858,6 +1871,7 @@ unsafe fn public_window_callback_inner(
                             force: None, // WM_TOUCH doesn't support pressure information
                             id: input.dwID as u64,
                             device_id: DEVICE_ID,
+                            timestamp: None,
                         }),
                     });
                 }
@@ -2006,6 +2020,7 @@ unsafe fn public_window_callback_inner(
                             force,
                             id: pointer_info.pointerId as u64,
                             device_id: DEVICE_ID,
+                            timestamp: None,
                         }),
                     });
                 }
@@ -2493,7 +2508,10 @@ unsafe fn handle_raw_input(userdata: &ThreadMsgTargetData, data: RAWINPUT) {
             if x != 0.0 || y != 0.0 {
                 userdata.send_event(Event::DeviceEvent {
                     device_id,
-                    event: MouseMotion { delta: (x, y) },
+                    event: MouseMotion {
+                        delta: (x, y),
+                        timestamp: None,
+                    },
                 });
             }
         }
@@ -668,6 +668,7 @@ impl PartialKeyEventInfo {
                 text_with_all_modifiers: char_with_all_modifiers,
                 key_without_modifiers: self.key_without_modifiers,
             },
+            timestamp: None,
         }
     }
 }
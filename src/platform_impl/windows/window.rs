@@ -84,8 +84,8 @@ use crate::{
         Fullscreen, SelectedCursor, WindowId,
     },
     window::{
-        CursorGrabMode, ImePurpose, ResizeDirection, Theme, UserAttentionType, WindowAttributes,
-        WindowButtons, WindowLevel,
+        CursorGrabMode, ImePurpose, OpaqueRegion, ResizeDirection, Theme, UserAttentionType,
+        WindowAttributes, WindowButtons, WindowLevel, WindowRegion,
     },
 };
 
@@ -147,6 +147,14 @@ impl Window {
 
     pub fn set_blur(&self, _blur: bool) {}
 
+    pub fn set_opacity(&self, _opacity: f32) {}
+
+    pub fn set_aspect_ratio(&self, _aspect_ratio: Option<(u32, u32)>) {}
+
+    pub fn set_opaque_region(&self, _region: Option<OpaqueRegion>) {}
+
+    pub fn set_idle_inhibited(&self, _inhibited: bool) {}
+
     #[inline]
     pub fn set_visible(&self, visible: bool) {
         let window = self.window;
@@ -651,6 +659,14 @@ impl Window {
         Ok(())
     }
 
+    pub fn set_input_region(&self, _region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
+    pub fn set_shape(&self, _shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        Err(ExternalError::NotSupported(NotSupportedError::new()))
+    }
+
     #[inline]
     pub fn id(&self) -> WindowId {
         WindowId(self.hwnd())
@@ -2,13 +2,15 @@
 use std::fmt;
 
 use crate::{
-    dpi::{PhysicalPosition, PhysicalSize, Position, Size},
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size},
     error::{ExternalError, NotSupportedError},
     monitor::{MonitorHandle, VideoModeHandle},
     platform_impl::{self, PlatformSpecificWindowAttributes},
 };
 
-pub use crate::cursor::{BadImage, Cursor, CustomCursor, CustomCursorSource, MAX_CURSOR_SIZE};
+pub use crate::cursor::{
+    BadImage, Cursor, CustomCursor, CustomCursorFrame, CustomCursorSource, MAX_CURSOR_SIZE,
+};
 pub use crate::icon::{BadIcon, Icon};
 
 #[doc(inline)]
@@ -143,14 +145,18 @@ pub struct WindowAttributes {
     pub visible: bool,
     pub transparent: bool,
     pub blur: bool,
+    pub opacity: f32,
     pub decorations: bool,
     pub window_icon: Option<Icon>,
     pub preferred_theme: Option<Theme>,
     pub resize_increments: Option<Size>,
+    pub aspect_ratio: Option<(u32, u32)>,
     pub content_protected: bool,
     pub window_level: WindowLevel,
     pub active: bool,
     pub cursor: Cursor,
+    pub owner: Option<WindowId>,
+    pub modal: bool,
     #[cfg(feature = "rwh_06")]
     pub(crate) parent_window: Option<SendSyncRawWindowHandle>,
     pub fullscreen: Option<Fullscreen>,
@@ -175,13 +181,17 @@ impl Default for WindowAttributes {
             visible: true,
             transparent: false,
             blur: false,
+            opacity: 1.,
             decorations: true,
             window_level: Default::default(),
             window_icon: None,
             preferred_theme: None,
             resize_increments: None,
+            aspect_ratio: None,
             content_protected: false,
             cursor: Cursor::default(),
+            owner: None,
+            modal: false,
             #[cfg(feature = "rwh_06")]
             parent_window: None,
             active: true,
@@ -375,6 +385,17 @@ impl WindowAttributes {
         self
     }
 
+    /// Sets the opacity of the whole window.
+    ///
+    /// The default is `1.0`.
+    ///
+    /// See [`Window::set_opacity`] for details.
+    #[inline]
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
     /// Get whether the window will support transparency.
     #[inline]
     pub fn transparent(&self) -> bool {
@@ -446,6 +467,17 @@ impl WindowAttributes {
         self
     }
 
+    /// Build window keeping the given aspect ratio, `width:height`, when resized by the user.
+    ///
+    /// The default is `None`.
+    ///
+    /// See [`Window::set_aspect_ratio`] for details.
+    #[inline]
+    pub fn with_aspect_ratio(mut self, width: u32, height: u32) -> Self {
+        self.aspect_ratio = Some((width, height));
+        self
+    }
+
     /// Prevents the window contents from being captured by other apps.
     ///
     /// The default is `false`.
@@ -490,6 +522,50 @@ impl WindowAttributes {
         self
     }
 
+    /// Build a dialog window owned by the window with the given id.
+    ///
+    /// The dialog is kept above its owner and doesn't get its own taskbar entry.
+    ///
+    /// The default is `None`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Sets `WM_TRANSIENT_FOR`, and `_NET_WM_WINDOW_TYPE_DIALOG` unless another type
+    ///   was set with [`with_x11_window_type`].
+    /// - **Wayland:** Sets the parent of the `xdg_toplevel`.
+    /// - **Windows:** Unsupported, the owner is set from its `HWND` with
+    ///   [`WindowAttributesExtWindows::with_owner_window`] instead.
+    /// - **iOS / Android / Web / macOS / Orbital:** Unsupported.
+    ///
+    #[cfg_attr(not(docsrs), allow(rustdoc::broken_intra_doc_links))]
+    /// [`with_x11_window_type`]: crate::platform::x11::WindowAttributesExtX11::with_x11_window_type
+    /// [`WindowAttributesExtWindows::with_owner_window`]: crate::platform::windows::WindowAttributesExtWindows::with_owner_window
+    #[inline]
+    pub fn with_owner(mut self, owner: WindowId) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Hint that the dialog is modal, thus that its owner shouldn't be interacted with while shown.
+    ///
+    /// The window manager or compositor may honour the hint, for instance by dimming the owner,
+    /// but winit doesn't filter the input of the owner, which is left to the application.
+    ///
+    /// Only has an effect along with [`WindowAttributes::with_owner`].
+    ///
+    /// The default is `false`.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Sets `_NET_WM_STATE_MODAL`.
+    /// - **Wayland:** Uses `xdg_dialog_v1.set_modal`, when supported by the compositor.
+    /// - **iOS / Android / Web / Windows / macOS / Orbital:** Unsupported.
+    #[inline]
+    pub fn with_modal(mut self, modal: bool) -> Self {
+        self.modal = modal;
+        self
+    }
+
     /// Build window with parent window.
     ///
     /// The default is `None`.
@@ -880,7 +956,7 @@ impl Window {
     ///
     /// ## Platform-specific
     ///
-    /// - **iOS / Android / Web / Wayland / Windows / Orbital:** Always returns [`None`].
+    /// - **iOS / Android / Web / Windows / Orbital:** Always returns [`None`].
     #[inline]
     pub fn resize_increments(&self) -> Option<PhysicalSize<u32>> {
         self.window.maybe_wait_on_main(|w| w.resize_increments())
@@ -894,7 +970,9 @@ impl Window {
     /// ## Platform-specific
     ///
     /// - **macOS:** Increments are converted to logical size and then macOS rounds them to whole numbers.
-    /// - **Wayland / Windows:** Not implemented.
+    /// - **Wayland:** The sizes picked by the compositor are rounded down to the increments, except
+    ///   when maximized, fullscreen or tiled.
+    /// - **Windows:** Not implemented.
     /// - **iOS / Android / Web / Orbital:** Unsupported.
     #[inline]
     pub fn set_resize_increments<S: Into<Size>>(&self, increments: Option<S>) {
@@ -902,6 +980,22 @@ impl Window {
         self.window
             .maybe_queue_on_main(move |w| w.set_resize_increments(increments))
     }
+
+    /// Sets the aspect ratio, as `(width, height)`, the window keeps when resized by the user.
+    ///
+    /// `None`, or a ratio with a zero term, lets the window be resized freely.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Relies on the window manager honoring the aspect of `WM_NORMAL_HINTS`.
+    /// - **Wayland:** The sizes picked by the compositor are shrunk to the ratio, except when
+    ///   maximized, fullscreen or tiled.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<(u32, u32)>) {
+        self.window
+            .maybe_queue_on_main(move |w| w.set_aspect_ratio(aspect_ratio))
+    }
 }
 
 /// Misc. attribute functions.
@@ -948,6 +1042,22 @@ impl Window {
         self.window.maybe_queue_on_main(move |w| w.set_blur(blur))
     }
 
+    /// Change the opacity of the whole window.
+    ///
+    /// The `opacity` ranges from `0.0`, fully transparent, to `1.0`, opaque. Unlike
+    /// [`Window::set_transparent`], the contents of the window don't need an alpha channel.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Only works with a compositing manager, through `_NET_WM_WINDOW_OPACITY`.
+    /// - **Wayland:** Only works with the `wp_alpha_modifier_v1` protocol.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_opacity(&self, opacity: f32) {
+        self.window
+            .maybe_queue_on_main(move |w| w.set_opacity(opacity))
+    }
+
     /// Modifies the window's visibility.
     ///
     /// If `false`, this will hide the window. If `true`, this will show the window.
@@ -1010,7 +1120,9 @@ impl Window {
     ///
     /// ## Platform-specific
     ///
-    /// - **Wayland / X11 / Orbital:** Not implemented.
+    /// - **Wayland:** Only affects the client-side decorations, where the disabled minimize and
+    ///   maximize buttons are hidden and the disabled close button is ignored.
+    /// - **X11 / Orbital:** Not implemented.
     /// - **Web / iOS / Android:** Unsupported.
     pub fn set_enabled_buttons(&self, buttons: WindowButtons) {
         self.window
@@ -1021,7 +1133,7 @@ impl Window {
     ///
     /// ## Platform-specific
     ///
-    /// - **Wayland / X11 / Orbital:** Not implemented. Always returns [`WindowButtons::all`].
+    /// - **X11 / Orbital:** Not implemented. Always returns [`WindowButtons::all`].
     /// - **Web / iOS / Android:** Unsupported. Always returns [`WindowButtons::all`].
     pub fn enabled_buttons(&self) -> WindowButtons {
         self.window.maybe_wait_on_main(|w| w.enabled_buttons())
@@ -1153,6 +1265,20 @@ impl Window {
     /// This is just a hint to the OS, and the system could ignore it.
     ///
     /// See [`WindowLevel`] for details.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **Wayland:** Unsupported, the levels other than [`WindowLevel::Normal`] only log a
+    ///   warning. This is reported by the absence of [`BackendCapabilities::WINDOW_LEVEL`].
+    ///
+    #[cfg_attr(
+        any(x11_platform, wayland_platform, docsrs),
+        doc = "[`BackendCapabilities::WINDOW_LEVEL`]: crate::platform::backend::BackendCapabilities::WINDOW_LEVEL"
+    )]
+    #[cfg_attr(
+        not(any(x11_platform, wayland_platform, docsrs)),
+        doc = "[`BackendCapabilities::WINDOW_LEVEL`]: #only-available-on-x11-and-wayland"
+    )]
     pub fn set_window_level(&self, level: WindowLevel) {
         self.window
             .maybe_queue_on_main(move |w| w.set_window_level(level))
@@ -1495,6 +1621,76 @@ impl Window {
         self.window
             .maybe_wait_on_main(|w| w.set_cursor_hittest(hittest))
     }
+
+    /// Restricts the area of the window catching the cursor events.
+    ///
+    /// Outside of the region, events are passed through the window such that any other window
+    /// behind it receives them. `None` resets the region to the whole window, which is the
+    /// default. This replaces the region set with [`Window::set_cursor_hittest`], and the other
+    /// way around.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Uses the XShape extension.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`ExternalError::NotSupported`].
+    #[inline]
+    pub fn set_input_region(&self, region: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.window
+            .maybe_wait_on_main(|w| w.set_input_region(region))
+    }
+
+    /// Sets the shape of the window, the parts of it outside of the region not being drawn.
+    ///
+    /// `None` resets the shape to the whole window, which is the default.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Uses the XShape extension. The shape applies to the window without its
+    ///   decorations, so it's best used with [`Window::set_decorations`] turned off.
+    /// - **Wayland:** Always returns an [`ExternalError::NotSupported`], use a transparent
+    ///   window instead.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Always returns an
+    ///   [`ExternalError::NotSupported`].
+    #[inline]
+    pub fn set_shape(&self, shape: Option<WindowRegion>) -> Result<(), ExternalError> {
+        self.window.maybe_wait_on_main(|w| w.set_shape(shape))
+    }
+
+    /// Sets the part of the window the compositor can assume to be fully opaque.
+    ///
+    /// This lets the compositor skip drawing what is behind this part of a transparent window.
+    /// `None` resets it to the default, the whole window being opaque unless it is transparent.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Uses `_NET_WM_OPAQUE_REGION`, only useful with a compositing manager.
+    /// - **Wayland:** Uses `wl_surface.set_opaque_region`. The region is ignored unless the window
+    ///   is transparent, see [`Window::set_transparent`], since the whole surface of an opaque
+    ///   window is already marked as opaque.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_opaque_region(&self, region: Option<OpaqueRegion>) {
+        self.window
+            .maybe_queue_on_main(move |w| w.set_opaque_region(region))
+    }
+
+    /// Prevents the screen from blanking and the screensaver from starting while the window is
+    /// shown, like during video playback.
+    ///
+    /// The inhibition is released when the window is destroyed.
+    ///
+    /// ## Platform-specific
+    ///
+    /// - **X11:** Uses the `ScreenSaverSuspend` request of the MIT-SCREEN-SAVER extension, which
+    ///   inhibits the screensaver regardless of the visibility of the window.
+    /// - **Wayland:** Only works with the `zwp_idle_inhibit_manager_v1` protocol.
+    /// - **Android / iOS / macOS / Orbital / Web / Windows:** Unsupported.
+    #[inline]
+    pub fn set_idle_inhibited(&self, inhibited: bool) {
+        self.window
+            .maybe_queue_on_main(move |w| w.set_idle_inhibited(inhibited))
+    }
 }
 
 /// Monitor info functions.
@@ -1631,8 +1827,14 @@ pub enum CursorGrabMode {
     ///
     /// ## Platform-specific
     ///
-    /// - **X11 / Windows:** Not implemented. Always returns [`ExternalError::NotSupported`] for now.
+    /// - **X11:** Emulated by confining the cursor and warping it back to the lock position, with
+    ///   the cursor hidden while locked. [`WindowEvent::CursorMoved`] isn't emitted, the motion is
+    ///   only reported by [`DeviceEvent::MouseMotion`].
+    /// - **Windows:** Not implemented. Always returns [`ExternalError::NotSupported`] for now.
     /// - **iOS / Android:** Always returns an [`ExternalError::NotSupported`].
+    ///
+    /// [`WindowEvent::CursorMoved`]: crate::event::WindowEvent::CursorMoved
+    /// [`DeviceEvent::MouseMotion`]: crate::event::DeviceEvent::MouseMotion
     Locked,
 }
 
@@ -1716,6 +1918,46 @@ bitflags::bitflags! {
     }
 }
 
+bitflags::bitflags! {
+    /// The state of the window, as applied by the user or the window manager.
+    ///
+    /// Delivered with [`WindowEvent::StateChanged`].
+    ///
+    /// [`WindowEvent::StateChanged`]: crate::event::WindowEvent::StateChanged
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct WindowState: u32 {
+        /// The window is maximized.
+        const MAXIMIZED = 1 << 0;
+        /// The window is minimized.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **Wayland:** Never set, the compositor doesn't tell whether the window is minimized.
+        const MINIMIZED = 1 << 1;
+        /// The window is fullscreen.
+        const FULLSCREEN = 1 << 2;
+        /// The left edge of the window is tiled against another window or the screen edge.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** Never set.
+        const TILED_LEFT = 1 << 3;
+        /// The right edge of the window is tiled, see [`WindowState::TILED_LEFT`].
+        const TILED_RIGHT = 1 << 4;
+        /// The top edge of the window is tiled, see [`WindowState::TILED_LEFT`].
+        const TILED_TOP = 1 << 5;
+        /// The bottom edge of the window is tiled, see [`WindowState::TILED_LEFT`].
+        const TILED_BOTTOM = 1 << 6;
+        /// The window isn't visible to the user, e.g. it's on another workspace or fully
+        /// obscured, thus rendering could be paused.
+        ///
+        /// ## Platform-specific
+        ///
+        /// - **X11:** Never set.
+        const SUSPENDED = 1 << 7;
+    }
+}
+
 /// A window level groups windows with respect to their z-position.
 ///
 /// The relative ordering between windows in different window levels is fixed.
@@ -1723,7 +1965,19 @@ bitflags::bitflags! {
 ///
 /// ## Platform-specific
 ///
-/// - **iOS / Android / Web / Wayland:** Unsupported.
+/// - **iOS / Android / Web / Wayland:** Unsupported. On Wayland, no protocol lets the regular
+///   clients keep their windows above or below the others.
+/// - **X11 / Wayland:** Whether the levels are supported is reported by
+///   [`BackendCapabilities::WINDOW_LEVEL`].
+///
+#[cfg_attr(
+    any(x11_platform, wayland_platform, docsrs),
+    doc = "[`BackendCapabilities::WINDOW_LEVEL`]: crate::platform::backend::BackendCapabilities::WINDOW_LEVEL"
+)]
+#[cfg_attr(
+    not(any(x11_platform, wayland_platform, docsrs)),
+    doc = "[`BackendCapabilities::WINDOW_LEVEL`]: #only-available-on-x11-and-wayland"
+)]
 #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
 pub enum WindowLevel {
     /// The window will always be below normal windows.
@@ -1779,3 +2033,141 @@ impl ActivationToken {
         Self { _token }
     }
 }
+
+/// An opaque handle to a [`Window`] which could be passed to another process, for windows
+/// created there to be parented to it.
+///
+/// The handle is only meaningful to the display server which issued it.
+///
+/// [`Window`]: crate::window::Window
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ForeignWindowHandle {
+    handle: String,
+}
+
+impl ForeignWindowHandle {
+    /// Create the handle from its string representation received from another process.
+    pub fn from_raw(handle: String) -> Self {
+        Self { handle }
+    }
+
+    /// The string representation of the handle, to be sent to another process.
+    pub fn as_raw(&self) -> &str {
+        &self.handle
+    }
+
+    /// Convert the handle into its string representation.
+    pub fn into_raw(self) -> String {
+        self.handle
+    }
+}
+
+/// An area of a window made of rectangles, relative to the top-left corner of its surface.
+///
+/// An empty region covers no part of the window.
+///
+/// See [`Window::set_input_region`] and [`Window::set_shape`].
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct WindowRegion {
+    rects: Vec<(Position, Size)>,
+}
+
+impl WindowRegion {
+    /// Create an empty region.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a rectangle to the region.
+    pub fn add_rect<P: Into<Position>, S: Into<Size>>(&mut self, position: P, size: S) {
+        self.rects.push((position.into(), size.into()));
+    }
+
+    /// Add a rectangle to the region.
+    #[inline]
+    pub fn with_rect<P: Into<Position>, S: Into<Size>>(mut self, position: P, size: S) -> Self {
+        self.add_rect(position, size);
+        self
+    }
+
+    /// The rectangles making up the region.
+    pub fn rects(&self) -> &[(Position, Size)] {
+        &self.rects
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn to_physical(
+        &self,
+        scale_factor: f64,
+    ) -> impl Iterator<Item = (PhysicalPosition<i32>, PhysicalSize<u32>)> + '_ {
+        self.rects.iter().map(move |(position, size)| {
+            (
+                position.to_physical(scale_factor),
+                size.to_physical(scale_factor),
+            )
+        })
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn to_logical(
+        &self,
+        scale_factor: f64,
+    ) -> impl Iterator<Item = (LogicalPosition<i32>, LogicalSize<i32>)> + '_ {
+        self.rects.iter().map(move |(position, size)| {
+            (
+                position.to_logical(scale_factor),
+                size.to_logical(scale_factor),
+            )
+        })
+    }
+}
+
+/// The part of a window the compositor can assume to be fully opaque.
+///
+/// See [`Window::set_opaque_region`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum OpaqueRegion {
+    /// The given region.
+    Region(WindowRegion),
+    /// The whole window except a translucent border and rounded corners, kept up to date when
+    /// the window is resized.
+    Inset {
+        /// The width of the translucent border around the window, in logical pixels.
+        inset: f64,
+        /// The radius of the translucent rounded corners, in logical pixels.
+        corner_radius: f64,
+    },
+}
+
+impl OpaqueRegion {
+    /// The opaque region of a window with the given inner size.
+    #[allow(dead_code)]
+    pub(crate) fn to_region(&self, size: LogicalSize<f64>) -> WindowRegion {
+        let (inset, corner_radius) = match self {
+            Self::Region(region) => return region.clone(),
+            Self::Inset {
+                inset,
+                corner_radius,
+            } => (inset.max(0.), corner_radius.max(0.)),
+        };
+
+        let width = size.width - 2. * inset;
+        let height = size.height - 2. * inset;
+
+        // Leave the corners out with two overlapping rectangles.
+        let mut region = WindowRegion::new();
+        for (x, y, width, height) in [
+            (corner_radius, 0., width - 2. * corner_radius, height),
+            (0., corner_radius, width, height - 2. * corner_radius),
+        ] {
+            if width > 0. && height > 0. {
+                region.add_rect(
+                    LogicalPosition::new(inset + x, inset + y),
+                    LogicalSize::new(width, height),
+                );
+            }
+        }
+        region
+    }
+}